@@ -1,23 +1,129 @@
 use self::{binding::*, class::*, enumeration::*, func::*};
 use crate::Opt;
 use cs_bindgen_shared::{
-    schematic::{self, Primitive, Schema, TypeName},
-    BindingStyle, Export, NamedType, Repr,
+    schematic::{self, Field, Primitive, Schema, TypeName},
+    BindingStyle, Export, NamedType, Repr, Trait,
 };
 use heck::*;
 use lazy_static::lazy_static;
 use proc_macro2::TokenStream;
 use quote::*;
-use std::{collections::HashMap, ffi::OsStr};
+use std::{collections::HashMap, ffi::OsStr, slice};
 use syn::Ident;
 
+mod array;
 mod binding;
+mod callback;
 mod class;
+mod docs;
 mod enumeration;
 mod func;
+mod interface;
+mod iter;
+mod map;
+mod option;
+mod result;
 mod strukt;
+mod task;
+mod tuple;
+
+/// The exported named types, along with the namespace configuration used when
+/// generating references to them.
+pub struct TypeMap<'a> {
+    types: HashMap<&'a TypeName, &'a NamedType>,
+    traits: HashMap<&'a TypeName, &'a Trait>,
+
+    /// The root namespace for the generated bindings, split into its components. Empty
+    /// if the bindings are generated in the global namespace.
+    root: Vec<Ident>,
+
+    /// Whether each exported type is generated in a namespace based on the module that
+    /// it's declared in.
+    module_namespaces: bool,
+}
+
+impl<'a> TypeMap<'a> {
+    pub fn get(&self, type_name: &TypeName) -> Option<&'a NamedType> {
+        self.types.get(type_name).copied()
+    }
+
+    /// Returns the exported trait with the specified name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `type_name` doesn't refer to an exported trait.
+    pub fn get_trait(&self, type_name: &TypeName) -> &'a Trait {
+        self.traits
+            .get(type_name)
+            .copied()
+            .unwrap_or_else(|| panic!("No export found for trait {:?}", type_name))
+    }
+
+    /// Returns the identifier of the C# type generated for the specified exported type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `type_name` doesn't refer to an exported type.
+    pub fn type_ident(&self, type_name: &TypeName) -> Ident {
+        self.get(type_name)
+            .unwrap_or_else(|| panic!("No export found for type name {:?}", type_name))
+            .ident()
+    }
+
+    /// Generates a fully-qualified reference to `ident` within the root namespace.
+    ///
+    /// Built-in types and the raw types used for marshaling are always declared in the
+    /// root namespace.
+    pub fn root_path<T: ToTokens>(&self, ident: T) -> TokenStream {
+        let root = &self.root;
+        quote! { global::#( #root. )* #ident }
+    }
+
+    /// Generates a fully-qualified reference to `ident` within the namespace of the
+    /// specified exported type, e.g. to reference the type itself or a nested type.
+    pub fn type_path<T: ToTokens>(&self, type_name: &TypeName, ident: T) -> TokenStream {
+        let root = &self.root;
+        let module = self.module_namespace(type_name);
+        quote! { global::#( #root. )* #( #module. )* #ident }
+    }
+
+    /// Wraps the generated declarations for an exported type in the namespace
+    /// corresponding to its module, if module namespaces are enabled.
+    ///
+    /// Declarations are already generated within the root namespace, so the namespace
+    /// is relative to the root.
+    pub fn in_type_namespace(&self, type_name: &TypeName, tokens: TokenStream) -> TokenStream {
+        let module = self.module_namespace(type_name);
+        if module.is_empty() {
+            tokens
+        } else {
+            quote! {
+                namespace #( #module ).*
+                {
+                    #tokens
+                }
+            }
+        }
+    }
+
+    /// Returns the namespace components for the module that the specified type is
+    /// declared in, relative to the root namespace.
+    ///
+    /// The crate name is omitted, so types declared at the root of the crate are
+    /// generated directly in the root namespace.
+    fn module_namespace(&self, type_name: &TypeName) -> Vec<Ident> {
+        if !self.module_namespaces {
+            return Vec::new();
+        }
 
-type TypeMap<'a> = HashMap<&'a TypeName, &'a NamedType>;
+        type_name
+            .module
+            .split("::")
+            .skip(1)
+            .map(|module| format_ident!("{}", module.to_camel_case()))
+            .collect()
+    }
+}
 
 lazy_static! {
     static ref STRING_SCHEMA: Schema = schematic::describe::<String>();
@@ -38,13 +144,67 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
 
     // Gather the definitions for all user-defined types so that the full export
     // information can be retrieved when an export represents another exported type.
-    let types = exports
-        .iter()
-        .filter_map(|export| match export {
-            Export::Named(export) => Some((&export.type_name, export)),
-            _ => None,
-        })
-        .collect::<HashMap<_, _>>();
+    let types = TypeMap {
+        types: exports
+            .iter()
+            .filter_map(|export| match export {
+                Export::Named(export) => Some((&export.type_name, export)),
+                _ => None,
+            })
+            .collect(),
+        traits: exports
+            .iter()
+            .filter_map(|export| match export {
+                Export::Trait(export) => Some((&export.type_name, export)),
+                _ => None,
+            })
+            .collect(),
+        root: opt
+            .namespace
+            .iter()
+            .flat_map(|namespace| namespace.split('.'))
+            .map(|name| format_ident!("{}", name))
+            .collect(),
+        module_namespaces: opt.module_namespaces,
+    };
+
+    // Verify that optional types aren't nested. C# has no equivalent of
+    // `Option<Option<T>>`, since `None` and `Some(None)` would both be `null`.
+    for export in &exports {
+        let mut nested_option = false;
+        visit_reprs(slice::from_ref(export), |repr| {
+            if let Repr::Option(inner) = repr {
+                nested_option |= matches!(**inner, Repr::Option(_));
+            }
+        });
+
+        if nested_option {
+            return Err(failure::format_err!(
+                "{} uses a nested `Option`, which isn't supported since `None` and `Some(None)` \
+                 can't be distinguished in C#",
+                describe_export(export),
+            ));
+        }
+    }
+
+    // Verify that function pointers aren't used. A C# delegate can only be passed as a
+    // plain function pointer by marshaling the delegate itself, which doesn't work under
+    // IL2CPP, and C# can't tell when Rust is done with the function pointer.
+    for export in &exports {
+        let mut fn_ptr = false;
+        visit_reprs(slice::from_ref(export), |repr| {
+            fn_ptr |= matches!(repr, Repr::FnPtr { .. });
+        });
+
+        if fn_ptr {
+            return Err(failure::format_err!(
+                "{} uses an `extern \"C\" fn`, which isn't supported since C# can't pass a \
+                 delegate as a plain function pointer, use a boxed callback such as \
+                 `Box<dyn Fn(..)>` instead",
+                describe_export(export),
+            ));
+        }
+    }
 
     // Generate the raw bindings for all exported items.
     let raw_bindings = exports
@@ -52,21 +212,338 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
         .map(|item| quote_raw_binding(item, dll_name, &types))
         .collect::<Vec<_>>();
 
+    // Verify that arrays aren't empty. `[T; 0]` is zero-sized in Rust, but C# has no
+    // zero-sized structs, so a struct containing an empty array would have a different
+    // layout in C#.
+    for export in &exports {
+        let mut empty_array = false;
+        visit_reprs(slice::from_ref(export), |repr| {
+            empty_array |= matches!(repr, Repr::Array { len: 0, .. });
+        });
+
+        if empty_array {
+            return Err(failure::format_err!(
+                "{} uses an array with no elements, which isn't supported since it has no \
+                 equivalent layout in C#",
+                describe_export(export),
+            ));
+        }
+    }
+
+    // Verify that `constructor` is only used for static methods that return the self
+    // type, and only for handle types. Value types are constructed from their fields,
+    // so their static methods are never generated as constructors.
+    for export in &exports {
+        let export = match export {
+            Export::Method(export) if export.constructor == Some(true) => export,
+            _ => continue,
+        };
+
+        let is_handle = types
+            .get(&export.self_type)
+            .map(|self_type| self_type.binding_style == BindingStyle::Handle)
+            .unwrap_or(false);
+        if !is_handle {
+            return Err(failure::format_err!(
+                "`constructor` was specified for {}::{}, but constructors are only supported \
+                 for types exported as handles",
+                export.self_type.name,
+                export.name,
+            ));
+        }
+
+        let returns_self = export.output == Some(Repr::Named(export.self_type.clone()));
+        if export.receiver.is_some() || !returns_self {
+            return Err(failure::format_err!(
+                "`constructor` was specified for {}::{}, but it isn't a static method \
+                 returning `Self`",
+                export.self_type.name,
+                export.name,
+            ));
+        }
+    }
+
+    // Verify that references are only used for handle types, and only directly as the
+    // type of an argument or as the return type. A borrowed handle returned to C# is
+    // only valid while the handles passed to the call are, which can't be tracked for a
+    // reference nested in another type.
+    for export in &exports {
+        let (inputs, output) = match export {
+            Export::Fn(export) => (&export.inputs[..], export.output.as_ref()),
+            Export::Method(export) => (&export.inputs[..], export.output.as_ref()),
+            Export::Named(_) | Export::Trait(_) => (&[][..], None),
+        };
+
+        let mut refs = 0;
+        let mut non_handle_ref = false;
+        visit_reprs(slice::from_ref(export), |repr| {
+            if let Repr::Ref(_) = repr {
+                refs += 1;
+                non_handle_ref |= !func::is_handle_ref(repr, &types);
+            }
+        });
+
+        let direct_refs = inputs
+            .iter()
+            .map(|arg| &arg.repr)
+            .chain(output)
+            .filter(|repr| matches!(repr, Repr::Ref(_)))
+            .count();
+
+        if non_handle_ref {
+            return Err(failure::format_err!(
+                "{} uses a reference to a type that isn't exported as a handle, references are \
+                 only supported for handle types",
+                describe_export(export),
+            ));
+        }
+
+        if refs != direct_refs {
+            return Err(failure::format_err!(
+                "{} uses a reference in an unsupported position, references can only be used \
+                 directly as the type of an argument or as the return type of a function",
+                describe_export(export),
+            ));
+        }
+    }
+
+    // Verify that slices passed from C# to Rust are passed directly as an argument and
+    // have a primitive numeric element type. These arguments are passed as pinned spans,
+    // and other slices would need their elements converted, in which case the converted
+    // elements couldn't be borrowed by Rust.
+    for export in &exports {
+        let inputs = match export {
+            Export::Fn(export) => &export.inputs,
+            Export::Method(export) => &export.inputs,
+            Export::Named(_) | Export::Trait(_) => continue,
+        };
+
+        let unsupported_slice = inputs.iter().any(|arg| {
+            let mut slices = 0;
+            visit_repr(&arg.repr, &mut |repr| {
+                if let Repr::Slice(_) | Repr::SliceMut(_) = repr {
+                    slices += 1;
+                }
+            });
+
+            let direct_span = func::span_element(&arg.repr).is_some() as usize;
+            slices != direct_span
+        });
+
+        if unsupported_slice {
+            return Err(failure::format_err!(
+                "{} takes an unsupported slice, slices can only be passed to Rust directly as \
+                 the type of an argument and must have a primitive numeric element type",
+                describe_export(export),
+            ));
+        }
+    }
+
+    // Verify that `try_pattern` is only used for functions that return a `Result`.
+    for export in &exports {
+        let (name, output, try_pattern) = match export {
+            Export::Fn(export) => (&export.name, &export.output, export.try_pattern),
+            Export::Method(export) => (&export.name, &export.output, export.try_pattern),
+            Export::Named(_) | Export::Trait(_) => continue,
+        };
+
+        if try_pattern && !matches!(output, Some(Repr::Result { .. })) {
+            return Err(failure::format_err!(
+                "`try_pattern` was specified for {}, but it does not return a `Result`",
+                name,
+            ));
+        }
+    }
+
+    // Verify that `async` methods with a receiver are only exported for handle types. The
+    // future borrows the receiver for as long as it runs, which is only possible for a
+    // handle. Static `async` methods don't have this restriction.
+    for export in &exports {
+        if let Export::Method(export) = export {
+            let is_handle = types
+                .get(&export.self_type)
+                .map(|self_type| self_type.binding_style == BindingStyle::Handle)
+                .unwrap_or(false);
+
+            if export.is_async && export.receiver.is_some() && !is_handle {
+                return Err(failure::format_err!(
+                    "`async` method {}::{} takes `self`, which is only supported for types \
+                     exported as handles",
+                    export.self_type.name,
+                    export.name,
+                ));
+            }
+        }
+    }
+
+    // Verify that callbacks and trait objects are only used as arguments, since they can
+    // only be passed from C# to Rust. The methods of an exported trait pass their
+    // arguments from Rust to C#, so they can't use callbacks at all.
+    for export in &exports {
+        let (name, inputs, output) = match export {
+            Export::Fn(export) => (&export.name, &export.inputs, &export.output),
+            Export::Method(export) => (&export.name, &export.inputs, &export.output),
+            Export::Named(_) => continue,
+
+            Export::Trait(export) => {
+                for method in &export.methods {
+                    let uses_callback = method
+                        .inputs
+                        .iter()
+                        .map(|arg| &arg.repr)
+                        .chain(&method.output)
+                        .any(callback::contains_callback);
+
+                    if uses_callback {
+                        return Err(failure::format_err!(
+                            "{}::{} uses a callback or trait object, which isn't supported for \
+                             the methods of an exported trait",
+                            export.type_name.name,
+                            method.name,
+                        ));
+                    }
+                }
+
+                continue;
+            }
+        };
+
+        let nested_callback = inputs.iter().any(|arg| match &arg.repr {
+            Repr::Callback { inputs, output } | Repr::FnPtr { inputs, output } => {
+                inputs.iter().any(callback::contains_callback)
+                    || callback::contains_callback(output)
+            }
+            Repr::TraitObject(_) => false,
+            repr => callback::contains_callback(repr),
+        });
+        let returns_callback = output
+            .as_ref()
+            .map(callback::contains_callback)
+            .unwrap_or(false);
+
+        if nested_callback || returns_callback {
+            return Err(failure::format_err!(
+                "{} uses a callback or trait object in an unsupported position, callbacks \
+                 and trait objects can only be used directly as the type of an argument",
+                name,
+            ));
+        }
+    }
+
+    // Verify that iterators are only used as the return type of a function, since the
+    // handle types for iterators are only generated for return types.
+    for export in &exports {
+        let (name, inputs, output) = match export {
+            Export::Fn(export) => (&export.name, &export.inputs, &export.output),
+            Export::Method(export) => (&export.name, &export.inputs, &export.output),
+            Export::Named(_) | Export::Trait(_) => continue,
+        };
+
+        let nested_iterator = match output {
+            Some(Repr::Iterator { item, .. }) => iter::contains_iterator(item),
+            Some(output) => iter::contains_iterator(output),
+            None => false,
+        };
+        let iterator_arg = inputs.iter().any(|arg| iter::contains_iterator(&arg.repr));
+
+        if nested_iterator || iterator_arg {
+            return Err(failure::format_err!(
+                "{} uses an iterator in an unsupported position, iterators can only be used \
+                 directly as the return type of a function",
+                name,
+            ));
+        }
+    }
+
+    // Property accessors are collected so that getters and setters can be merged into
+    // a single property.
+    let accessors = exports
+        .iter()
+        .filter_map(|export| match export {
+            Export::Method(export) if export.accessor.is_some() => Some(export),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let properties = class::collect_properties(&accessors, &types)?;
+
+    // Verify that trait impls are only exported for handle types, and that they export
+    // every method of the trait so that the generated class implements the whole
+    // interface.
+    for (self_type, trait_name) in interface::trait_impls(&exports) {
+        let is_handle = types
+            .get(self_type)
+            .map(|export| export.binding_style == BindingStyle::Handle)
+            .unwrap_or(false);
+        if !is_handle {
+            return Err(failure::format_err!(
+                "`{}` implements `{}`, but trait impls are only supported for types exported \
+                 as handles",
+                self_type.name,
+                trait_name.name,
+            ));
+        }
+
+        for method in &types.get_trait(trait_name).methods {
+            let is_exported = exports.iter().any(|export| match export {
+                Export::Method(export) => {
+                    export.self_type == *self_type
+                        && export.trait_name.as_ref() == Some(trait_name)
+                        && export.name == method.name
+                }
+                _ => false,
+            });
+
+            if !is_exported {
+                return Err(failure::format_err!(
+                    "The impl of `{}` for `{}` doesn't export `{}`, methods that use the default \
+                     implementation must also be declared in the `#[cs_bindgen]` impl",
+                    trait_name.name,
+                    self_type.name,
+                    method.name,
+                ));
+            }
+        }
+    }
+
+    check_collisions(&exports, &properties, &class_name, &types)?;
+
     let mut fn_bindings = Vec::new();
     let mut binding_items = Vec::new();
     for export in &exports {
         match export {
-            Export::Fn(export) => fn_bindings.push(quote_wrapper_fn(
-                &*export.name,
-                &*export.binding,
-                None,
-                &export.inputs,
-                export.output.as_ref(),
-                &types,
-            )),
+            Export::Fn(export) => {
+                let docs = docs::quote_func_docs(export);
+                let name = func::fn_name(&export.name, export.rename.as_deref());
+                let wrapper_fn = if export.is_async {
+                    task::quote_async_wrapper_fn(
+                        &name,
+                        &export.binding,
+                        None,
+                        &export.inputs,
+                        export.output.as_ref(),
+                        &types,
+                    )
+                } else {
+                    quote_wrapper_fn(
+                        &name,
+                        &*export.binding,
+                        None,
+                        &export.inputs,
+                        export.output.as_ref(),
+                        export.try_pattern,
+                        &types,
+                    )
+                };
+                fn_bindings.push(quote! {
+                    #docs
+                    #wrapper_fn
+                });
+            }
 
             Export::Named(export) => match &export.binding_style {
-                BindingStyle::Handle => binding_items.push(class::quote_handle_type(export)),
+                BindingStyle::Handle => {
+                    binding_items.push(class::quote_handle_type(export, &types))
+                }
 
                 BindingStyle::Value(schema) => match schema {
                     Schema::Struct(_)
@@ -93,15 +570,51 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
                 },
             },
 
+            // Property accessors are generated with their properties below.
+            Export::Method(export) if export.accessor.is_some() => {}
+
             Export::Method(export) => binding_items.push(quote_method_binding(export, &types)),
+
+            Export::Trait(export) => binding_items.push(interface::quote_interface(export, &types)),
         }
     }
+    binding_items.push(class::quote_properties(&properties, &types));
+    binding_items.push(interface::quote_trait_impls(&exports, &types));
+
+    // Generate the raw structs and conversions for any optional types used in the
+    // exported items.
+    let option_bindings = option::quote_option_bindings(&exports, &types);
+
+    // Generate the raw structs, conversions, and exception types for any fallible
+    // functions.
+    let result_bindings = result::quote_result_bindings(&exports, &types);
+
+    // Generate the raw structs and conversions for any tuple types used in the
+    // exported items.
+    let tuple_bindings = tuple::quote_tuple_bindings(&exports, &types);
+
+    // Generate the conversions for any map types used in the exported items.
+    let map_bindings = map::quote_map_bindings(&exports, &types);
+
+    // Generate the trampolines and conversions for any callbacks passed in from C#.
+    let callback_bindings = callback::quote_callback_bindings(&exports, &types);
+
+    // Generate the completion trampolines for any `async` functions.
+    let task_bindings = task::quote_task_bindings(&exports, &types);
+
+    // Generate the enumerator classes for any iterators returned from Rust.
+    let iterator_bindings = iter::quote_iterator_bindings(&exports, &types, dll_name);
+
+    // Generate the raw structs and conversions for any fixed-size array types used in
+    // the exported items.
+    let array_bindings = array::quote_array_bindings(&exports, &types);
 
     // Wrap the raw bindings for exported functions/methods in the bindings class definition.
     let raw_bindings = binding::wrap_bindings(quote! {
         #( #raw_bindings )*
     });
 
+    let check_panic = binding::check_panic_fn_ident();
     let built_in_bindings = binding::wrap_bindings(quote! {
         // Bindings to built-in helper functions.
         [DllImport(
@@ -244,12 +757,82 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
             CallingConvention = CallingConvention.Cdecl)]
         internal static extern RawVec __cs_bindgen_convert_vec_char(RawSlice raw);
 
+        [DllImport(
+            #dll_name,
+            CallingConvention = CallingConvention.Cdecl)]
+        internal static extern void __cs_bindgen_drop_vec_string(RawVec raw);
+
+        [DllImport(
+            #dll_name,
+            CallingConvention = CallingConvention.Cdecl)]
+        internal static extern RawVec __cs_bindgen_convert_vec_string(RawSlice raw);
+
+        [DllImport(
+            #dll_name,
+            CallingConvention = CallingConvention.Cdecl)]
+        internal static extern RawSlice __cs_bindgen_index_vec_string(RawSlice raw, UIntPtr index);
+
         [DllImport(
             #dll_name,
             EntryPoint = "__cs_bindgen_string_from_utf16",
             CallingConvention = CallingConvention.Cdecl)]
         internal static extern RawVec __cs_bindgen_string_from_utf16(RawSlice raw);
 
+        [DllImport(
+            #dll_name,
+            EntryPoint = "__cs_bindgen_take_panic",
+            CallingConvention = CallingConvention.Cdecl)]
+        internal static extern RawPanic __cs_bindgen_take_panic();
+
+        // Checks if the most recent call into Rust panicked, and throws an exception with
+        // the panic message if it did. Also rethrows any exception thrown by a callback
+        // during the call, which takes precedence since it's the likely cause of a panic.
+        internal static void #check_panic()
+        {
+            var panic = __TakePanic();
+
+            var callbackException = __callbackException;
+            if (callbackException != null)
+            {
+                __callbackException = null;
+                callbackException.Throw();
+            }
+
+            if (panic != null)
+            {
+                throw panic;
+            }
+        }
+
+        // Takes the panic from the most recent call into Rust on the current thread, if
+        // any, without throwing it.
+        internal static RustPanicException __TakePanic()
+        {
+            var raw = __cs_bindgen_take_panic();
+            if (raw.IsSome == 0)
+            {
+                return null;
+            }
+
+            __FromRaw(raw.Message, out string message);
+            __FromRaw(raw.Location, out string location);
+            return new RustPanicException(message, location.Length > 0 ? location : null);
+        }
+
+        // The first exception thrown by a callback invoked from Rust on the current thread,
+        // which is rethrown by `__CheckPanic` once the call into Rust returns. Exceptions
+        // can't unwind through Rust code, so callbacks catch them instead.
+        [ThreadStatic]
+        private static ExceptionDispatchInfo __callbackException;
+
+        internal static void __SetCallbackException(Exception exception)
+        {
+            if (__callbackException == null)
+            {
+                __callbackException = ExceptionDispatchInfo.Capture(exception);
+            }
+        }
+
         // Overloads of `__FromRaw` for primitives and built-in types.
         internal static void __FromRaw(byte raw, out byte result) { result = raw; }
         internal static void __FromRaw(sbyte raw, out sbyte result) { result = raw; }
@@ -267,12 +850,89 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
             result = raw != 0;
         }
 
+        internal static void __FromRaw(uint raw, out RustChar result)
+        {
+            result = new RustChar(raw);
+        }
+
         internal static void __FromRaw(RawVec raw, out string result)
         {
             result = Encoding.UTF8.GetString((byte*)raw.Ptr, (int)raw.Length);
             __bindings.__cs_bindgen_drop_vec_u8(raw);
         }
 
+        // Overloads of `__FromRaw` for borrowed strings and slices. The data is owned by
+        // the Rust side, so it's copied out without being freed.
+        internal static void __FromRaw(RawSlice raw, out string result)
+        {
+            result = Encoding.UTF8.GetString((byte*)raw.Ptr, (int)raw.Length);
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<byte> result)
+        {
+            result = raw.ToPrimitiveList<byte>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<sbyte> result)
+        {
+            result = raw.ToPrimitiveList<sbyte>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<short> result)
+        {
+            result = raw.ToPrimitiveList<short>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<ushort> result)
+        {
+            result = raw.ToPrimitiveList<ushort>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<int> result)
+        {
+            result = raw.ToPrimitiveList<int>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<uint> result)
+        {
+            result = raw.ToPrimitiveList<uint>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<long> result)
+        {
+            result = raw.ToPrimitiveList<long>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<ulong> result)
+        {
+            result = raw.ToPrimitiveList<ulong>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<float> result)
+        {
+            result = raw.ToPrimitiveList<float>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<double> result)
+        {
+            result = raw.ToPrimitiveList<double>();
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<bool> result)
+        {
+            result = raw.ToPrimitiveList<byte, bool>(rawElem => rawElem != 0);
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<RustChar> result)
+        {
+            result = raw.ToPrimitiveList<uint, RustChar>(rawElem => new RustChar(rawElem));
+        }
+
+        internal static void __FromRaw(RawSlice raw, out List<string> result)
+        {
+            result = raw.ToList<RawSlice, string>(__cs_bindgen_index_vec_string, __FromRaw);
+        }
+
         internal static void __FromRaw(RawVec raw, out List<byte> result)
         {
             result = raw.ToPrimitiveList<byte>();
@@ -339,6 +999,19 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
             __bindings.__cs_bindgen_drop_vec_u8(raw);
         }
 
+        internal static void __FromRaw(RawVec raw, out List<RustChar> result)
+        {
+            result = raw.ToPrimitiveList<uint, RustChar>(rawElem => new RustChar(rawElem));
+            __bindings.__cs_bindgen_drop_vec_char(raw);
+        }
+
+        // The elements are borrowed from the vec, so dropping the vec frees them.
+        internal static void __FromRaw(RawVec raw, out List<string> result)
+        {
+            result = raw.ToList<RawSlice, string>(__cs_bindgen_index_vec_string, __FromRaw);
+            __bindings.__cs_bindgen_drop_vec_string(raw);
+        }
+
         // Overloads of `__IntoRaw` for primitives and built-in types.
         internal static void __IntoRaw(byte value, out byte result) { result = value; }
         internal static void __IntoRaw(sbyte value, out sbyte result) { result = value; }
@@ -356,6 +1029,11 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
             result = value ? (byte)1 : (byte)0;
         }
 
+        internal static void __IntoRaw(RustChar value, out uint result)
+        {
+            result = value.Value;
+        }
+
         internal static void __IntoRaw(string value, out RawVec result)
         {
             fixed (char* charPtr = value)
@@ -421,14 +1099,28 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
                 item => item ? (byte)1 : (byte)0,
                 __cs_bindgen_convert_vec_bool);
         }
+
+        internal static void __IntoRaw(List<RustChar> value, out RawVec result)
+        {
+            result = RawVec.FromList<RustChar, uint>(
+                value,
+                item => item.Value,
+                __cs_bindgen_convert_vec_char);
+        }
+
+        internal static void __IntoRaw(List<string> value, out RawVec result)
+        {
+            result = RawVec.FromList(
+                value,
+                item => {
+                    __IntoRaw(item, out RawVec raw);
+                    return raw;
+                },
+                __cs_bindgen_convert_vec_string);
+        }
     });
 
     let generated = quote! {
-        using System;
-        using System.Collections.Generic;
-        using System.Runtime.InteropServices;
-        using System.Text;
-
         #built_in_bindings
         #raw_bindings
 
@@ -439,39 +1131,135 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
 
         #( #binding_items )*
 
+        #option_bindings
+        #result_bindings
+        #tuple_bindings
+        #map_bindings
+        #array_bindings
+        #callback_bindings
+        #task_bindings
+        #iterator_bindings
+
         internal delegate void FromRaw<R, T>(R raw, out T result);
 
-        [StructLayout(LayoutKind.Sequential)]
-        internal unsafe struct RawVec
+        // Exception thrown when Rust code panics. The panic is caught at the FFI boundary
+        // and rethrown as an exception, since unwinding across the FFI boundary is
+        // undefined behavior.
+        public class RustPanicException : Exception
         {
-            public IntPtr Ptr;
-            public UIntPtr Length;
-            public UIntPtr Capacity;
+            // The source location of the panic, e.g. `src/lib.rs:12:5`. May be `null` if
+            // the location of the panic isn't known.
+            public readonly string Location;
 
-            public List<T> ToPrimitiveList<T>() where T: unmanaged
+            public RustPanicException(string message, string location) : base(message)
             {
-                var result = new List<T>((int)Length);
-                var orig = (T*)Ptr;
+                Location = location;
+            }
+        }
 
-                for (int index = 0; index < (int)Length; index += 1)
+        // A Unicode scalar value, equivalent to Rust's `char`.
+        //
+        // C#'s `char` is a UTF-16 code unit, which can't represent characters outside of
+        // the Basic Multilingual Plane, so Rust's `char` is exposed as a `RustChar`
+        // instead. A `RustChar` can only be constructed from a valid scalar value, so it
+        // can always be passed to Rust safely.
+        public readonly struct RustChar : IEquatable<RustChar>
+        {
+            // The Unicode scalar value of the character.
+            public readonly uint Value;
+
+            public RustChar(uint value)
+            {
+                if (!IsValid(value))
                 {
-                    result.Add(orig[index]);
+                    throw new ArgumentOutOfRangeException(
+                        nameof(value),
+                        value,
+                        "Value is not a valid Unicode scalar value");
                 }
 
-                return result;
+                Value = value;
             }
 
-            public List<T> ToPrimitiveList<D, T>(Func<D, T> conversion) where D: unmanaged
+            // Creates a `RustChar` from a UTF-16 code unit. Throws if `value` is a surrogate,
+            // use `FromString` to convert a surrogate pair.
+            public RustChar(char value) : this((uint)value) { }
+
+            // Returns the `RustChar` at `index` in `value`, combining surrogate pairs into a
+            // single character.
+            public static RustChar FromString(string value, int index)
             {
-                var result = new List<T>((int)Length);
-                var orig = (D*)Ptr;
+                return new RustChar((uint)char.ConvertToUtf32(value, index));
+            }
 
-                for (int index = 0; index < (int)Length; index += 1)
-                {
-                    result.Add(conversion(orig[index]));
-                }
+            // Returns `true` if `value` is a valid Unicode scalar value, i.e. it is not a
+            // surrogate code point and is no larger than `0x10FFFF`.
+            public static bool IsValid(uint value)
+            {
+                return value < 0xD800 || (value > 0xDFFF && value <= 0x10FFFF);
+            }
 
-                return result;
+            public static implicit operator RustChar(char value)
+            {
+                return new RustChar(value);
+            }
+
+            public static bool operator ==(RustChar left, RustChar right)
+            {
+                return left.Value == right.Value;
+            }
+
+            public static bool operator !=(RustChar left, RustChar right)
+            {
+                return left.Value != right.Value;
+            }
+
+            public bool Equals(RustChar other)
+            {
+                return Value == other.Value;
+            }
+
+            public override bool Equals(object obj)
+            {
+                return obj is RustChar other && Equals(other);
+            }
+
+            public override int GetHashCode()
+            {
+                return Value.GetHashCode();
+            }
+
+            public override string ToString()
+            {
+                return char.ConvertFromUtf32((int)Value);
+            }
+        }
+
+        // Raw representation of a caught panic. This needs to match the layout of
+        // `RawOption<RawPanic>` on the Rust side.
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct RawPanic
+        {
+            public byte IsSome;
+            public RawVec Message;
+            public RawVec Location;
+        }
+
+        [StructLayout(LayoutKind.Sequential)]
+        internal unsafe struct RawVec
+        {
+            public IntPtr Ptr;
+            public UIntPtr Length;
+            public UIntPtr Capacity;
+
+            public List<T> ToPrimitiveList<T>() where T: unmanaged
+            {
+                return AsSlice().ToPrimitiveList<T>();
+            }
+
+            public List<T> ToPrimitiveList<D, T>(Func<D, T> conversion) where D: unmanaged
+            {
+                return AsSlice().ToPrimitiveList<D, T>(conversion);
             }
 
             public List<T> ToList<R, T>(
@@ -479,30 +1267,40 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
                 FromRaw<R, T> fromRaw)
             where R: unmanaged
             {
-                var slice = AsSlice();
-                var result = new List<T>((int)Length);
-
-                for (int index = 0; index < (int)Length; index += 1)
-                {
-                    R rawElement = indexFn(slice, (UIntPtr)index);
-                    fromRaw(rawElement, out T element);
-                    result.Add(element);
-                }
-
-                return result;
+                return AsSlice().ToList<R, T>(indexFn, fromRaw);
             }
 
             public static RawVec FromPrimitiveList<T>(List<T> items, Func<RawSlice, RawVec> allocVec)
                 where T: unmanaged
             {
-                // TODO: It would be nice to not have to copy the list in order to get the pointer.
-                // Support for getting a `Span<T>` from a `List<T>` is supposedly coming in
-                // netstandard5.0, though even then we wouldn't be able to use it in Unity for a
-                // while.
-                var array = items.ToArray();
-                fixed (T* ptr = array)
+                // NOTE: `List<T>` doesn't expose its backing array, so the items have to be
+                // copied into a buffer that can be pinned before Rust can copy them into the
+                // new `Vec`. Small lists are copied onto the stack, using the same cutoff as
+                // `FromList`, and larger lists are copied into a pooled array so that we
+                // don't allocate a new array for every conversion.
+                if (items.Count <= 32)
+                {
+                    T* buffer = stackalloc T[items.Count];
+                    for (int index = 0; index < items.Count; index += 1)
+                    {
+                        buffer[index] = items[index];
+                    }
+
+                    return allocVec(new RawSlice((IntPtr)buffer, items.Count));
+                }
+
+                var array = System.Buffers.ArrayPool<T>.Shared.Rent(items.Count);
+                try
                 {
-                    return allocVec(new RawSlice((IntPtr)ptr, items.Count));
+                    items.CopyTo(array);
+                    fixed (T* ptr = array)
+                    {
+                        return allocVec(new RawSlice((IntPtr)ptr, items.Count));
+                    }
+                }
+                finally
+                {
+                    System.Buffers.ArrayPool<T>.Shared.Return(array);
                 }
             }
 
@@ -545,6 +1343,190 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
             }
         }
 
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct RawMap
+        {
+            public RawVec Keys;
+            public RawVec Values;
+
+            public RawMap(RawVec keys, RawVec values)
+            {
+                Keys = keys;
+                Values = values;
+            }
+        }
+
+        // Base class for the `SafeHandle`s owned by the generated handle classes. Every call
+        // into Rust that borrows the value adds a reference, and the borrows are tracked
+        // under a lock with the same rules as Rust's references: any number of calls may
+        // borrow the value through `AddRef`, or a single call may borrow it mutably through
+        // `AddMutRef`, and the value can't be moved into Rust by `Take` while it's borrowed.
+        //
+        // A value that's `Send` but not `Sync` can't be shared between threads, so the calls
+        // that borrow it are serialized: each borrow holds `_callLock` until it's released,
+        // and borrows from other threads wait for it.
+        //
+        // An `async` call borrows the value until its task completes through `AddTaskRef`.
+        // The future can only borrow a value that's `Sync`, so these borrows don't hold
+        // `_callLock`, which couldn't be released from the thread completing the task.
+        internal abstract class RustHandle : SafeHandle
+        {
+            private readonly string _typeName;
+            private readonly object _callLock;
+            private int _refs;
+            private bool _mutBorrowed;
+
+            protected RustHandle(IntPtr raw, bool ownsHandle, string typeName, bool serializeCalls)
+                : base(IntPtr.Zero, ownsHandle)
+            {
+                SetHandle(raw);
+                _typeName = typeName;
+                _callLock = serializeCalls ? new object() : null;
+            }
+
+            public override bool IsInvalid => handle == IntPtr.Zero;
+
+            // Adds a shared reference to the handle, throwing if it has already been closed
+            // or is mutably borrowed. Every call must be paired with a call to `Release`.
+            internal void AddRef()
+            {
+                EnterCall();
+                try
+                {
+                    AddTaskRef();
+                }
+                catch
+                {
+                    ExitCall();
+                    throw;
+                }
+            }
+
+            // Adds a shared reference for an `async` call, which lasts until its task
+            // completes. Every call must be paired with a call to `ReleaseTaskRef`.
+            internal void AddTaskRef()
+            {
+                lock (this)
+                {
+                    if (_mutBorrowed)
+                    {
+                        throw new InvalidOperationException(
+                            "Cannot borrow a " + _typeName + " while it's mutably borrowed");
+                    }
+
+                    var added = false;
+                    DangerousAddRef(ref added);
+                    _refs += 1;
+                }
+            }
+
+            // Adds an exclusive reference to the handle, throwing if it has already been
+            // closed or is borrowed by another call. Every call must be paired with a call to
+            // `ReleaseMut`.
+            internal void AddMutRef()
+            {
+                EnterCall();
+                try
+                {
+                    lock (this)
+                    {
+                        if (_refs != 0 || _mutBorrowed)
+                        {
+                            throw new InvalidOperationException(
+                                "Cannot mutably borrow a " + _typeName + " while it's borrowed");
+                        }
+
+                        var added = false;
+                        DangerousAddRef(ref added);
+                        _mutBorrowed = true;
+                    }
+                }
+                catch
+                {
+                    ExitCall();
+                    throw;
+                }
+            }
+
+            internal void Release()
+            {
+                ReleaseTaskRef();
+                ExitCall();
+            }
+
+            internal void ReleaseTaskRef()
+            {
+                lock (this)
+                {
+                    _refs -= 1;
+                    DangerousRelease();
+                }
+            }
+
+            internal void ReleaseMut()
+            {
+                lock (this)
+                {
+                    _mutBorrowed = false;
+                    DangerousRelease();
+                }
+
+                ExitCall();
+            }
+
+            private void EnterCall()
+            {
+                if (_callLock != null)
+                {
+                    System.Threading.Monitor.Enter(_callLock);
+                }
+            }
+
+            private void ExitCall()
+            {
+                if (_callLock != null)
+                {
+                    System.Threading.Monitor.Exit(_callLock);
+                }
+            }
+
+            // Gives up the handle without releasing it, since Rust now owns the value.
+            internal IntPtr Take()
+            {
+                lock (this)
+                {
+                    if (IsClosed || IsInvalid)
+                    {
+                        throw new ObjectDisposedException(_typeName);
+                    }
+
+                    if (_refs != 0 || _mutBorrowed)
+                    {
+                        throw new InvalidOperationException(
+                            "Cannot move out of a " + _typeName + " while it's borrowed");
+                    }
+
+                    var raw = handle;
+                    SetHandleAsInvalid();
+                    return raw;
+                }
+            }
+        }
+
+        // Raw representation of a reference to a handle type. This has the same layout as
+        // a raw pointer, but is a distinct type so that borrowed handles use separate
+        // conversions from owned handles.
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct RawRef
+        {
+            public IntPtr Ptr;
+
+            public RawRef(IntPtr ptr)
+            {
+                Ptr = ptr;
+            }
+        }
+
         [StructLayout(LayoutKind.Sequential)]
         internal unsafe struct RawSlice
         {
@@ -562,10 +1544,87 @@ pub fn generate_bindings(exports: Vec<Export>, opt: &Opt) -> Result<String, fail
                 Ptr = ptr;
                 Length = (UIntPtr)len;
             }
+
+            public List<T> ToPrimitiveList<T>() where T: unmanaged
+            {
+                var result = new List<T>((int)Length);
+                var orig = (T*)Ptr;
+
+                for (int index = 0; index < (int)Length; index += 1)
+                {
+                    result.Add(orig[index]);
+                }
+
+                return result;
+            }
+
+            public List<T> ToPrimitiveList<D, T>(Func<D, T> conversion) where D: unmanaged
+            {
+                var result = new List<T>((int)Length);
+                var orig = (D*)Ptr;
+
+                for (int index = 0; index < (int)Length; index += 1)
+                {
+                    result.Add(conversion(orig[index]));
+                }
+
+                return result;
+            }
+
+            public List<T> ToList<R, T>(
+                Func<RawSlice, UIntPtr, R> indexFn,
+                FromRaw<R, T> fromRaw)
+            where R: unmanaged
+            {
+                var result = new List<T>((int)Length);
+
+                for (int index = 0; index < (int)Length; index += 1)
+                {
+                    R rawElement = indexFn(this, (UIntPtr)index);
+                    fromRaw(rawElement, out T element);
+                    result.Add(element);
+                }
+
+                return result;
+            }
         }
     };
 
-    Ok(generated.to_string())
+    // Wrap everything in the root namespace, if one was specified.
+    let root = &types.root;
+    let generated = if root.is_empty() {
+        generated
+    } else {
+        quote! {
+            namespace #( #root ).*
+            {
+                #generated
+            }
+        }
+    };
+
+    let generated = quote! {
+        using System;
+        using System.Collections.Generic;
+        using System.Runtime.ExceptionServices;
+        using System.Runtime.InteropServices;
+        using System.Text;
+        using System.Threading.Tasks;
+
+        #generated
+    };
+
+    Ok(docs::expand_doc_comments(&generated.to_string()))
+}
+
+/// Describes an exported item for use in error messages.
+fn describe_export(export: &Export) -> String {
+    match export {
+        Export::Fn(export) => format!("function `{}`", export.name),
+        Export::Method(export) => format!("method `{}::{}`", export.self_type.name, export.name),
+        Export::Named(export) => format!("type `{}`", export.type_name.name),
+        Export::Trait(export) => format!("trait `{}`", export.type_name.name),
+    }
 }
 
 /// Quotes the C# type corresponding to the given Rust primitive.
@@ -606,7 +1665,7 @@ fn quote_cs_type_for_repr(repr: &Repr, types: &TypeMap) -> TokenStream {
 
         Repr::Bool => quote! { bool },
 
-        Repr::Char => todo!("Support passing `char` values"),
+        Repr::Char => quote! { RustChar },
 
         Repr::I8 => quote! { sbyte },
         Repr::I16 => quote! { short },
@@ -633,28 +1692,52 @@ fn quote_cs_type_for_repr(repr: &Repr, types: &TypeMap) -> TokenStream {
             // naming convention from Rust structs.
             let ident = match &export.binding_style {
                 BindingStyle::Value(Schema::Enum(schema)) => {
-                    enumeration::quote_type_reference(schema)
+                    enumeration::quote_type_reference(export, schema)
                 }
-                _ => format_ident!("{}", &*export.type_name.name).into_token_stream(),
+                _ => export.ident().into_token_stream(),
             };
 
-            // TODO: Take into account things like custom namespaces or renaming the type, once
-            // those are supported. For now, we manually prefix references to user-defined types
-            // with `global::` in order to avoid name collisions. Once we support custom
-            // namespaces, we'll want to use the correct namespace name instead.
-            quote! { global::#ident }
+            // NOTE: References to user-defined types are always fully-qualified in order to
+            // avoid name collisions.
+            types.type_path(&export.type_name, ident)
         }
 
         Repr::Vec(inner) => quote_sequence_type(inner),
-        Repr::Slice(inner) => quote_sequence_type(inner),
-        Repr::Array { element, .. } => quote_sequence_type(element),
+        Repr::Slice(inner) | Repr::SliceMut(inner) => quote_sequence_type(inner),
+        Repr::Array { element, .. } => array::quote_cs_type(element, types),
 
         Repr::String | Repr::Str => quote! { string },
 
-        Repr::Option(_) => todo!("Support optional values"),
-        Repr::Result { .. } => todo!("Support results"),
+        Repr::Option(inner) => option::quote_cs_type(inner, types),
+        Repr::Result { ok, .. } => result::quote_cs_type(ok, types),
+        Repr::Tuple(elements) => tuple::quote_cs_type(elements, types),
+        Repr::Map { key, value } => map::quote_cs_type(key, value, types),
+
+        Repr::Callback { inputs, output } => callback::quote_cs_type(inputs, output, types),
+        Repr::FnPtr { .. } => unreachable!("Function pointers should have already been rejected"),
+
+        Repr::TraitObject(type_name) => {
+            types.type_path(type_name, types.get_trait(type_name).ident())
+        }
+
+        Repr::Iterator { item, .. } => iter::quote_cs_type(item, types),
+
+        // References to handle types are exposed as the handle class. The conversion
+        // from `RawRef` creates a handle object that doesn't own the underlying value.
+        Repr::Ref(inner) => match &**inner {
+            Repr::Named(type_name)
+                if types
+                    .get(type_name)
+                    .map(|export| export.binding_style == BindingStyle::Handle)
+                    .unwrap_or(false) =>
+            {
+                quote_cs_type_for_repr(inner, types)
+            }
 
-        Repr::Box(_) | Repr::Ref(_) => todo!("Support pointer types"),
+            _ => unreachable!("References to non-handle types should have already been rejected"),
+        },
+
+        Repr::Box(_) => todo!("Support pointer types"),
     }
 }
 
@@ -677,15 +1760,15 @@ fn quote_cs_type_for_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
         // type for a data-carrying enum is an interface, and therefore has a different
         // naming convention from Rust structs.
         let ident = match &export.binding_style {
-            BindingStyle::Value(Schema::Enum(schema)) => enumeration::quote_type_reference(schema),
-            _ => format_ident!("{}", &*export.type_name.name).into_token_stream(),
+            BindingStyle::Value(Schema::Enum(schema)) => {
+                enumeration::quote_type_reference(export, schema)
+            }
+            _ => export.ident().into_token_stream(),
         };
 
-        // TODO: Take into account things like custom namespaces or renaming the type, once
-        // those are supported. For now, we manually prefix references to user-defined types
-        // with `global::` in order to avoid name collisions. Once we support custom
-        // namespaces, we'll want to use the correct namespace name instead.
-        quote! { global::#ident }
+        // NOTE: References to user-defined types are always fully-qualified in order to
+        // avoid name collisions.
+        types.type_path(&export.type_name, ident)
     };
 
     match schema {
@@ -716,7 +1799,7 @@ fn quote_cs_type_for_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
         Schema::F32 => quote! { float },
         Schema::F64 => quote! { double },
 
-        Schema::Char => todo!("Support passing single chars"),
+        Schema::Char => quote! { RustChar },
 
         Schema::Str | Schema::String(_) => quote! { string },
 
@@ -729,9 +1812,10 @@ fn quote_cs_type_for_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
         | Schema::UnitStruct(_)
         | Schema::NewtypeStruct(_) => named_type_reference(schema.type_name().unwrap(), types),
 
-        // All sequence types are exposed in C# as a `List<T>`, since for all practical
-        // purposes that's the most efficient and flexible option.
-        Schema::Array(schema) => quote_sequence_type(&schema.element),
+        // All dynamically-sized sequence types are exposed in C# as a `List<T>`, since for
+        // all practical purposes that's the most efficient and flexible option. Fixed-size
+        // arrays are exposed as C# arrays, which is handled in terms of their `Repr`.
+        Schema::Array(_) => quote_cs_type_for_repr(&repr_from_schema(schema), types),
         Schema::Slice(element) => quote_sequence_type(element),
         Schema::Seq(schema) => quote_sequence_type(&schema.element),
 
@@ -744,32 +1828,450 @@ fn quote_cs_type_for_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
             }
         }
 
-        // Generate an unnamed tuple type for an exported Rust tuple. Conveniently this has
-        // the same syntax in C# as in Rust, e.g. `(int, Foo, Bar)`. How nice!
-        Schema::Tuple(elements) => {
-            let element = elements
-                .iter()
-                .map(|schema| quote_cs_type_for_schema(schema, types));
-            quote! {
-                ( #( #element ),* )
+        // Optional values are handled in terms of their `Repr`, since the nullable C# type
+        // depends on whether the inner type is a value type or a reference type. Tuples
+        // are likewise handled in terms of their `Repr`, since single-element tuples need
+        // special handling.
+        Schema::Option(_) | Schema::Tuple(_) => {
+            quote_cs_type_for_repr(&repr_from_schema(schema), types)
+        }
+
+        Schema::I128 | Schema::U128 => {
+            unreachable!("Invalid argument types should have already been rejected");
+        }
+    }
+}
+
+/// Visits `repr` and every type nested within it, visiting the nested types first.
+fn visit_repr(repr: &Repr, visit: &mut impl FnMut(&Repr)) {
+    match repr {
+        Repr::Box(inner)
+        | Repr::Ref(inner)
+        | Repr::Vec(inner)
+        | Repr::Slice(inner)
+        | Repr::SliceMut(inner)
+        | Repr::Option(inner) => visit_repr(inner, visit),
+
+        Repr::Array { element, .. } => visit_repr(element, visit),
+        Repr::Iterator { item, .. } => visit_repr(item, visit),
+
+        Repr::Result { ok, err } => {
+            visit_repr(ok, visit);
+            visit_repr(err, visit);
+        }
+
+        Repr::Map { key, value } => {
+            visit_repr(key, visit);
+            visit_repr(value, visit);
+        }
+
+        Repr::Tuple(elements) => {
+            for element in elements {
+                visit_repr(element, visit);
+            }
+        }
+
+        Repr::Callback { inputs, output } | Repr::FnPtr { inputs, output } => {
+            for input in inputs {
+                visit_repr(input, visit);
+            }
+            visit_repr(output, visit);
+        }
+
+        _ => {}
+    }
+
+    visit(repr);
+}
+
+/// Calls `visit` for every `Repr` used in the exported items.
+///
+/// This includes the reprs nested within other reprs (e.g. the element type of a
+/// `Vec<T>`), as well as the fields of types that are exported by value. Nested
+/// reprs are visited before the repr that contains them. Reprs may be visited more
+/// than once if they're used in more than one place.
+fn visit_reprs(exports: &[Export], mut visit: impl FnMut(&Repr)) {
+    fn visit_fields<'a>(fields: impl Iterator<Item = Field<'a>>, visit: &mut impl FnMut(&Repr)) {
+        for field in fields {
+            visit_repr(&repr_from_schema(field.schema), visit);
+        }
+    }
+
+    for export in exports {
+        let (inputs, output) = match export {
+            Export::Fn(export) => (&export.inputs, &export.output),
+            Export::Method(export) => (&export.inputs, &export.output),
+
+            Export::Named(export) => {
+                match &export.binding_style {
+                    BindingStyle::Handle => {}
+
+                    BindingStyle::Value(Schema::Enum(schema)) => {
+                        for variant in &schema.variants {
+                            visit_fields(variant.fields(), &mut visit);
+                        }
+                    }
+
+                    BindingStyle::Value(schema) => {
+                        if let Some(schema) = schema.as_struct_like() {
+                            visit_fields(schema.fields.into_iter(), &mut visit);
+                        }
+                    }
+                }
+
+                continue;
+            }
+
+            Export::Trait(export) => {
+                for method in &export.methods {
+                    for arg in &method.inputs {
+                        visit_repr(&arg.repr, &mut visit);
+                    }
+
+                    if let Some(output) = &method.output {
+                        visit_repr(output, &mut visit);
+                    }
+                }
+
+                continue;
             }
+        };
+
+        for arg in inputs {
+            visit_repr(&arg.repr, &mut visit);
         }
 
-        // TODO: Add support for optional types. In order to do so, we'll need to determine
-        // if the type is a reference type or a value type. Reference types are inherently
-        // nullable, but value types need to be converted to `Nullable<T>` (or `T?` for
-        // short).
-        Schema::Option(_) => todo!("Generate nullable type reference"),
+        if let Some(output) = output {
+            visit_repr(output, &mut visit);
+        }
+    }
+}
+
+/// The built-in types that are always declared in the root namespace.
+const BUILT_IN_TYPES: &[&str] = &[
+    "MonoPInvokeCallbackAttribute",
+    "RawCallback",
+    "RawMap",
+    "RawPanic",
+    "RawRef",
+    "RawSlice",
+    "RawVec",
+    "RustChar",
+    "RustException",
+    "RustHandle",
+    "RustPanicException",
+    "__bindings",
+];
+
+/// Verifies that the exported items don't produce any conflicting names in the
+/// generated bindings.
+///
+/// Exported items are mangled using their module path, so items with the same name
+/// in different modules only conflict if they end up generating the same C# type or
+/// function. This happens if the bindings aren't generated with module namespaces,
+/// or if two module paths are mangled into the same identifier.
+fn check_collisions(
+    exports: &[Export],
+    properties: &[class::Property<'_>],
+    class_name: &Ident,
+    types: &TypeMap,
+) -> Result<(), failure::Error> {
+    fn check(
+        seen: &mut HashMap<String, String>,
+        key: String,
+        item: String,
+    ) -> Result<(), failure::Error> {
+        // Generated names are formatted from token streams, so remove the whitespace
+        // between tokens to get the name as it would be written in C#.
+        let key = key.replace(' ', "");
+        match seen.get(&key) {
+            Some(existing) if *existing != item => Err(failure::format_err!(
+                "{} and {} both generate `{}` in the C# bindings",
+                existing,
+                item,
+                key,
+            )),
+            _ => {
+                seen.insert(key, item);
+                Ok(())
+            }
+        }
+    }
+
+    let mut cs_types = HashMap::new();
+    let mut entry_points = HashMap::new();
+    let mut fns = HashMap::new();
+    let mut members = HashMap::new();
+
+    // Properties and methods share a namespace within the generated type, so a property
+    // can't have the same name as a method. The key is the same for every member with
+    // a given name, and the item names the kind of member, so methods only conflict
+    // with properties and not with their own overloads.
+    let member_key = |self_type: &TypeName, name: &str| {
+        format!(
+            "{}.{}",
+            types.type_path(self_type, types.type_ident(self_type)),
+            name
+        )
+    };
+    for property in properties {
+        check(
+            &mut members,
+            member_key(property.self_type, &property.name),
+            format!("property `{}::{}`", property.self_type.name, property.name),
+        )?;
+    }
+
+    for name in BUILT_IN_TYPES {
+        check(
+            &mut cs_types,
+            types.root_path(format_ident!("{}", name)).to_string(),
+            format!("built-in type `{}`", name),
+        )?;
+    }
+    check(
+        &mut cs_types,
+        types.root_path(class_name).to_string(),
+        format!("the class for exported functions `{}`", class_name),
+    )?;
+
+    for export in exports {
+        match export {
+            Export::Fn(export) => {
+                let item = format!("function `{}`", export.binding);
+                check(
+                    &mut entry_points,
+                    entry_point_ident(&export.binding).to_string(),
+                    item.clone(),
+                )?;
+
+                // Free functions are all generated as static methods in a single class, so
+                // two functions conflict if they have the same name and argument types.
+                let args = export
+                    .inputs
+                    .iter()
+                    .map(|arg| quote_cs_type_for_repr(&arg.repr, types).to_string())
+                    .collect::<Vec<_>>();
+                let signature = format!(
+                    "{}({})",
+                    func::fn_name(&export.name, export.rename.as_deref()),
+                    args.join(","),
+                );
+                check(&mut fns, signature, item)?;
+            }
+
+            Export::Method(export) => {
+                check(
+                    &mut entry_points,
+                    entry_point_ident(&export.binding).to_string(),
+                    format!("method `{}`", export.binding),
+                )?;
+
+                let is_handle = types
+                    .get(&export.self_type)
+                    .map(|self_type| self_type.binding_style == BindingStyle::Handle)
+                    .unwrap_or(false);
+                if export.accessor.is_some() || (is_handle && class::is_constructor(export)) {
+                    continue;
+                }
+
+                // The methods of a trait impl implement the interface, so they can't share a
+                // name with any other method of the type, even one with different arguments.
+                let (name, item) = match &export.trait_name {
+                    Some(trait_name) => {
+                        let name =
+                            interface::impl_method_name(types.get_trait(trait_name), &export.name);
+                        let item = format!(
+                            "method `{}::{}` of trait `{}::{}`",
+                            export.self_type.name, name, trait_name.module, trait_name.name,
+                        );
+                        (name, item)
+                    }
+                    None => {
+                        let name = func::fn_name(&export.name, export.rename.as_deref());
+                        let item = format!("method `{}::{}`", export.self_type.name, name);
+                        (name, item)
+                    }
+                };
+                check(&mut members, member_key(&export.self_type, &name), item)?;
+            }
+
+            Export::Named(export) => {
+                let type_name = &export.type_name;
+                let item = format!("type `{}::{}`", type_name.module, type_name.name);
+
+                let ident = export.ident();
+                let mut cs_names = vec![ident.clone()];
+                if let BindingStyle::Value(Schema::Enum(schema)) = &export.binding_style {
+                    let has_methods = exports.iter().any(|export| {
+                        matches!(export, Export::Method(method) if method.self_type == *type_name)
+                    });
+
+                    if schema.has_data() {
+                        cs_names.push(format_ident!("I{}", ident));
+                    } else if has_methods {
+                        cs_names.push(format_ident!("{}Extensions", ident));
+                    }
+                }
+                for ident in cs_names {
+                    check(
+                        &mut cs_types,
+                        types.type_path(type_name, ident).to_string(),
+                        item.clone(),
+                    )?;
+                }
+
+                check(
+                    &mut cs_types,
+                    types.root_path(raw_ident(type_name)).to_string(),
+                    item.clone(),
+                )?;
+
+                let mut fn_entry_points = vec![
+                    export.index_fn.to_string(),
+                    export.convert_list_fn.to_string(),
+                    export.drop_vec_fn.to_string(),
+                ];
+                if export.binding_style == BindingStyle::Handle {
+                    fn_entry_points.push(drop_fn_entry_point(type_name));
+                }
+                for entry_point in fn_entry_points {
+                    check(
+                        &mut entry_points,
+                        entry_point_ident(&entry_point).to_string(),
+                        item.clone(),
+                    )?;
+                }
+            }
+
+            Export::Trait(export) => {
+                let type_name = &export.type_name;
+                let item = format!("trait `{}::{}`", type_name.module, type_name.name);
+                check(
+                    &mut cs_types,
+                    types.type_path(type_name, export.ident()).to_string(),
+                    item.clone(),
+                )?;
+                check(
+                    &mut cs_types,
+                    types.root_path(raw_ident(type_name)).to_string(),
+                    item,
+                )?;
+            }
+        }
+    }
+
+    // Each iterator has its own handle type, which exports a `next` function alongside
+    // its drop function.
+    let mut iterators = Vec::new();
+    visit_reprs(exports, |repr| {
+        if let Repr::Iterator { type_name, .. } = repr {
+            iterators.push(type_name.clone());
+        }
+    });
+    for type_name in &iterators {
+        let item = format!("iterator `{}::{}`", type_name.module, type_name.name);
+        for entry_point in &[
+            next_fn_entry_point(type_name),
+            drop_fn_entry_point(type_name),
+        ] {
+            check(
+                &mut entry_points,
+                entry_point_ident(entry_point).to_string(),
+                item.clone(),
+            )?;
+        }
+        check(
+            &mut cs_types,
+            types.root_path(raw_ident(type_name)).to_string(),
+            item,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts a type schema into the equivalent `Repr`.
+///
+/// The fields of exported types are described using their `Schema`, whereas
+/// function arguments and return types are described using `Repr`. This allows
+/// code generation that is defined in terms of `Repr` to also be used for fields.
+///
+/// # Panics
+///
+/// Panics for 128 bit integers, since they don't have an equivalent `Repr`.
+fn repr_from_schema(schema: &Schema) -> Repr {
+    match schema {
+        Schema::Unit => Repr::Unit,
+        Schema::Bool => Repr::Bool,
+        Schema::Char => Repr::Char,
+
+        Schema::I8 => Repr::I8,
+        Schema::I16 => Repr::I16,
+        Schema::I32 => Repr::I32,
+        Schema::I64 => Repr::I64,
+        Schema::ISize => Repr::ISize,
+
+        Schema::U8 => Repr::U8,
+        Schema::U16 => Repr::U16,
+        Schema::U32 => Repr::U32,
+        Schema::U64 => Repr::U64,
+        Schema::USize => Repr::USize,
+
+        Schema::F32 => Repr::F32,
+        Schema::F64 => Repr::F64,
+
+        Schema::Str => Repr::Str,
+        Schema::String(_) => Repr::String,
+
+        // NOTE: The unwrap here is valid because all of the struct-like variants are
+        // guaranteed to have a type name. If this panics, that indicates a bug in the
+        // schematic crate.
+        Schema::Enum(_)
+        | Schema::Struct(_)
+        | Schema::TupleStruct(_)
+        | Schema::UnitStruct(_)
+        | Schema::NewtypeStruct(_) => Repr::Named(schema.type_name().unwrap().clone()),
+
+        Schema::Option(inner) => Repr::Option(Box::new(repr_from_schema(inner))),
+        Schema::Seq(schema) => Repr::Vec(Box::new(repr_from_schema(&schema.element))),
+        Schema::Slice(element) => Repr::Slice(Box::new(repr_from_schema(element))),
+        Schema::Array(schema) => Repr::Array {
+            element: Box::new(repr_from_schema(&schema.element)),
+            len: schema.len,
+        },
+
+        Schema::Tuple(elements) => Repr::Tuple(elements.iter().map(repr_from_schema).collect()),
+
+        Schema::Map(schema) => Repr::Map {
+            key: Box::new(repr_from_schema(&schema.key)),
+            value: Box::new(repr_from_schema(&schema.value)),
+        },
 
         Schema::I128 | Schema::U128 => {
-            unreachable!("Invalid argument types should have already been rejected");
+            unreachable!("Invalid types should have already been handled")
         }
     }
 }
 
 #[extend::ext]
-impl TypeName {
+impl NamedType {
+    /// Returns the identifier of the generated C# type, i.e. the name specified with
+    /// `#[cs_bindgen(rename = "...")]` or the name of the Rust type.
+    fn ident(&self) -> Ident {
+        format_ident!("{}", self.rename.as_deref().unwrap_or(&self.type_name.name))
+    }
+}
+
+#[extend::ext]
+impl Trait {
+    /// Returns the identifier of the generated C# interface, i.e. the name specified
+    /// with `#[cs_bindgen(rename = "...")]` or the name of the Rust trait prefixed with
+    /// `I`.
     fn ident(&self) -> Ident {
-        format_ident!("{}", self.name)
+        match &self.rename {
+            Some(rename) => format_ident!("{}", &**rename),
+            None => format_ident!("I{}", self.type_name.name),
+        }
     }
 }
@@ -0,0 +1,173 @@
+//! Code generation for fixed-size arrays.
+//!
+//! Rust arrays (e.g. `[f32; 16]`) are exposed in C# as regular arrays (e.g.
+//! `float[]`). Arrays are passed by value, so we generate a raw struct for each
+//! array type that has the same layout as the raw array in Rust:
+//!
+//! * If the raw element type is a primitive that can be used in a fixed-size buffer
+//!   (i.e. the numeric types), the raw struct contains a single `fixed` buffer.
+//! * Otherwise the raw struct has a separate field for each element, since C#
+//!   doesn't support fixed-size buffers of other types. All of the fields have the
+//!   same type, so the fields are laid out exactly like an array.
+//!
+//! In both cases the conversions access the elements through a pointer to the first
+//! element. Empty arrays are rejected, since `[T; 0]` is zero-sized in Rust and a C#
+//! struct can't be.
+
+use crate::generate::{self, binding, TypeMap};
+use cs_bindgen_shared::{Export, Repr};
+use proc_macro2::{Literal, TokenStream};
+use quote::*;
+use syn::Ident;
+
+/// Raw element types that are allowed in a C# fixed-size buffer.
+const FIXED_BUFFER_TYPES: &[&str] = &[
+    "byte", "sbyte", "short", "ushort", "int", "uint", "long", "ulong", "float", "double",
+];
+
+/// Generates the raw structs and `__FromRaw`/`__IntoRaw` conversions for every
+/// array type used in the exported items.
+pub fn quote_array_bindings(exports: &[Export], types: &TypeMap) -> TokenStream {
+    let mut array_types = Vec::new();
+    generate::visit_reprs(exports, |repr| {
+        if let Repr::Array { element, len } = repr {
+            let pair = ((**element).clone(), *len);
+            if !array_types.contains(&pair) {
+                array_types.push(pair);
+            }
+        }
+    });
+
+    // Multiple array types may share the same raw representation (e.g. `[bool; 4]`
+    // and `[u8; 4]` are both passed as 4 `byte`s), so we only generate each raw
+    // struct once.
+    let mut raw_structs = Vec::new();
+    let mut generated_structs = Vec::new();
+    let mut conversions = Vec::new();
+    for (element, len) in &array_types {
+        let ident = raw_ident(element, *len, types);
+        if !generated_structs.contains(&ident) {
+            raw_structs.push(quote_raw_struct(&ident, element, *len, types));
+            generated_structs.push(ident);
+        }
+
+        conversions.push(quote_conversions(element, *len, types));
+    }
+
+    let conversions = binding::wrap_bindings(quote! {
+        #( #conversions )*
+    });
+
+    quote! {
+        #( #raw_structs )*
+        #conversions
+    }
+}
+
+/// Quotes the C# array type corresponding to an array of `element`.
+pub fn quote_cs_type(element: &Repr, types: &TypeMap) -> TokenStream {
+    let element = generate::quote_cs_type_for_repr(element, types);
+    quote! { #element[] }
+}
+
+/// Quotes a reference to the raw struct used to represent `[element; len]`.
+pub fn raw_type_reference(element: &Repr, len: usize, types: &TypeMap) -> TokenStream {
+    let ident = raw_ident(element, len, types);
+    types.root_path(ident)
+}
+
+/// Generates the identifier of the raw struct used to represent `[element; len]`.
+///
+/// The identifier is derived from the raw type of the element and the length of
+/// the array, e.g. the raw representation of `[f32; 16]` is
+/// `__Array__float__16__Raw`.
+fn raw_ident(element: &Repr, len: usize, types: &TypeMap) -> Ident {
+    format_ident!(
+        "__Array__{}__{}__Raw",
+        binding::mangled_raw_type(element, types),
+        len,
+    )
+}
+
+/// Determines if the raw representation of an array of `element` can use a
+/// fixed-size buffer.
+fn use_fixed_buffer(element: &Repr, types: &TypeMap) -> bool {
+    let raw_element = binding::raw_type_from_repr(element, types).to_string();
+    FIXED_BUFFER_TYPES.contains(&&*raw_element)
+}
+
+fn quote_raw_struct(ident: &Ident, element: &Repr, len: usize, types: &TypeMap) -> TokenStream {
+    let raw_element = binding::raw_type_from_repr(element, types);
+
+    let fields = if use_fixed_buffer(element, types) {
+        let len = Literal::usize_unsuffixed(len);
+        quote! {
+            public fixed #raw_element Elements[#len];
+        }
+    } else {
+        let field = (0..len).map(|index| format_ident!("Element{}", index));
+        quote! {
+            #(
+                public #raw_element #field;
+            )*
+        }
+    };
+
+    quote! {
+        [StructLayout(LayoutKind.Sequential)]
+        internal unsafe struct #ident
+        {
+            #fields
+        }
+    }
+}
+
+fn quote_conversions(element: &Repr, len: usize, types: &TypeMap) -> TokenStream {
+    let raw_ty = raw_type_reference(element, len, types);
+    let raw_element = binding::raw_type_from_repr(element, types);
+    let cs_ty = quote_cs_type(element, types);
+    let element_ty = generate::quote_cs_type_for_repr(element, types);
+
+    let from_raw = binding::from_raw_fn_ident();
+    let into_raw = binding::into_raw_fn_ident();
+
+    // Get a pointer to the first element, depending on how the elements are stored in
+    // the raw struct.
+    let raw_ptr = if use_fixed_buffer(element, types) {
+        quote! { raw.Elements }
+    } else {
+        quote! { &raw.Element0 }
+    };
+
+    let error_message = format!("Expected an array with {} elements", len);
+    let len = Literal::usize_unsuffixed(len);
+
+    quote! {
+        internal static void #from_raw(#raw_ty raw, out #cs_ty result)
+        {
+            #raw_element* elements = #raw_ptr;
+            result = new #element_ty[#len];
+            for (int index = 0; index < #len; index += 1)
+            {
+                #from_raw(elements[index], out result[index]);
+            }
+        }
+
+        internal static void #into_raw(#cs_ty value, out #raw_ty result)
+        {
+            if (value.Length != #len)
+            {
+                throw new ArgumentException(#error_message, nameof(value));
+            }
+
+            var raw = new #raw_ty();
+            #raw_element* elements = #raw_ptr;
+            for (int index = 0; index < #len; index += 1)
+            {
+                #into_raw(value[index], out elements[index]);
+            }
+
+            result = raw;
+        }
+    }
+}
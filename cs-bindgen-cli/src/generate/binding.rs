@@ -10,23 +10,17 @@
 //! function, using the `[DllImport]` attribute to load the corresponding function
 //! from the Rust dylib.
 
-use crate::generate::{self, class, enumeration, strukt, TypeMap, STRING_SCHEMA};
+use crate::generate::{
+    self, array, class, enumeration, iter, option, result, task, tuple, TypeMap, STRING_SCHEMA,
+};
 use cs_bindgen_shared::{
     schematic::{Field, Schema, TypeName},
-    BindingStyle, Export, FnArg, Repr,
+    BindingStyle, Export, FnArg, ReceiverStyle, Repr,
 };
 use proc_macro2::TokenStream;
 use quote::*;
 use syn::{punctuated::Punctuated, token::Comma, Ident};
 
-// TODO: For the below functions that generate identifiers based on a type name, we
-// should use the fully-qualified `TypeName` instead of just a `&str` name. Right
-// now, if two types with the same name in different modules are exported, the
-// generated bindings will collide. We can avoid this by taking the module name into
-// account when generating the idents. This will require some additional mangling
-// logic, since the module paths include `::` characters, which aren't valid in C#
-// identifiers.
-
 /// Returns the identifier of the generating bindings class.
 pub fn bindings_class_ident() -> Ident {
     format_ident!("__bindings")
@@ -48,6 +42,14 @@ pub fn into_raw_fn_ident() -> Ident {
     format_ident!("__IntoRaw")
 }
 
+/// The identifier of the method that checks for a Rust panic.
+///
+/// This method must be called after every call into Rust, and throws a
+/// `RustPanicException` if the Rust code panicked.
+pub fn check_panic_fn_ident() -> Ident {
+    format_ident!("__CheckPanic")
+}
+
 /// Generate the identifier for the raw type corresponding to the specified type.
 ///
 /// When a user-defined type is marshaled by value, we generate a type that acts as
@@ -56,7 +58,83 @@ pub fn into_raw_fn_ident() -> Ident {
 /// This function provides the canonical way to generate the name of the raw type
 /// corresponding to any given exported Rust type.
 pub fn raw_ident(type_name: &TypeName) -> Ident {
-    format_ident!("__{}__Raw", type_name.name)
+    format_ident!("__{}__Raw", mangled_type_name(type_name))
+}
+
+/// Mangles the full path of an exported type into a valid C# identifier fragment.
+///
+/// Raw types are all declared in the root namespace, so the module path is included
+/// in the mangled name in order to keep the raw types distinct for exported types
+/// with the same name in different modules.
+pub fn mangled_type_name(type_name: &TypeName) -> String {
+    mangle(&format!("{}::{}", type_name.module, type_name.name))
+}
+
+/// Generates the identifier for the raw binding to the exported function with the
+/// specified entry point.
+///
+/// Entry points include the module path of the exported item, e.g.
+/// `__cs_bindgen_generated__my_crate::foo::bar`, so the entry point is mangled into a
+/// valid C# identifier.
+pub fn entry_point_ident(entry_point: &str) -> Ident {
+    format_ident!("{}", mangle(entry_point))
+}
+
+/// Returns the entry point for the function that drops a handle of the specified type.
+///
+/// This needs to match the `export_name` generated for the drop function by the
+/// `#[cs_bindgen]` macro.
+pub fn drop_fn_entry_point(type_name: &TypeName) -> String {
+    format!(
+        "__cs_bindgen_drop__{}::{}",
+        type_name.module, type_name.name
+    )
+}
+
+/// Returns the entry point for the function that advances an iterator returned from
+/// Rust, where `type_name` is the name of the handle type for the iterator.
+///
+/// This needs to match the `export_name` generated for the `next` function by the
+/// `#[cs_bindgen]` macro.
+pub fn next_fn_entry_point(type_name: &TypeName) -> String {
+    format!(
+        "__cs_bindgen_next__{}::{}",
+        type_name.module, type_name.name
+    )
+}
+
+/// Replaces any characters that aren't valid in a C# identifier with `_`.
+pub fn mangle(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Generates a name fragment based on the raw type of `repr`.
+///
+/// Generic structs can't be used in P/Invoke signatures, so we generate a separate
+/// raw struct for each instantiation of a built-in generic type (e.g. `Option<T>`).
+/// The raw struct names are built from the raw types of their type parameters, so
+/// the raw type reference is mangled into a valid C# identifier fragment.
+///
+/// Exported types always use their type name, even if they're represented by a
+/// primitive type (e.g. handles and C-like enums). This ensures that the generated
+/// raw structs (and the conversions for those structs) remain distinct for
+/// different exported types.
+pub fn mangled_raw_type(repr: &Repr, types: &TypeMap) -> String {
+    if let Repr::Named(type_name) = repr {
+        return mangled_type_name(type_name);
+    }
+
+    // Raw types are declared in the root namespace, so strip the namespace from the
+    // fully-qualified reference before mangling it.
+    let root = types.root_path(TokenStream::new()).to_string();
+    mangle(
+        raw_type_from_repr(repr, types)
+            .to_string()
+            .trim_start_matches(&root)
+            .trim(),
+    )
 }
 
 pub fn wrap_bindings(tokens: TokenStream) -> TokenStream {
@@ -71,8 +149,12 @@ pub fn wrap_bindings(tokens: TokenStream) -> TokenStream {
 pub fn quote_raw_binding(export: &Export, dll_name: &str, types: &TypeMap) -> TokenStream {
     match export {
         Export::Fn(export) => {
-            let args = quote_binding_args(&export.inputs, types);
+            let mut args = quote_binding_args(&export.inputs, types);
             let return_ty = match &export.output {
+                _ if export.is_async => {
+                    args.push(task::quote_completion_arg());
+                    quote! { void }
+                }
                 Some(output) => raw_type_from_repr(output, types),
                 None => quote! { void },
             };
@@ -82,6 +164,7 @@ pub fn quote_raw_binding(export: &Export, dll_name: &str, types: &TypeMap) -> To
 
         Export::Method(export) => {
             let return_ty = match &export.output {
+                _ if export.is_async => quote! { void },
                 Some(output) => raw_type_from_repr(output, types),
                 None => quote! { void },
             };
@@ -90,10 +173,28 @@ pub fn quote_raw_binding(export: &Export, dll_name: &str, types: &TypeMap) -> To
             // manually insert the receiver. The current blocker is that schematic can't
             // represent reference types, so we can't generate a full list of inputs that
             // includes the receiver.
+            //
+            // Handle types always pass the handle pointer as the receiver. Value types
+            // pass the raw value, or a reference to it for `&mut self` methods so that
+            // Rust can write back the modified value.
             let mut args = quote_binding_args(&export.inputs, types);
-            if export.receiver.is_some() {
-                let handle_type = class::quote_handle_ptr();
-                args.insert(0, quote! { #handle_type self });
+            if let Some(style) = &export.receiver {
+                let self_export = types.get(&export.self_type).unwrap_or_else(|| {
+                    panic!("No export found for type name {:?}", export.self_type)
+                });
+
+                let receiver_ty = match (&self_export.binding_style, style) {
+                    (BindingStyle::Handle, _) => class::quote_handle_ptr(),
+                    (BindingStyle::Value(schema), ReceiverStyle::RefMut) => {
+                        let raw_ty = raw_type_from_schema(schema, types);
+                        quote! { ref #raw_ty }
+                    }
+                    (BindingStyle::Value(schema), _) => raw_type_from_schema(schema, types),
+                };
+                args.insert(0, quote! { #receiver_ty self });
+            }
+            if export.is_async {
+                args.push(task::quote_completion_arg());
             }
 
             quote_raw_fn_binding(&export.binding, return_ty, args.to_token_stream(), dll_name)
@@ -130,9 +231,9 @@ pub fn quote_raw_binding(export: &Export, dll_name: &str, types: &TypeMap) -> To
                 let into_raw = into_raw_fn_ident();
                 let ty = generate::quote_cs_type_for_schema(schema, types);
                 let raw_repr = raw_type_from_schema(schema, types);
-                let index_fn_name = format_ident!("{}", &*export.index_fn);
-                let drop_vec_fn_name = format_ident!("{}", &*export.drop_vec_fn);
-                let convert_list_fn_name = format_ident!("{}", &*export.convert_list_fn);
+                let index_fn_name = entry_point_ident(&export.index_fn);
+                let drop_vec_fn_name = entry_point_ident(&export.drop_vec_fn);
+                let convert_list_fn_name = entry_point_ident(&export.convert_list_fn);
 
                 let list_from_raw = quote! {
                     internal static void #from_raw(RawVec raw, out List<#ty> result)
@@ -142,6 +243,15 @@ pub fn quote_raw_binding(export: &Export, dll_name: &str, types: &TypeMap) -> To
                     }
                 };
 
+                // Borrowed slices are owned by the Rust side, so the list is copied out
+                // without freeing the original slice.
+                let slice_from_raw = quote! {
+                    internal static void #from_raw(RawSlice raw, out List<#ty> result)
+                    {
+                        result = raw.ToList<#raw_repr, #ty>(#index_fn_name, #from_raw);
+                    }
+                };
+
                 let list_into_raw = quote! {
                     internal static void #into_raw(List<#ty> items, out RawVec result)
                     {
@@ -160,10 +270,14 @@ pub fn quote_raw_binding(export: &Export, dll_name: &str, types: &TypeMap) -> To
                     #convert_list_fn
                     #drop_vec_fn
                     #list_from_raw
+                    #slice_from_raw
                     #list_into_raw
                 }
             }
         },
+
+        // The conversions for exported traits are generated along with the interface.
+        Export::Trait(_) => TokenStream::new(),
     }
 }
 
@@ -174,6 +288,10 @@ pub fn quote_raw_binding(export: &Export, dll_name: &str, types: &TypeMap) -> To
 /// * For primitive numeric types we use the corresponding C# numeric type.
 /// * For `String` and `Vec` we use `RawVec`.
 /// * For `str` and slices use `RawSlice`.
+/// * For references we use `RawRef`.
+/// * For maps we use `RawMap`.
+/// * For fixed-size arrays, tuples, optional values, and results we generate a raw
+///   struct for each instantiation.
 /// * For named types we look up the export definition to determine what raw repr to
 ///   use:
 ///   * Handle types are represented as a raw pointer (`IntPtr`, specifically).
@@ -215,18 +333,36 @@ pub fn raw_type_from_repr(repr: &Repr, types: &TypeMap) -> TokenStream {
             }
         }
 
-        // Pointer types are all marshalled as `IntPtr`.
-        Repr::Box(_) | Repr::Ref(_) => quote! { IntPtr },
+        // References use `RawRef` so that borrowed handles aren't converted like owned
+        // handles. Other pointer types are marshalled as `IntPtr`.
+        Repr::Ref(_) => quote! { RawRef },
+        Repr::Box(_) => quote! { IntPtr },
 
         Repr::Vec(_) => quote! { RawVec },
-        Repr::Slice(_) => quote! { RawSlice },
+        Repr::Slice(_) | Repr::SliceMut(_) => quote! { RawSlice },
 
         Repr::String => quote! { RawVec },
         Repr::Str => quote! { RawSlice },
 
-        Repr::Array { .. } => todo!("Support arrays"),
-        Repr::Option(_) => todo!("Support optional types"),
-        Repr::Result { .. } => todo!("Support `Result`"),
+        Repr::Array { element, len } => array::raw_type_reference(element, *len, types),
+        Repr::Option(inner) => option::raw_type_reference(inner, types),
+        Repr::Result { ok, err } => result::raw_type_reference(ok, err, types),
+        Repr::Tuple(elements) => tuple::raw_type_reference(elements, types),
+        Repr::Map { .. } => quote! { RawMap },
+
+        // Boxed callbacks are passed as a `GCHandle` for the delegate along with
+        // pointers to the trampoline functions.
+        Repr::Callback { .. } => quote! { RawCallback },
+        Repr::FnPtr { .. } => {
+            unreachable!("Function pointers should have already been rejected")
+        }
+
+        // Trait objects are passed as a raw struct generated for each trait.
+        Repr::TraitObject(type_name) => named_type_raw_reference(type_name, types),
+
+        // Iterators are passed as a handle wrapped in a raw struct generated for each
+        // handle type.
+        Repr::Iterator { type_name, .. } => iter::raw_type_reference(type_name, types),
     }
 }
 
@@ -281,7 +417,7 @@ pub fn raw_type_from_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
             if matches!(export.binding_style, BindingStyle::Handle) {
                 class::quote_handle_ptr()
             } else if schema.has_data() {
-                named_type_raw_reference(&schema.name)
+                named_type_raw_reference(&schema.name, types)
             } else {
                 enumeration::quote_discriminant_type(schema)
             }
@@ -304,11 +440,11 @@ pub fn raw_type_from_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
             if matches!(export.binding_style, BindingStyle::Handle) {
                 class::quote_handle_ptr()
             } else {
-                named_type_raw_reference(type_name)
+                named_type_raw_reference(type_name, types)
             }
         }
 
-        Schema::Array(_) => todo!("Support passing fixed-size arrays"),
+        Schema::Array(_) => raw_type_from_repr(&generate::repr_from_schema(schema), types),
 
         Schema::Slice(_) => quote! { RawSlice },
 
@@ -320,11 +456,12 @@ pub fn raw_type_from_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
             }
         }
 
-        // TODO: Add support for collection types.
-        Schema::Option(_) | Schema::Tuple(_) | Schema::Map { .. } => {
-            todo!("Generate argument binding")
+        Schema::Option(_) | Schema::Tuple(_) => {
+            raw_type_from_repr(&generate::repr_from_schema(schema), types)
         }
 
+        Schema::Map(_) => quote! { RawMap },
+
         Schema::I128 | Schema::U128 => {
             unreachable!("Invalid types should have already been handled")
         }
@@ -333,12 +470,16 @@ pub fn raw_type_from_schema(schema: &Schema, types: &TypeMap) -> TokenStream {
 
 /// Generates the field definitions for the raw struct representation of an exported
 /// Rust type.
-pub fn raw_struct_fields(fields: &[Field<'_>], types: &TypeMap) -> TokenStream {
-    let field_name = fields
-        .iter()
-        .enumerate()
-        .map(|(index, field)| strukt::field_ident(field.name, index));
-
+///
+/// The raw fields use the same idents as the fields of the user-facing struct, as
+/// returned by [`strukt::field_idents`].
+///
+/// [`strukt::field_idents`]: ../strukt/fn.field_idents.html
+pub fn raw_struct_fields(
+    fields: &[Field<'_>],
+    field_name: &[Ident],
+    types: &TypeMap,
+) -> TokenStream {
     let field_ty = fields
         .iter()
         .map(|field| raw_type_from_schema(&field.schema, types));
@@ -367,7 +508,7 @@ fn quote_raw_fn_binding(
     args: TokenStream,
     dll: &str,
 ) -> TokenStream {
-    let fn_name = format_ident!("{}", entry_point);
+    let fn_name = entry_point_ident(entry_point);
     quote! {
         [DllImport(
             #dll,
@@ -377,9 +518,6 @@ fn quote_raw_fn_binding(
     }
 }
 
-fn named_type_raw_reference(type_name: &TypeName) -> TokenStream {
-    let ident = raw_ident(type_name);
-    quote! {
-        global::#ident
-    }
+fn named_type_raw_reference(type_name: &TypeName, types: &TypeMap) -> TokenStream {
+    types.root_path(raw_ident(type_name))
 }
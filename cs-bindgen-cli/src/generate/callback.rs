@@ -0,0 +1,286 @@
+//! Code generation for callbacks passed from C# to Rust.
+//!
+//! Callbacks are exposed in C# as `Func<..>` delegates, or as `Action<..>` delegates
+//! if the callback doesn't return a value.
+//!
+//! A boxed callback, e.g. `Box<dyn Fn(i32) -> bool>`, is passed as a `RawCallback`,
+//! which matches the layout of `RawCallback` in the Rust runtime. It contains a
+//! `GCHandle` for the delegate, which keeps the delegate alive until Rust drops the
+//! callback, along with pointers to a trampoline function that invokes the delegate
+//! and a function that frees the `GCHandle`. We generate a trampoline for each
+//! callback signature used in the exported items, which converts the raw arguments,
+//! invokes the delegate, and converts the result back into its raw representation.
+//!
+//! The trampolines are static methods marked with `[MonoPInvokeCallback]`, since
+//! IL2CPP can only marshal delegates that point to static methods. IL2CPP identifies
+//! the attribute by name, so the bindings declare their own copy of it rather than
+//! depending on the one provided by Unity.
+//!
+//! Function pointers, e.g. `extern "C" fn(i32) -> i32`, are rejected before any code
+//! is generated. A function pointer doesn't have a context pointer, so the delegate
+//! itself would have to be marshaled as a function pointer, which IL2CPP doesn't
+//! support.
+//!
+//! Exceptions can't unwind through Rust code, so any exception thrown by a callback
+//! is caught and stored until the call into Rust returns, at which point it's
+//! rethrown by `__CheckPanic`. The trampoline also reports the exception to Rust,
+//! which then panics rather than converting the invalid result.
+
+use crate::generate::{self, binding, TypeMap};
+use cs_bindgen_shared::{Export, Repr};
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::Ident;
+
+/// Generates the raw delegate types, trampolines, and conversions for every callback
+/// signature used in the exported items, along with the built-in types used by all
+/// callbacks.
+pub fn quote_callback_bindings(exports: &[Export], types: &TypeMap) -> TokenStream {
+    // Multiple callback types may be exposed as the same C# delegate type (e.g. a
+    // boxed callback with and without a `Send` bound), so we only generate the
+    // bindings for each delegate type once.
+    let mut generated = Vec::new();
+    let mut bindings = Vec::new();
+    generate::visit_reprs(exports, |repr| {
+        let (inputs, output) = match repr {
+            Repr::Callback { inputs, output } => (inputs, output),
+            _ => return,
+        };
+
+        let ident = raw_delegate_ident(inputs, output, types);
+        if generated.contains(&ident) {
+            return;
+        }
+
+        bindings.push(quote_boxed_bindings(&ident, inputs, output, types));
+        generated.push(ident);
+    });
+
+    let bindings = binding::wrap_bindings(quote! {
+        // Frees the `GCHandle` for a boxed callback once Rust drops it.
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        internal delegate void __DropCallback(IntPtr context);
+
+        [MonoPInvokeCallback(typeof(__DropCallback))]
+        private static void __InvokeDropCallback(IntPtr context)
+        {
+            GCHandle.FromIntPtr(context).Free();
+        }
+
+        // NOTE: The delegates for the trampolines are stored in static fields so that
+        // they're never garbage collected while Rust holds a pointer to them.
+        private static readonly __DropCallback __DropCallbackDelegate = __InvokeDropCallback;
+        internal static readonly IntPtr __DropCallbackPtr =
+            Marshal.GetFunctionPointerForDelegate(__DropCallbackDelegate);
+
+        #( #bindings )*
+    });
+
+    quote! {
+        #bindings
+
+        // Raw representation of a boxed callback. This needs to match the layout of
+        // `RawCallback` on the Rust side.
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct RawCallback
+        {
+            public IntPtr Context;
+            public IntPtr Invoke;
+            public IntPtr Drop;
+
+            public RawCallback(IntPtr context, IntPtr invoke, IntPtr drop)
+            {
+                Context = context;
+                Invoke = invoke;
+                Drop = drop;
+            }
+        }
+
+        // Marks the static methods that Rust calls through a function pointer. IL2CPP
+        // requires this attribute in order to marshal a delegate to a static method.
+        [AttributeUsage(AttributeTargets.Method)]
+        internal sealed class MonoPInvokeCallbackAttribute : Attribute
+        {
+            public MonoPInvokeCallbackAttribute(Type type) { }
+        }
+    }
+}
+
+/// Quotes the C# delegate type corresponding to a callback with the given argument
+/// and return types.
+pub fn quote_cs_type(inputs: &[Repr], output: &Repr, types: &TypeMap) -> TokenStream {
+    let mut type_args = inputs
+        .iter()
+        .map(|repr| generate::quote_cs_type_for_repr(repr, types))
+        .collect::<Vec<_>>();
+
+    if *output == Repr::Unit {
+        if type_args.is_empty() {
+            quote! { Action }
+        } else {
+            quote! { Action<#( #type_args ),*> }
+        }
+    } else {
+        type_args.push(generate::quote_cs_type_for_repr(output, types));
+        quote! { Func<#( #type_args ),*> }
+    }
+}
+
+/// Returns `true` if `repr` contains a callback anywhere within it.
+///
+/// Trait objects are also considered callbacks, since they can likewise only be
+/// passed from C# to Rust.
+pub fn contains_callback(repr: &Repr) -> bool {
+    match repr {
+        Repr::Callback { .. } | Repr::FnPtr { .. } | Repr::TraitObject(_) => true,
+
+        Repr::Box(inner)
+        | Repr::Ref(inner)
+        | Repr::Vec(inner)
+        | Repr::Slice(inner)
+        | Repr::SliceMut(inner)
+        | Repr::Option(inner) => contains_callback(inner),
+
+        Repr::Array { element, .. } => contains_callback(element),
+        Repr::Result { ok, err } => contains_callback(ok) || contains_callback(err),
+        Repr::Map { key, value } => contains_callback(key) || contains_callback(value),
+        Repr::Tuple(elements) => elements.iter().any(contains_callback),
+        Repr::Iterator { item, .. } => contains_callback(item),
+
+        _ => false,
+    }
+}
+
+/// Generates the identifier of the raw delegate type for a callback.
+///
+/// The identifier is derived from the C# delegate type, since the trampoline converts
+/// the arguments to and from their C# types.
+fn raw_delegate_ident(inputs: &[Repr], output: &Repr, types: &TypeMap) -> Ident {
+    // References to exported types are fully-qualified, so strip the root namespace
+    // in order to keep the identifiers reasonably short.
+    let root = types.root_path(TokenStream::new()).to_string();
+    let cs_ty = quote_cs_type(inputs, output, types)
+        .to_string()
+        .replace(&root, "")
+        .replace("global ::", "");
+    let mangled = binding::mangle(&cs_ty.replace(' ', ""));
+    let mangled = mangled.trim_end_matches('_');
+    format_ident!("__Callback__{}", mangled)
+}
+
+/// Generates the trampoline and `__IntoRaw` conversion for a boxed callback.
+fn quote_boxed_bindings(
+    ident: &Ident,
+    inputs: &[Repr],
+    output: &Repr,
+    types: &TypeMap,
+) -> TokenStream {
+    let cs_ty = quote_cs_type(inputs, output, types);
+    let (trampoline, ptr_field) =
+        quote_trampoline(ident, &cs_ty, &quote! { target }, inputs, output, types);
+    let into_raw = binding::into_raw_fn_ident();
+
+    quote! {
+        #trampoline
+
+        internal static void #into_raw(#cs_ty value, out RawCallback result)
+        {
+            if (value == null)
+            {
+                throw new ArgumentNullException(nameof(value));
+            }
+
+            result = new RawCallback(
+                GCHandle.ToIntPtr(GCHandle.Alloc(value)),
+                #ptr_field,
+                __DropCallbackPtr);
+        }
+    }
+}
+
+/// Generates a trampoline that Rust calls with the `GCHandle` of a C# object as its
+/// context, along with the raw delegate type for the trampoline. Returns the
+/// trampoline and the static field holding the pointer to it.
+///
+/// The trampoline converts the raw arguments and passes them to `invoke`, which is
+/// evaluated with the object bound to `target` (e.g. `target` for a delegate, or
+/// `target.Method` for a method of an interface). Any exception thrown by the object
+/// is stored for `__CheckPanic` to rethrow, and `threw` is set so that Rust doesn't
+/// convert the invalid result.
+pub fn quote_trampoline(
+    ident: &Ident,
+    target_ty: &TokenStream,
+    invoke: &TokenStream,
+    inputs: &[Repr],
+    output: &Repr,
+    types: &TypeMap,
+) -> (TokenStream, Ident) {
+    let raw_output = binding::raw_type_from_repr(output, types);
+    let arg = (0..inputs.len())
+        .map(|index| format_ident!("arg{}", index))
+        .collect::<Vec<_>>();
+    let temp = (0..inputs.len())
+        .map(|index| format_ident!("__arg{}", index))
+        .collect::<Vec<_>>();
+    let raw_input = inputs
+        .iter()
+        .map(|repr| binding::raw_type_from_repr(repr, types))
+        .collect::<Vec<_>>();
+    let input_ty = inputs
+        .iter()
+        .map(|repr| generate::quote_cs_type_for_repr(repr, types))
+        .collect::<Vec<_>>();
+
+    let invoke_fn = format_ident!("__Invoke{}", ident);
+    let delegate_field = format_ident!("{}__Delegate", ident);
+    let ptr_field = format_ident!("{}__Ptr", ident);
+
+    let from_raw = binding::from_raw_fn_ident();
+    let into_raw = binding::into_raw_fn_ident();
+
+    // NOTE: The raw representation of `()` is a `byte`, so callbacks that don't return
+    // a value still return a placeholder value to Rust.
+    let invoke = if *output == Repr::Unit {
+        quote! {
+            #invoke(#( #temp ),*);
+            return 0;
+        }
+    } else {
+        let output_ty = generate::quote_cs_type_for_repr(output, types);
+        quote! {
+            #output_ty __result = #invoke(#( #temp ),*);
+            #into_raw(__result, out #raw_output __raw_result);
+            return __raw_result;
+        }
+    };
+
+    let trampoline = quote! {
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        internal delegate #raw_output #ident(IntPtr context, out byte threw #( , #raw_input #arg )*);
+
+        [MonoPInvokeCallback(typeof(#ident))]
+        private static #raw_output #invoke_fn(IntPtr context, out byte threw #( , #raw_input #arg )*)
+        {
+            threw = 0;
+            try
+            {
+                var target = (#target_ty)GCHandle.FromIntPtr(context).Target;
+                #(
+                    #from_raw(#arg, out #input_ty #temp);
+                )*
+                #invoke
+            }
+            catch (Exception e)
+            {
+                __SetCallbackException(e);
+                threw = 1;
+                return default(#raw_output);
+            }
+        }
+
+        private static readonly #ident #delegate_field = #invoke_fn;
+        private static readonly IntPtr #ptr_field = Marshal.GetFunctionPointerForDelegate(#delegate_field);
+    };
+
+    (trampoline, ptr_field)
+}
@@ -1,13 +1,20 @@
 //! Code generation for exported named types that are marshaled as handles.
 
-use crate::generate::{binding, func, TypeMap, TypeNameExt};
-use cs_bindgen_shared::{BindingStyle, Method, NamedType, Repr};
+use crate::generate::{
+    self, binding, docs, enumeration,
+    func::{self, Receiver},
+    interface, strukt, task, NamedTypeExt, TypeMap,
+};
+use cs_bindgen_shared::{
+    schematic::Schema, Accessor, BindingStyle, FnArg, Method, NamedType, Repr, TypeName,
+};
+use heck::*;
 use proc_macro2::TokenStream;
 use quote::*;
 
 pub fn quote_drop_fn(export: &NamedType, dll_name: &str) -> TokenStream {
-    let binding_ident = format_ident!("__cs_bindgen_drop__{}", export.type_name.name);
-    let entry_point = binding_ident.to_string();
+    let entry_point = binding::drop_fn_entry_point(&export.type_name);
+    let binding_ident = binding::entry_point_ident(&entry_point);
     quote! {
         [DllImport(
             #dll_name,
@@ -18,50 +25,350 @@ pub fn quote_drop_fn(export: &NamedType, dll_name: &str) -> TokenStream {
 }
 
 /// Quotes the pointer type used for handles, i.e. `IntPtr`.
+///
+/// Handles are passed to and from Rust as raw pointers. Within the generated class,
+/// the pointer is owned by a `SafeHandle`, which is what drops the Rust value once the
+/// object is disposed or garbage collected.
 pub fn quote_handle_ptr() -> TokenStream {
     quote! { IntPtr }
 }
 
-pub fn quote_handle_type(export: &NamedType) -> TokenStream {
-    let ident = export.type_name.ident();
-    let drop_fn = format_ident!("__cs_bindgen_drop__{}", export.type_name.name);
+pub fn quote_handle_type(export: &NamedType, types: &TypeMap) -> TokenStream {
+    let ident = export.ident();
+    let ty = types.type_path(&export.type_name, &ident);
+    let drop_fn = binding::entry_point_ident(&binding::drop_fn_entry_point(&export.type_name));
     let raw_repr = quote_handle_ptr();
 
     let from_raw = binding::from_raw_fn_ident();
     let into_raw = binding::into_raw_fn_ident();
 
     let raw_conversions = binding::wrap_bindings(quote! {
-        internal static void #from_raw(#raw_repr raw, out #ident result)
+        internal static void #from_raw(#raw_repr raw, out #ty result)
         {
-            result = new #ident(raw);
+            result = new #ty(raw);
         }
 
-        internal static void #into_raw(#ident value, out #raw_repr result)
+        // NOTE: Passing a handle by value moves the value into Rust, so the C# object
+        // gives up its handle.
+        internal static void #into_raw(#ty value, out #raw_repr result)
         {
-            result = value._handle;
+            result = value.__TakeHandle();
         }
-    });
 
-    quote! {
-        public unsafe partial class #ident : IDisposable
+        internal static void #from_raw(RawRef raw, out #ty result)
         {
-            internal IntPtr _handle;
+            result = new #ty(raw.Ptr, true);
+        }
 
-            internal #ident(#raw_repr raw)
-            {
-                _handle = raw;
-            }
+        // NOTE: Passing a handle by reference adds a reference to it, so every call must be
+        // paired with a call to `__Release` on the value once Rust is done with it.
+        internal static void #into_raw(#ty value, out RawRef result)
+        {
+            result = new RawRef(value.__AddRef());
+        }
+    });
+
+    // Values of a type that isn't `Send` can only be used and dropped on the thread
+    // that created them, so the generated class checks the current thread before
+    // using the handle.
+    let (thread_id_field, leak_on_other_thread, check_thread_fn, check_thread) = if export.is_send {
+        Default::default()
+    } else {
+        (
+            quote! {
+                internal readonly int _threadId = Environment.CurrentManagedThreadId;
+            },
+            quote! {
+                // NOTE: The value can't be dropped on any other thread, e.g. the
+                // finalizer thread, so it's leaked instead.
+                if (Environment.CurrentManagedThreadId != _threadId)
+                {
+                    return true;
+                }
+            },
+            quote! {
+                internal void __CheckThread()
+                {
+                    if (Environment.CurrentManagedThreadId != _handle._threadId)
+                    {
+                        throw new InvalidOperationException(
+                            GetType().Name + " can only be used from the thread that created it");
+                    }
+                }
+            },
+            quote! { __CheckThread(); },
+        )
+    };
+
+    // Calls that borrow a value that's `Send` but not `Sync` are serialized, since it
+    // can't be used from several threads at once.
+    let serialize_calls = export.is_send && !export.is_sync;
 
-            public void Dispose()
+    let docs = docs::quote_handle_docs(export);
+    let class = types.in_type_namespace(
+        &export.type_name,
+        quote! {
+            #docs
+            public unsafe partial class #ident : IDisposable
             {
-                if (_handle != IntPtr.Zero)
+                // Owns the raw handle and drops the Rust value when released. `SafeHandle` has
+                // a critical finalizer, so the value is dropped even if the object is never
+                // disposed.
+                internal sealed class __Handle : RustHandle
                 {
-                    __bindings.#drop_fn(_handle);
-                    _handle = IntPtr.Zero;
+                    internal __Handle(#raw_repr raw, bool ownsHandle) : base(raw, ownsHandle, nameof(#ident), #serialize_calls) { }
+
+                    #thread_id_field
+
+                    // The panic raised while dropping the value, if any. `Dispose` reports it
+                    // if the value was dropped by that call, otherwise it's discarded along
+                    // with the handle.
+                    internal RustPanicException _dropPanic;
+
+                    // NOTE: This may run on the finalizer thread, or on whichever thread
+                    // releases the last reference, so it must not throw.
+                    protected override bool ReleaseHandle()
+                    {
+                        #leak_on_other_thread
+                        __bindings.#drop_fn(handle);
+                        _dropPanic = __bindings.__TakePanic();
+                        return true;
+                    }
+                }
+
+                internal __Handle _handle;
+
+                // Whether the handle was borrowed from another object, e.g. a `&T` returned
+                // from a method. Borrowed handles don't own the underlying value, so it's not
+                // dropped when the object is disposed. A borrowed handle is only valid as long
+                // as the object it was borrowed from.
+                internal bool _borrowed;
+
+                // The handles of the objects that a borrowed handle may have been borrowed
+                // from, i.e. the receiver and the handles passed by reference to the call that
+                // returned it. The borrowed handle can't be used once any of them has been
+                // disposed or moved into Rust.
+                internal RustHandle[] _owners;
+
+                internal #ident(#raw_repr raw) : this(raw, false) { }
+
+                internal #ident(#raw_repr raw, bool borrowed)
+                {
+                    _handle = new __Handle(raw, !borrowed);
+                    _borrowed = borrowed;
+                }
+
+                // Returns the handle, throwing if the object has already been disposed or its
+                // value has been moved into Rust.
+                internal IntPtr __GetHandle()
+                {
+                    if (_handle.IsClosed || _handle.IsInvalid)
+                    {
+                        throw new ObjectDisposedException(GetType().Name);
+                    }
+
+                    if (_owners != null)
+                    {
+                        foreach (var owner in _owners)
+                        {
+                            if (owner.IsClosed)
+                            {
+                                throw new ObjectDisposedException(GetType().Name);
+                            }
+                        }
+                    }
+
+                    #check_thread
+
+                    return _handle.DangerousGetHandle();
+                }
+
+                // Returns the handle for the duration of a call into Rust, adding a reference to
+                // the `SafeHandle` so that it can't be released until the call completes, even
+                // if the object is disposed or finalized on another thread. Every call must be
+                // paired with a call to `__Release`.
+                internal IntPtr __AddRef()
+                {
+                    var handle = __GetHandle();
+                    _handle.AddRef();
+                    try
+                    {
+                        __AddOwnerRefs(false);
+                    }
+                    catch
+                    {
+                        _handle.Release();
+                        throw;
+                    }
+
+                    return handle;
+                }
+
+                // Same as `__AddRef`, but for an `async` call, which borrows the value until its
+                // task completes. Every call must be paired with a call to `__ReleaseTaskRef`.
+                internal IntPtr __AddTaskRef()
+                {
+                    var handle = __GetHandle();
+                    _handle.AddTaskRef();
+                    try
+                    {
+                        __AddOwnerRefs(true);
+                    }
+                    catch
+                    {
+                        _handle.ReleaseTaskRef();
+                        throw;
+                    }
+
+                    return handle;
+                }
+
+                // Same as `__AddRef`, but for a call that takes `&mut self`. The borrow is
+                // exclusive, so this throws if any other call is using the value. A borrowed
+                // handle points to a shared reference, so it can't be borrowed mutably at all.
+                // Every call must be paired with a call to `__ReleaseMut`.
+                internal IntPtr __AddMutRef()
+                {
+                    var handle = __GetHandle();
+                    if (_borrowed)
+                    {
+                        throw new InvalidOperationException(
+                            "Cannot mutably borrow a borrowed " + GetType().Name);
+                    }
+
+                    _handle.AddMutRef();
+                    return handle;
+                }
+
+                internal void __Release()
+                {
+                    __ReleaseOwnerRefs(false);
+                    _handle.Release();
+                }
+
+                internal void __ReleaseTaskRef()
+                {
+                    __ReleaseOwnerRefs(true);
+                    _handle.ReleaseTaskRef();
+                }
+
+                internal void __ReleaseMut()
+                {
+                    _handle.ReleaseMut();
+                }
+
+                // A borrowed handle also adds a reference to each of its owners, since the
+                // value it points to is dropped along with them. The references added for an
+                // `async` call are task references, see `RustHandle.AddTaskRef`.
+                private void __AddOwnerRefs(bool task)
+                {
+                    if (_owners == null)
+                    {
+                        return;
+                    }
+
+                    var ownersAdded = 0;
+                    try
+                    {
+                        foreach (var owner in _owners)
+                        {
+                            if (task)
+                            {
+                                owner.AddTaskRef();
+                            }
+                            else
+                            {
+                                owner.AddRef();
+                            }
+
+                            ownersAdded += 1;
+                        }
+                    }
+                    catch
+                    {
+                        for (var index = 0; index < ownersAdded; index += 1)
+                        {
+                            if (task)
+                            {
+                                _owners[index].ReleaseTaskRef();
+                            }
+                            else
+                            {
+                                _owners[index].Release();
+                            }
+                        }
+
+                        throw;
+                    }
+                }
+
+                private void __ReleaseOwnerRefs(bool task)
+                {
+                    if (_owners == null)
+                    {
+                        return;
+                    }
+
+                    foreach (var owner in _owners)
+                    {
+                        if (task)
+                        {
+                            owner.ReleaseTaskRef();
+                        }
+                        else
+                        {
+                            owner.Release();
+                        }
+                    }
+                }
+
+                // Takes the handle for a call that consumes the value. The object can't be
+                // used after the call, and disposing it does nothing. Throws if another call,
+                // or a handle borrowed from this one, is still using it.
+                internal IntPtr __TakeHandle()
+                {
+                    __GetHandle();
+                    if (_borrowed)
+                    {
+                        // NOTE: String interpolation can't be used here, since the `$` would be
+                        // separated from the string literal when the tokens are printed.
+                        throw new InvalidOperationException(
+                            "Cannot move out of a borrowed " + GetType().Name);
+                    }
+
+                    return _handle.Take();
+                }
+
+                #check_thread_fn
+
+                // NOTE: The handle is disposed under its lock so that it can't race with
+                // `__TakeHandle` moving the value into Rust.
+                public void Dispose()
+                {
+                    lock (_handle)
+                    {
+                        if (_handle.IsClosed)
+                        {
+                            return;
+                        }
+
+                        #check_thread
+                        _handle.Dispose();
+                    }
+
+                    var panic = _handle._dropPanic;
+                    if (panic != null)
+                    {
+                        _handle._dropPanic = null;
+                        throw panic;
+                    }
                 }
             }
-        }
+        },
+    );
 
+    quote! {
+        #class
         #raw_conversions
     }
 }
@@ -71,15 +378,47 @@ pub fn quote_method_binding(item: &Method, types: &TypeMap) -> TokenStream {
         .get(&item.self_type)
         .unwrap_or_else(|| panic!("No export found for type name {:?}", item.self_type));
 
+    // Determine how to generate the method based on what type of item the self type is.
+    match &self_type_export.binding_style {
+        // For any type that's marshaled by handle we extend the generated class with a
+        // partial class containing the method.
+        BindingStyle::Handle => quote_handle_method(item, types),
+
+        // * For structs exported by value, we generate a partial struct containing the
+        //   method.
+        // * For data-carrying enums exported by value, we generate a partial interface
+        //   containing the method.
+        // * For a C-like enum exported by value, we generate a partial static class with
+        //   an extension method.
+        //
+        // Static methods on value types are never generated as constructors.
+        BindingStyle::Value(_) if item.constructor == Some(true) => {
+            unreachable!("Constructors for value types should have already been rejected")
+        }
+        BindingStyle::Value(Schema::Enum(schema)) => {
+            enumeration::quote_method(item, self_type_export, schema, types)
+        }
+        BindingStyle::Value(_) => strukt::quote_method(item, self_type_export, types),
+    }
+}
+
+/// Returns `true` if a method of a handle type is generated as a constructor.
+///
+/// If the method wasn't explicitly marked as a `constructor` or `factory`, any static
+/// method that returns the self type is treated as a constructor, unless it's `async`.
+pub fn is_constructor(item: &Method) -> bool {
+    let returns_self = item.output == Some(Repr::Named(item.self_type.clone()));
+    match item.constructor {
+        Some(constructor) => constructor,
+        None => item.receiver.is_none() && returns_self && !item.is_async,
+    }
+}
+
+fn quote_handle_method(item: &Method, types: &TypeMap) -> TokenStream {
     // Determine the name of the generated wrapper class based on the self type.
-    let class_ident = item.self_type.ident();
+    let class_ident = types.type_ident(&item.self_type);
 
-    // Use a heuristic to determine if the method should be treated as a constructor.
-    //
-    // TODO: Also support an explicit attribute to specify that a method should (or
-    // should not) be treated as a constructor.
-    let is_constructor =
-        item.receiver.is_none() && item.output == Some(Repr::Named(item.self_type.clone()));
+    let is_constructor = is_constructor(item);
 
     // Generate the right type of function for the exported method. There are three options:
     //
@@ -87,66 +426,273 @@ pub fn quote_method_binding(item: &Method, types: &TypeMap) -> TokenStream {
     // * A non-static method.
     // * A static method.
     let wrapper_fn = if is_constructor {
+        let docs = docs::quote_constructor_docs(item);
         let args = func::quote_args(&item.inputs, types);
+        let raw_repr = quote_handle_ptr();
         let body = func::quote_wrapper_body(
             &item.binding,
             None,
             &item.inputs,
-            Some(&quote! { this._handle }),
+            Some(&quote! { __raw_result }),
+            TokenStream::new(),
+            None,
             types,
         );
 
         quote! {
+            #docs
             public #class_ident(#( #args ),*)
             {
                 unsafe {
+                    #raw_repr __raw_result;
                     #body
+                    this._handle = new __Handle(__raw_result, true);
                 }
             }
         }
-    } else if let Some(_style) = &item.receiver {
-        // TODO: Correctly handle `self` receivers. `&self` and `&mut self` are handled
-        // correctly by passing the handle pointer directly, but in order to handle
-        // `self` we'll need some concept of "consuming" the handle. Likely this will
-        // meaning setting the handle to `null` after calling the function.
-        func::quote_wrapper_fn(
-            &*item.name,
-            &*item.binding,
-            Some(quote! { this._handle }),
-            &item.inputs,
-            item.output.as_ref(),
-            types,
-        )
     } else {
-        func::quote_wrapper_fn(
-            &*item.name,
-            &*item.binding,
+        let receiver = item.receiver.as_ref().map(Receiver::handle);
+
+        // The methods of a trait impl implement the corresponding interface method, so
+        // they use its name and inherit its docs unless they have their own.
+        let (name, docs) = match &item.trait_name {
+            Some(trait_name) => {
+                let name = interface::impl_method_name(types.get_trait(trait_name), &item.name);
+                let docs = match item.docs {
+                    Some(_) => docs::quote_method_docs(item),
+                    None => docs::quote_inherited_docs(),
+                };
+                (name, docs)
+            }
+            None => (
+                func::fn_name(&item.name, item.rename.as_deref()),
+                docs::quote_method_docs(item),
+            ),
+        };
+        let wrapper_fn = if item.is_async {
+            task::quote_async_wrapper_fn(
+                &name,
+                &item.binding,
+                receiver,
+                &item.inputs,
+                item.output.as_ref(),
+                types,
+            )
+        } else {
+            func::quote_wrapper_fn(
+                &name,
+                &*item.binding,
+                receiver,
+                &item.inputs,
+                item.output.as_ref(),
+                item.try_pattern,
+                types,
+            )
+        };
+
+        quote! {
+            #docs
+            #wrapper_fn
+        }
+    };
+
+    types.in_type_namespace(
+        &item.self_type,
+        quote! {
+            partial class #class_ident
+            {
+                #wrapper_fn
+            }
+        },
+    )
+}
+
+/// Groups the methods marked with `#[cs_bindgen(getter)]` or `#[cs_bindgen(setter)]`
+/// into the properties of their self types.
+///
+/// Accessors for the same self type with the same property name are merged into a
+/// single property. Returns an error if the accessors for a property don't agree, or
+/// if properties aren't supported for the self type.
+pub fn collect_properties<'a>(
+    accessors: &[&'a Method],
+    types: &TypeMap,
+) -> Result<Vec<Property<'a>>, failure::Error> {
+    // Group the accessors by property, keeping the properties in the order they were
+    // first declared so that the generated code is deterministic.
+    let mut properties = Vec::<Property<'a>>::new();
+    for &item in accessors {
+        let name = item
+            .rename
+            .as_ref()
+            .map(|rename| rename.to_string())
+            .unwrap_or_else(|| property_name(&item.name).to_camel_case());
+        let index = match properties
+            .iter()
+            .position(|property| property.self_type == &item.self_type && property.name == name)
+        {
+            Some(index) => index,
+            None => {
+                properties.push(Property {
+                    self_type: &item.self_type,
+                    name,
+                    getter: None,
+                    setter: None,
+                });
+                properties.len() - 1
+            }
+        };
+
+        let property = &mut properties[index];
+        let accessor = match item.accessor {
+            Some(Accessor::Getter) => &mut property.getter,
+            Some(Accessor::Setter) => &mut property.setter,
+            None => unreachable!("Method {} is not a property accessor", item.name),
+        };
+        if accessor.is_some() {
+            return Err(failure::format_err!(
+                "Found multiple {:?}s for property {}::{}",
+                item.accessor.unwrap(),
+                item.self_type.name,
+                property.name,
+            ));
+        }
+        *accessor = Some(item);
+    }
+
+    for property in &properties {
+        let is_handle = types
+            .get(property.self_type)
+            .map(|export| export.binding_style == BindingStyle::Handle)
+            .unwrap_or(false);
+        if !is_handle {
+            return Err(failure::format_err!(
+                "Property {}::{} is only supported for types exported as handles",
+                property.self_type.name,
+                property.name,
+            ));
+        }
+
+        // The getter and setter must agree on the type and on whether the property is
+        // static. The raw types may differ, e.g. a getter returning `&str` can be paired
+        // with a setter taking a `String`.
+        if let (Some(getter), Some(setter)) = (property.getter, property.setter) {
+            let getter_ty =
+                generate::quote_cs_type_for_repr(getter.output.as_ref().unwrap(), types);
+            let setter_ty = generate::quote_cs_type_for_repr(&setter.inputs[0].repr, types);
+            if getter_ty.to_string() != setter_ty.to_string() {
+                return Err(failure::format_err!(
+                    "Getter and setter for property {}::{} have different types",
+                    property.self_type.name,
+                    property.name,
+                ));
+            }
+
+            if getter.receiver.is_none() != setter.receiver.is_none() {
+                return Err(failure::format_err!(
+                    "Getter and setter for property {}::{} must both be static or both be \
+                     methods",
+                    property.self_type.name,
+                    property.name,
+                ));
+            }
+        }
+    }
+
+    Ok(properties)
+}
+
+/// Generates the C# properties collected by [`collect_properties`], which are added
+/// to the generated classes.
+///
+/// [`collect_properties`]: fn.collect_properties.html
+pub fn quote_properties(properties: &[Property<'_>], types: &TypeMap) -> TokenStream {
+    properties
+        .iter()
+        .map(|property| quote_property(property, types))
+        .collect()
+}
+
+/// The getter and setter methods for a property of an exported type.
+pub struct Property<'a> {
+    pub self_type: &'a TypeName,
+
+    /// The C# name of the property.
+    pub name: String,
+    getter: Option<&'a Method>,
+    setter: Option<&'a Method>,
+}
+
+fn quote_property(property: &Property<'_>, types: &TypeMap) -> TokenStream {
+    let export = types
+        .get(property.self_type)
+        .unwrap_or_else(|| panic!("No export found for type name {:?}", property.self_type));
+
+    // NOTE: `collect_properties` has already verified that the getter and setter agree
+    // on the type and on whether the property is static.
+    let ty = match (property.getter, property.setter) {
+        (Some(getter), _) => {
+            generate::quote_cs_type_for_repr(getter.output.as_ref().unwrap(), types)
+        }
+        (None, Some(setter)) => generate::quote_cs_type_for_repr(&setter.inputs[0].repr, types),
+        (None, None) => unreachable!("Property must have a getter or a setter"),
+    };
+
+    let is_static = |item: &Method| item.receiver.is_none();
+    let static_ = match (property.getter, property.setter) {
+        (Some(item), _) | (None, Some(item)) if is_static(item) => quote! { static },
+        _ => TokenStream::new(),
+    };
+
+    let receiver = |item: &Method| item.receiver.as_ref().map(Receiver::handle);
+
+    let getter = property.getter.map(|getter| {
+        let block = func::quote_wrapper_block(
+            &getter.binding,
+            receiver(getter),
+            &[],
+            getter.output.as_ref(),
+            types,
+        );
+        quote! { get { #block } }
+    });
+
+    // The new value is passed to the setter as the implicit `value` parameter.
+    let setter = property.setter.map(|setter| {
+        let block = func::quote_wrapper_block(
+            &setter.binding,
+            receiver(setter),
+            &[FnArg::new("value", setter.inputs[0].repr.clone())],
             None,
-            &item.inputs,
-            item.output.as_ref(),
             types,
-        )
-    };
+        );
+        quote! { set { #block } }
+    });
 
-    // Determine how to generate the method based on what type of item the self type is.
-    match &self_type_export.binding_style {
-        // For any type that's marshaled by handle we extend the generated class with a
-        // partial class containing the method.
-        BindingStyle::Handle => {
-            quote! {
-                partial class #class_ident
+    let class_ident = export.ident();
+    let name = format_ident!("{}", property.name);
+    let docs = docs::quote_property_docs(property.getter, property.setter);
+
+    types.in_type_namespace(
+        property.self_type,
+        quote! {
+            partial class #class_ident
+            {
+                #docs
+                public #static_ #ty #name
                 {
-                    #wrapper_fn
+                    #getter
+                    #setter
                 }
             }
-        }
+        },
+    )
+}
 
-        // * For structs exported by value, we generate a partial struct containing the
-        //   method.
-        // * For data-carrying enums exported by value, we generate a partial interface
-        //   containing the method.
-        // * For a C-like enum exported by value, we generate a partial static class with
-        //   an extension method.
-        BindingStyle::Value(_) => todo!("Support methods on non-handle types"),
-    }
+/// Returns the name of the property for an accessor method, i.e. the name of the
+/// method without the `get_` or `set_` prefix.
+fn property_name(method_name: &str) -> &str {
+    method_name
+        .strip_prefix("get_")
+        .or_else(|| method_name.strip_prefix("set_"))
+        .unwrap_or(method_name)
 }
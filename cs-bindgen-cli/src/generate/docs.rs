@@ -0,0 +1,306 @@
+//! Code generation for the XML doc comments of the generated C# items.
+//!
+//! The generated code is built as a Rust token stream, which can't contain comments.
+//! Instead, each doc comment is emitted as a marker ident followed by a string
+//! literal containing the XML for the comment. The markers are replaced with
+//! `/** ... */` comments by [`expand_doc_comments`] once the final code has been
+//! converted to a string.
+//!
+//! The XML is encoded such that converting the string literal to a string doesn't
+//! escape any characters, i.e. quotes, backslashes, and non-printable characters are
+//! replaced with XML character references.
+//!
+//! [`expand_doc_comments`]: fn.expand_doc_comments.html
+
+use crate::generate::func;
+use cs_bindgen_shared::{FnArg, Func, Method, NamedType, TraitMethod};
+use proc_macro2::{Literal, TokenStream};
+use quote::*;
+
+const DOC_COMMENT_MARKER: &str = "__cs_bindgen_doc_comment";
+
+/// Generates the doc comment for a type, field, or enum variant.
+pub fn quote_docs(docs: Option<&str>) -> TokenStream {
+    quote_doc_comment(docs.map(summary).unwrap_or_default())
+}
+
+/// Generates the doc comment for the class generated for a handle type, which also
+/// describes whether the type can be used from multiple threads.
+pub fn quote_handle_docs(export: &NamedType) -> TokenStream {
+    let thread_safety = if !export.is_send {
+        "This type isn't thread-safe. It can only be used from the thread that created \
+         it, and using it from any other thread throws an \
+         <see cref='System.InvalidOperationException'/>."
+    } else if !export.is_sync {
+        "This type isn't thread-safe. It can be used from any thread, but calls that use \
+         it are serialized, so a call made while another thread is using it waits for \
+         that call to complete."
+    } else {
+        "This type is thread-safe."
+    };
+
+    quote_doc_comment(summary_with_remarks(
+        export.docs.as_deref(),
+        &[thread_safety],
+    ))
+}
+
+/// Generates the doc comment for the wrapper function of an exported function.
+pub fn quote_func_docs(item: &Func) -> TokenStream {
+    quote_fn_docs(
+        item.docs.as_deref(),
+        &item.inputs,
+        item.return_docs.as_deref(),
+        item.try_pattern,
+    )
+}
+
+/// Generates the doc comment for the wrapper function of an exported method.
+pub fn quote_method_docs(item: &Method) -> TokenStream {
+    quote_fn_docs(
+        item.docs.as_deref(),
+        &item.inputs,
+        item.return_docs.as_deref(),
+        item.try_pattern,
+    )
+}
+
+/// Generates the doc comment for a method of the interface generated for an exported
+/// trait.
+pub fn quote_trait_method_docs(item: &TraitMethod) -> TokenStream {
+    quote_fn_docs(
+        item.docs.as_deref(),
+        &item.inputs,
+        item.return_docs.as_deref(),
+        false,
+    )
+}
+
+/// Generates a doc comment that inherits the docs of the interface member being
+/// implemented.
+pub fn quote_inherited_docs() -> TokenStream {
+    quote_doc_comment("<inheritdoc/>".into())
+}
+
+/// Generates the doc comment for a constructor generated for an exported method.
+///
+/// Constructors don't have a return value, so the `# Returns` docs are omitted.
+pub fn quote_constructor_docs(item: &Method) -> TokenStream {
+    quote_fn_docs(item.docs.as_deref(), &item.inputs, None, false)
+}
+
+/// Generates the doc comment for a property, using the docs for the getter if it has
+/// any and the docs for the setter otherwise.
+pub fn quote_property_docs(getter: Option<&Method>, setter: Option<&Method>) -> TokenStream {
+    let docs = getter
+        .and_then(|getter| getter.docs.as_deref())
+        .or_else(|| setter.and_then(|setter| setter.docs.as_deref()));
+    let value = getter.and_then(|getter| getter.return_docs.as_deref());
+
+    let mut xml = docs.map(summary).unwrap_or_default();
+    if let Some(value) = value {
+        xml.push_str(&format!("<value>{}</value>", paragraphs(value)));
+    }
+
+    quote_doc_comment(xml)
+}
+
+fn quote_fn_docs(
+    docs: Option<&str>,
+    inputs: &[FnArg],
+    return_docs: Option<&str>,
+    try_pattern: bool,
+) -> TokenStream {
+    let mut xml = docs.map(summary).unwrap_or_default();
+
+    for arg in inputs {
+        if let Some(docs) = &arg.docs {
+            xml.push_str(&format!(
+                "<param name='{}'>{}</param>",
+                func::arg_ident(arg),
+                paragraphs(docs),
+            ));
+        }
+    }
+
+    // The "try" wrapper returns a `bool` indicating success rather than the return
+    // value of the original function, so the `# Returns` docs don't apply to it.
+    if let (Some(docs), false) = (return_docs, try_pattern) {
+        xml.push_str(&format!("<returns>{}</returns>", paragraphs(docs)));
+    }
+
+    quote_doc_comment(xml)
+}
+
+/// Replaces the doc comment markers in the generated code with the corresponding
+/// `/** ... */` doc comments.
+pub fn expand_doc_comments(generated: &str) -> String {
+    let marker = format!("{} \"", DOC_COMMENT_MARKER);
+
+    let mut result = String::with_capacity(generated.len());
+    let mut remaining = generated;
+    while let Some(start) = remaining.find(&marker) {
+        let (before, after) = remaining.split_at(start);
+        let after = &after[marker.len()..];
+        let end = after
+            .find('"')
+            .expect("Unterminated doc comment in generated code");
+
+        result.push_str(before);
+        result.push_str("/** ");
+        result.push_str(&after[..end]);
+        result.push_str(" */");
+
+        remaining = &after[end + 1..];
+    }
+    result.push_str(remaining);
+
+    result
+}
+
+fn quote_doc_comment(xml: String) -> TokenStream {
+    if xml.is_empty() {
+        return TokenStream::new();
+    }
+
+    // Replace any characters that would otherwise be escaped in the string literal.
+    let mut encoded = String::with_capacity(xml.len());
+    for c in xml.chars() {
+        if c == '"' || c == '\\' || (c != '\'' && c.escape_debug().count() != 1) {
+            encoded.push_str(&format!("&#x{:X};", c as u32));
+        } else {
+            encoded.push(c);
+        }
+    }
+
+    let marker = format_ident!("{}", DOC_COMMENT_MARKER);
+    let literal = Literal::string(&encoded);
+    quote! { #marker #literal }
+}
+
+/// Generates the `<summary>` for a doc comment, which contains the first paragraph
+/// of the docs. Any remaining paragraphs are put in the `<remarks>` section.
+fn summary(docs: &str) -> String {
+    summary_with_remarks(Some(docs), &[])
+}
+
+/// Generates the `<summary>` for a doc comment like [`summary`], adding the
+/// paragraphs in `extra_remarks` to the end of the `<remarks>` section. The extra
+/// paragraphs must already be valid XML.
+///
+/// [`summary`]: fn.summary.html
+fn summary_with_remarks(docs: Option<&str>, extra_remarks: &[&str]) -> String {
+    let mut paragraphs = docs.map(split_paragraphs).unwrap_or_default().into_iter();
+
+    let mut xml = paragraphs
+        .next()
+        .map(|paragraph| format!("<summary>{}</summary>", paragraph))
+        .unwrap_or_default();
+    let remarks = paragraphs
+        .chain(extra_remarks.iter().map(|&paragraph| paragraph.into()))
+        .map(|paragraph| format!("<para>{}</para>", paragraph))
+        .collect::<String>();
+    if !remarks.is_empty() {
+        xml.push_str(&format!("<remarks>{}</remarks>", remarks));
+    }
+
+    xml
+}
+
+/// Converts docs into XML, wrapping each paragraph in a `<para>` element if there is
+/// more than one.
+fn paragraphs(docs: &str) -> String {
+    let paragraphs = split_paragraphs(docs);
+    if paragraphs.len() == 1 {
+        return paragraphs.into_iter().collect();
+    }
+
+    paragraphs
+        .into_iter()
+        .map(|paragraph| format!("<para>{}</para>", paragraph))
+        .collect()
+}
+
+/// Splits markdown docs into paragraphs, converting each paragraph to XML.
+///
+/// The lines of a paragraph are joined into a single line. Fenced code blocks are
+/// converted to `<code>` elements, preserving their line breaks, and inline code is
+/// converted to `<c>` elements. Headings, e.g. `# Panics`, are put in their own
+/// paragraph without the leading `#`s.
+fn split_paragraphs(docs: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut lines = Vec::new();
+    let mut code_block = None;
+
+    for line in docs.lines() {
+        if line.trim_start().starts_with("```") {
+            match code_block.take() {
+                Some(code) => paragraphs.push(format!("<code>{}</code>", code)),
+                None => {
+                    push_paragraph(&mut paragraphs, &mut lines);
+                    code_block = Some(String::new());
+                }
+            }
+        } else if let Some(code) = &mut code_block {
+            if !code.is_empty() {
+                code.push('\n');
+            }
+            code.push_str(&escape(line));
+        } else if line.trim().is_empty() {
+            push_paragraph(&mut paragraphs, &mut lines);
+        } else if line.starts_with('#') {
+            push_paragraph(&mut paragraphs, &mut lines);
+            lines.push(line.trim_start_matches('#').trim());
+            push_paragraph(&mut paragraphs, &mut lines);
+        } else {
+            lines.push(line.trim());
+        }
+    }
+
+    // Treat an unterminated code block as if it ended with the docs.
+    if let Some(code) = code_block {
+        paragraphs.push(format!("<code>{}</code>", code));
+    }
+    push_paragraph(&mut paragraphs, &mut lines);
+
+    paragraphs
+}
+
+fn push_paragraph(paragraphs: &mut Vec<String>, lines: &mut Vec<&str>) {
+    if lines.is_empty() {
+        return;
+    }
+
+    let text = lines.join(" ");
+    lines.clear();
+
+    // Convert inline code spans, but only if the backticks are balanced.
+    let segments = text.split('`').collect::<Vec<_>>();
+    let xml = if segments.len() % 2 == 1 {
+        segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                if index % 2 == 1 {
+                    format!("<c>{}</c>", escape(segment))
+                } else {
+                    escape(segment)
+                }
+            })
+            .collect()
+    } else {
+        escape(&text)
+    };
+
+    paragraphs.push(xml);
+}
+
+/// Escapes text for use in an XML doc comment.
+///
+/// The `/` in `*/` is also escaped, since it would otherwise end the comment.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace("*/", "*&#x2F;")
+}
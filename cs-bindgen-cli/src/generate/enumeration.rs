@@ -1,7 +1,14 @@
 //! Code generation for exported enum types that are marshaled by value.
 
-use crate::generate::{binding, quote_primitive_type, strukt, TypeMap, TypeNameExt};
-use cs_bindgen_shared::{schematic::Enum, schematic::Variant, BindingStyle, NamedType, TypeName};
+use crate::generate::{
+    self, binding, docs,
+    func::{self, Receiver},
+    quote_primitive_type, strukt, task, NamedTypeExt, TypeMap,
+};
+use cs_bindgen_shared::{
+    schematic::Enum, schematic::Variant, BindingStyle, Method, NamedType, ReceiverStyle, Repr,
+    TypeName,
+};
 use proc_macro2::{Literal, TokenStream};
 use quote::*;
 use syn::Ident;
@@ -17,10 +24,10 @@ pub fn quote_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> TokenSt
     let generated = if schema.has_data() {
         quote_complex_enum(export, schema, types)
     } else {
-        quote_simple_enum(export, schema)
+        quote_simple_enum(export, schema, types)
     };
 
-    let repr = quote_type_reference(schema);
+    let repr = types.type_path(&export.type_name, quote_type_reference(export, schema));
     let from_raw = binding::from_raw_fn_ident();
     let into_raw = binding::into_raw_fn_ident();
 
@@ -28,8 +35,8 @@ pub fn quote_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> TokenSt
     // binding style is by-value.
     let raw_repr = binding::raw_type_from_schema(&export.schema().unwrap(), types);
 
-    let from_raw_impl = from_raw_impl(export, schema);
-    let into_raw_impl = into_raw_impl(export, schema);
+    let from_raw_impl = from_raw_impl(export, schema, types);
+    let into_raw_impl = into_raw_impl(export, schema, types);
     let raw_conversions = binding::wrap_bindings(quote! {
         internal static void #from_raw(#raw_repr raw, out #repr result)
         {
@@ -48,11 +55,11 @@ pub fn quote_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> TokenSt
     }
 }
 
-pub fn quote_type_reference(schema: &Enum) -> TokenStream {
+pub fn quote_type_reference(export: &NamedType, schema: &Enum) -> TokenStream {
     if schema.has_data() {
-        format_ident!("I{}", &*schema.name.name).into_token_stream()
+        interface_name(export).into_token_stream()
     } else {
-        format_ident!("{}", &*schema.name.name).into_token_stream()
+        export.ident().into_token_stream()
     }
 }
 
@@ -69,11 +76,11 @@ pub fn quote_discriminant_type(schema: &Enum) -> TokenStream {
         .unwrap_or_else(|| quote! { IntPtr })
 }
 
-fn from_raw_impl(export: &NamedType, schema: &Enum) -> TokenStream {
+fn from_raw_impl(export: &NamedType, schema: &Enum, types: &TypeMap) -> TokenStream {
     // For C-like enums, the conversion is just casting the raw discriminant value to
     // the C# enum type.
     if !schema.has_data() {
-        let cs_repr = quote_type_reference(schema);
+        let cs_repr = types.type_path(&export.type_name, quote_type_reference(export, schema));
         return quote! { result = (#cs_repr)raw; };
     }
 
@@ -83,8 +90,8 @@ fn from_raw_impl(export: &NamedType, schema: &Enum) -> TokenStream {
         .enumerate()
         .map(|(index, _)| Literal::usize_unsuffixed(index));
 
-    let convert_variants = schema.variants.iter().map(|variant| {
-        let cs_repr = variant_struct_type_ref(export, variant);
+    let convert_variants = schema.variants.iter().enumerate().map(|(index, variant)| {
+        let cs_repr = variant_struct_type_ref(export, index, variant, types);
 
         if variant.is_empty() {
             quote! {
@@ -113,7 +120,7 @@ fn from_raw_impl(export: &NamedType, schema: &Enum) -> TokenStream {
     }
 }
 
-fn into_raw_impl(export: &NamedType, schema: &Enum) -> TokenStream {
+fn into_raw_impl(export: &NamedType, schema: &Enum, types: &TypeMap) -> TokenStream {
     // For C-like enums, the conversion is just casting the C# enum value to the
     // appropriate discriminant type.
     if !schema.has_data() {
@@ -134,7 +141,8 @@ fn into_raw_impl(export: &NamedType, schema: &Enum) -> TokenStream {
     let variant_type = schema
         .variants
         .iter()
-        .map(|variant| variant_struct_type_ref(export, variant));
+        .enumerate()
+        .map(|(index, variant)| variant_struct_type_ref(export, index, variant, types));
 
     let discriminant = schema
         .variants
@@ -149,7 +157,7 @@ fn into_raw_impl(export: &NamedType, schema: &Enum) -> TokenStream {
             quote! {}
         } else {
             let variant_name = format_ident!("{}", variant.name());
-            let raw_variant_type = raw_variant_struct_type_ref(export, variant);
+            let raw_variant_type = raw_variant_struct_type_ref(export, variant, types);
             quote! {
                 #variant_name = new #raw_variant_type(#variant_name)
             }
@@ -174,16 +182,16 @@ fn into_raw_impl(export: &NamedType, schema: &Enum) -> TokenStream {
     }
 }
 
-fn quote_simple_enum(export: &NamedType, schema: &Enum) -> TokenStream {
-    let ident = export.type_name.ident();
-    let variants = schema.variants.iter().map(|variant| {
+fn quote_simple_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> TokenStream {
+    let ident = export.ident();
+    let variants = schema.variants.iter().enumerate().map(|(index, variant)| {
         let (name, discriminant) = match variant {
             Variant::Unit { name, discriminant } => (name, discriminant),
 
             _ => panic!("Simple enum can only have unit variants"),
         };
 
-        let variant_ident = format_ident!("{}", &**name);
+        let variant_ident = format_ident!("{}", variant_rename(export, index).unwrap_or(name));
         let discriminant = match discriminant {
             Some(discriminant) => {
                 let lit = syn::parse_str::<syn::Expr>(&discriminant.to_string())
@@ -193,16 +201,24 @@ fn quote_simple_enum(export: &NamedType, schema: &Enum) -> TokenStream {
             None => TokenStream::new(),
         };
 
+        let docs = docs::quote_docs(variant_docs(export, index));
+
         quote! {
+            #docs
             #variant_ident #discriminant
         }
     });
 
-    quote! {
-        public enum #ident {
-            #( #variants ),*
-        }
-    }
+    let docs = docs::quote_docs(export.docs.as_deref());
+    types.in_type_namespace(
+        &export.type_name,
+        quote! {
+            #docs
+            public enum #ident {
+                #( #variants ),*
+            }
+        },
+    )
 }
 
 fn quote_complex_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> TokenStream {
@@ -211,8 +227,8 @@ fn quote_complex_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> Tok
         "Right now we only support exporting complex enums by value",
     );
 
-    let wrapper_class = export.type_name.ident();
-    let interface = format_ident!("I{}", export.type_name.name);
+    let wrapper_class = wrapper_class_name(export);
+    let interface = interface_name(export);
 
     // Generate the declarations for the fields of the raw union. There's one field for
     // each data-carrying variant of the enum, i.e. unit-like variants don't have a
@@ -223,8 +239,10 @@ fn quote_complex_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> Tok
             let binding_ty = raw_variant_struct_name(&export.type_name, variant.name());
             let name = format_ident!("{}", variant.name());
 
+            let binding_ty =
+                types.type_path(&export.type_name, quote! { #wrapper_class.#binding_ty });
             Some(quote! {
-                #wrapper_class.#binding_ty #name
+                #binding_ty #name
             })
         }
     });
@@ -235,38 +253,48 @@ fn quote_complex_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> Tok
     // * The public struct that acts as the C# representation of the variant.
     // * The raw representation which is kept internal and used as a field of the raw
     //   union for the enum.
-    let variant_structs = schema.variants.iter().map(|variant| {
-        let ident = variant_struct_name(variant);
+    let variant_structs = schema.variants.iter().enumerate().map(|(index, variant)| {
+        let ident = variant_struct_name(export, index, variant);
         let raw_ident = raw_variant_struct_name(&export.type_name, variant.name());
 
         let fields = variant.fields().collect::<Vec<_>>();
-
-        let struct_fields = strukt::struct_fields(&fields, types);
+        let field_ident = strukt::field_idents(&fields, |field_index| {
+            export
+                .variant_renames(index)
+                .and_then(|renames| renames.field_rename(field_index))
+        });
+
+        let struct_fields = strukt::struct_fields(
+            &fields,
+            &field_ident,
+            |field_index| {
+                export
+                    .variant_docs(index)
+                    .and_then(|docs| docs.field_doc(field_index))
+            },
+            types,
+        );
+        let docs = docs::quote_docs(variant_docs(export, index));
 
         // Generate a basic constructor for the user-facing struct, but only if the
         // struct has fields since we're not allowed to generate an explicit parameter-
         // less constructor for structs in C#.
         let struct_constructor = if !variant.is_empty() {
-            strukt::struct_constructor(&ident, &fields, types)
+            strukt::struct_constructor(&ident, &fields, &field_ident, types)
         } else {
             quote! {}
         };
 
-        let field_ident = fields
-            .iter()
-            .enumerate()
-            .map(|(index, field)| strukt::field_ident(field.name, index))
-            .collect::<Vec<_>>();
-
         let bindings = binding::bindings_class_ident();
         let from_raw_fn = binding::from_raw_fn_ident();
         let into_raw_fn = binding::into_raw_fn_ident();
 
-        let raw_fields = binding::raw_struct_fields(&fields, types);
+        let raw_fields = binding::raw_struct_fields(&fields, &field_ident, types);
 
         quote! {
             // Generate the C# struct for the variant.
-            public struct #ident : #interface
+            #docs
+            public partial struct #ident : #interface
             {
                 #struct_fields
                 #struct_constructor
@@ -302,16 +330,29 @@ fn quote_complex_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> Tok
     let raw_struct = binding::raw_ident(&export.type_name);
     let union_struct = union_struct_name(&export.type_name);
 
-    quote! {
-        // Generate an interface for the enum.
-        public interface #interface { }
+    // The interface and the wrapper class are generated in the namespace for the type,
+    // but the raw struct and union are kept in the root namespace along with the other
+    // raw types.
+    let docs = docs::quote_docs(export.docs.as_deref());
+    let user_types = types.in_type_namespace(
+        &export.type_name,
+        quote! {
+            // Generate an interface for the enum.
+            #docs
+            public partial interface #interface { }
 
-        // Generate wrapper class in order to namespace the variants.
-        public static class #wrapper_class
-        {
-            // Generate the struct declarations for each variant of the enum.
-            #( #variant_structs )*
-        }
+            // Generate wrapper class in order to namespace the variants.
+            #docs
+            public static partial class #wrapper_class
+            {
+                // Generate the struct declarations for each variant of the enum.
+                #( #variant_structs )*
+            }
+        },
+    );
+
+    quote! {
+        #user_types
 
         // Generate the raw struct, which contains the discriminant and a union of all the
         // possible variants. This needs to match the `RawEnum<D, V>` type on the Rust side.
@@ -352,9 +393,148 @@ fn quote_complex_enum(export: &NamedType, schema: &Enum, types: &TypeMap) -> Tok
     }
 }
 
+/// Generates a method on an enum that is marshaled by value.
+///
+/// C# enums can't have methods, so methods on C-like enums are generated as
+/// extension methods in a static `{Name}Extensions` class. Static methods are added
+/// to the same class.
+///
+/// For data-carrying enums, the method is generated as a static method on the
+/// wrapper class that takes the receiver as its first parameter. For `&self` and
+/// `self` methods, the method is also added to the enum's interface and implemented
+/// by each variant by forwarding to the static method. A `&mut self` method may
+/// change which variant the value is, so it's only available as a static method
+/// taking the receiver by `ref`.
+pub fn quote_method(
+    item: &Method,
+    export: &NamedType,
+    schema: &Enum,
+    types: &TypeMap,
+) -> TokenStream {
+    let self_repr = Repr::Named(item.self_type.clone());
+    let self_ty = generate::quote_cs_type_for_repr(&self_repr, types);
+    let raw_ty = binding::raw_type_from_repr(&self_repr, types);
+
+    // Both C-like and data-carrying enums pass the receiver as an explicit `self`
+    // parameter, though only extension methods use `this`.
+    let this_ = if schema.has_data() {
+        quote! {}
+    } else {
+        quote! { this }
+    };
+    let receiver = item.receiver.as_ref().map(|style| {
+        let param = match style {
+            ReceiverStyle::Move | ReceiverStyle::Ref => quote! { #this_ #self_ty self },
+            ReceiverStyle::RefMut => quote! { #this_ ref #self_ty self },
+        };
+
+        Receiver::value(style, quote! { self }, Some(param), raw_ty.clone())
+    });
+
+    // NOTE: Only static methods of value types can be `async`, which is verified before
+    // the bindings are generated.
+    let name = func::fn_name(&item.name, item.rename.as_deref());
+    let docs = docs::quote_method_docs(item);
+    let wrapper_fn = if item.is_async {
+        task::quote_async_wrapper_fn(
+            &name,
+            &item.binding,
+            receiver,
+            &item.inputs,
+            item.output.as_ref(),
+            types,
+        )
+    } else {
+        func::quote_wrapper_fn(
+            &name,
+            &item.binding,
+            receiver,
+            &item.inputs,
+            item.output.as_ref(),
+            item.try_pattern,
+            types,
+        )
+    };
+
+    if !schema.has_data() {
+        let extensions_class = format_ident!("{}Extensions", export.ident());
+        return types.in_type_namespace(
+            &export.type_name,
+            quote! {
+                public static partial class #extensions_class
+                {
+                    #docs
+                    #wrapper_fn
+                }
+            },
+        );
+    }
+
+    let wrapper_class = wrapper_class_name(export);
+    let interface = interface_name(export);
+
+    // Add the method to the interface and each of the variant structs, unless the
+    // method is static or takes `&mut self`.
+    let interface_methods = match &item.receiver {
+        Some(ReceiverStyle::Move) | Some(ReceiverStyle::Ref) => {
+            let (decl, forward) = func::quote_forwarding_fn(
+                &func::fn_name(&item.name, item.rename.as_deref()),
+                &item.inputs,
+                item.output.as_ref(),
+                item.try_pattern,
+                &types.type_path(&export.type_name, &wrapper_class),
+                types,
+            );
+            let variant = schema
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(index, variant)| variant_struct_name(export, index, variant));
+
+            quote! {
+                partial interface #interface
+                {
+                    #docs
+                    #decl
+                }
+
+                partial class #wrapper_class
+                {
+                    #(
+                        partial struct #variant
+                        {
+                            #forward
+                        }
+                    )*
+                }
+            }
+        }
+
+        Some(ReceiverStyle::RefMut) | None => quote! {},
+    };
+
+    types.in_type_namespace(
+        &export.type_name,
+        quote! {
+            partial class #wrapper_class
+            {
+                #docs
+                #wrapper_fn
+            }
+
+            #interface_methods
+        },
+    )
+}
+
 /// Returns the name of the wrapper class generated for for the specified exported type.
 fn wrapper_class_name(export: &NamedType) -> Ident {
-    format_ident!("{}", &*export.type_name.name)
+    export.ident()
+}
+
+/// Returns the name of the interface generated for a data-carrying enum.
+fn interface_name(export: &NamedType) -> Ident {
+    format_ident!("I{}", export.ident())
 }
 
 /// Returns the name for the C# struct representing the specified variant.
@@ -365,17 +545,37 @@ fn wrapper_class_name(export: &NamedType) -> Ident {
 /// [`variant_struct_type_ref`] instead.
 ///
 /// [`variant_struct_type_ref`]: fn.variant_struct_type_ref.html
-fn variant_struct_name(variant: &Variant) -> Ident {
-    format_ident!("{}", variant.name())
+fn variant_struct_name(export: &NamedType, index: usize, variant: &Variant) -> Ident {
+    format_ident!(
+        "{}",
+        variant_rename(export, index).unwrap_or(variant.name())
+    )
+}
+
+/// Returns the doc comment for the variant at `index`, if any.
+fn variant_docs(export: &NamedType, index: usize) -> Option<&str> {
+    export.variant_docs(index)?.docs.as_deref()
+}
+
+/// Returns the name specified for the variant at `index` with
+/// `#[cs_bindgen(rename = "...")]`, if any.
+fn variant_rename(export: &NamedType, index: usize) -> Option<&str> {
+    export.variant_renames(index)?.rename.as_deref()
 }
 
 /// Generates a type reference to the C# type for the specified enum variant.
-fn variant_struct_type_ref(export: &NamedType, variant: &Variant) -> TokenStream {
+fn variant_struct_type_ref(
+    export: &NamedType,
+    index: usize,
+    variant: &Variant,
+    types: &TypeMap,
+) -> TokenStream {
     let wrapper_class = wrapper_class_name(export);
-    let variant_struct_name = variant_struct_name(variant);
-    quote! {
-        global::#wrapper_class.#variant_struct_name
-    }
+    let variant_struct_name = variant_struct_name(export, index, variant);
+    types.type_path(
+        &export.type_name,
+        quote! { #wrapper_class.#variant_struct_name },
+    )
 }
 
 fn raw_variant_struct_name(type_name: &TypeName, variant_name: &str) -> Ident {
@@ -383,14 +583,19 @@ fn raw_variant_struct_name(type_name: &TypeName, variant_name: &str) -> Ident {
     format_ident!("{}__{}", raw_name, variant_name)
 }
 
-fn raw_variant_struct_type_ref(export: &NamedType, variant: &Variant) -> TokenStream {
+fn raw_variant_struct_type_ref(
+    export: &NamedType,
+    variant: &Variant,
+    types: &TypeMap,
+) -> TokenStream {
     let wrapper_class = wrapper_class_name(export);
     let raw_variant_struct_name = raw_variant_struct_name(&export.type_name, &variant.name());
-    quote! {
-        global::#wrapper_class.#raw_variant_struct_name
-    }
+    types.type_path(
+        &export.type_name,
+        quote! { #wrapper_class.#raw_variant_struct_name },
+    )
 }
 
 fn union_struct_name(type_name: &TypeName) -> Ident {
-    format_ident!("{}_Data_Raw", type_name.name)
+    format_ident!("__{}_Data_Raw", binding::mangled_type_name(type_name))
 }
@@ -1,30 +1,234 @@
 //! Code generation for exported functions and methods.
 
 use super::quote_cs_type_for_repr;
-use crate::generate::{binding, TypeMap};
+use crate::generate::{binding, result, TypeMap};
 use cs_bindgen_shared::*;
 use heck::*;
 use proc_macro2::TokenStream;
 use quote::*;
+use std::iter;
+use syn::Ident;
 
+/// The receiver of a generated wrapper method, i.e. how the C# value is passed as the
+/// first argument to the raw binding function.
+pub struct Receiver {
+    /// An explicit parameter declaration for the receiver, e.g. the `this` parameter of
+    /// an extension method. If `None`, the wrapper is an instance method.
+    pub param: Option<TokenStream>,
+
+    /// Statements that prepare the raw receiver before invoking the raw binding.
+    pub setup: TokenStream,
+
+    /// The receiver argument passed to the raw binding.
+    pub arg: TokenStream,
+
+    /// Statements that run once the call has completed without panicking, e.g. to copy
+    /// changes made by a `&mut self` method back into a value type.
+    pub write_back: TokenStream,
+
+    /// Statements that run once the call has completed, even if it threw, e.g. to
+    /// release the reference added to a handle by `setup`. If not empty, the call is
+    /// wrapped in a `try`/`finally` block.
+    pub release: TokenStream,
+
+    /// The argument that forwards the receiver to another overload of the wrapper, if
+    /// the receiver is passed as an explicit parameter.
+    pub forward_arg: Option<TokenStream>,
+
+    /// The handle of a borrowed receiver, which a borrowed handle returned from the
+    /// call is tied to.
+    pub owner: Option<TokenStream>,
+}
+
+impl Receiver {
+    /// Creates the receiver for an instance method on a type that is marshaled as a
+    /// handle.
+    ///
+    /// A `self` receiver consumes the value, so the object gives up its handle before
+    /// making the call. `&self` and `&mut self` borrow the handle, adding a reference to
+    /// it for the duration of the call so that it can't be released by a concurrent
+    /// `Dispose` or by the finalizer while Rust is still using it. A `&mut self` borrow
+    /// is exclusive, so it's refused while the handle is borrowed by another call,
+    /// including an `async` call whose task hasn't completed yet.
+    pub fn handle(style: &ReceiverStyle) -> Self {
+        let (setup, release, owner) = match style {
+            ReceiverStyle::Move => (
+                quote! { IntPtr __self = this.__TakeHandle(); },
+                TokenStream::new(),
+                None,
+            ),
+            ReceiverStyle::Ref => (
+                quote! { IntPtr __self = this.__AddRef(); },
+                quote! { this.__Release(); },
+                Some(quote! { this._handle }),
+            ),
+            ReceiverStyle::RefMut => (
+                quote! { IntPtr __self = this.__AddMutRef(); },
+                quote! { this.__ReleaseMut(); },
+                Some(quote! { this._handle }),
+            ),
+        };
+
+        Self {
+            param: None,
+            setup,
+            arg: quote! { __self },
+            write_back: TokenStream::new(),
+            release,
+            forward_arg: None,
+            owner,
+        }
+    }
+
+    /// Creates the receiver for a method on a type that is marshaled by value.
+    ///
+    /// The value is converted to its raw representation before the call. For
+    /// `&mut self` methods the raw value is passed by reference so that Rust can
+    /// write the modified value back, after which it's converted back into `value`.
+    pub fn value(
+        style: &ReceiverStyle,
+        value: TokenStream,
+        param: Option<TokenStream>,
+        raw_ty: TokenStream,
+    ) -> Self {
+        let bindings = binding::bindings_class_ident();
+        let from_raw = binding::from_raw_fn_ident();
+        let into_raw = binding::into_raw_fn_ident();
+
+        let setup = quote! {
+            #bindings.#into_raw(#value, out #raw_ty __self);
+        };
+
+        let (arg, write_back) = match style {
+            ReceiverStyle::Move | ReceiverStyle::Ref => (quote! { __self }, TokenStream::new()),
+            ReceiverStyle::RefMut => (
+                quote! { ref __self },
+                quote! { #bindings.#from_raw(__self, out #value); },
+            ),
+        };
+        let forward_arg = param.as_ref().map(|_| match style {
+            ReceiverStyle::Move | ReceiverStyle::Ref => value.clone(),
+            ReceiverStyle::RefMut => quote! { ref #value },
+        });
+
+        Self {
+            param,
+            setup,
+            arg,
+            write_back,
+            release: TokenStream::new(),
+            forward_arg,
+            owner: None,
+        }
+    }
+
+    /// Returns `true` if the wrapper method should be static, i.e. if the receiver is
+    /// passed as an explicit parameter.
+    pub fn is_static(receiver: Option<&Self>) -> bool {
+        receiver
+            .map(|receiver| receiver.param.is_some())
+            .unwrap_or(true)
+    }
+}
+
+/// Returns the C# name for an exported function or method.
+///
+/// This is the name specified with `#[cs_bindgen(rename = "...")]` if there is one.
+/// Otherwise, the original function name is going to be in `snake_case`, so we
+/// convert it to `CamelCase` to keep with C# naming conventions.
+pub fn fn_name(name: &str, rename: Option<&str>) -> String {
+    rename
+        .map(Into::into)
+        .unwrap_or_else(|| name.to_camel_case())
+}
+
+/// Returns the identifier for an argument of a C# wrapper function.
+///
+/// This is the name specified with `#[cs_bindgen(rename = "...")]` if there is one,
+/// otherwise the original argument name is converted to `mixedCase`.
+pub fn arg_ident(arg: &FnArg) -> Ident {
+    match &arg.rename {
+        Some(rename) => format_ident!("{}", &**rename),
+        None => format_ident!("{}", arg.name.to_mixed_case()),
+    }
+}
+
+/// Generates a C# wrapper function that calls the raw binding function.
+///
+/// `name` is the C# name of the function, as returned by [`fn_name`].
+///
+/// [`fn_name`]: fn.fn_name.html
 pub fn quote_wrapper_fn<'a>(
     name: &str,
     binding: &str,
-    receiver: Option<TokenStream>,
+    receiver: Option<Receiver>,
     inputs: &[FnArg],
     output: Option<&Repr>,
+    try_pattern: bool,
     types: &'a TypeMap,
 ) -> TokenStream {
-    // Determine the name of the wrapper function. The original function name is
-    // going to be in `snake_case`, so we need to convert it to `CamelCase` to keep
-    // with C# naming conventions.
-    let name = format_ident!("{}", name.to_camel_case());
+    // NOTE: `generate_bindings` rejects `try_pattern` for functions that don't return a
+    // `Result`, so `try_pattern` always comes with a `Result` output here.
+    debug_assert!(
+        !try_pattern || matches!(output, Some(Repr::Result { .. })),
+        "Function {} must return a `Result` in order to use `try_pattern`",
+        name,
+    );
+    if let (true, Some(Repr::Result { ok, err })) = (try_pattern, output) {
+        return quote_try_wrapper_fn(name, binding, receiver, inputs, ok, err, types);
+    }
+
+    let name = format_ident!("{}", name);
 
     let return_ty = match output {
         Some(output) => quote_cs_type_for_repr(&output, types),
         None => quote! { void },
     };
 
+    // Determine if the function should be static or not based on whether or not it has
+    // a receiver.
+    let static_ = if Receiver::is_static(receiver.as_ref()) {
+        quote! { static }
+    } else {
+        TokenStream::default()
+    };
+
+    let receiver_param = receiver
+        .as_ref()
+        .and_then(|receiver| receiver.param.clone());
+    let list_overload = quote_list_overload(
+        &name,
+        &return_ty,
+        output
+            .map(|output| !result::is_unit_result(output))
+            .unwrap_or(false),
+        receiver.as_ref(),
+        inputs,
+        &[],
+        types,
+    );
+    let args = receiver_param.into_iter().chain(quote_args(inputs, types));
+    let block = quote_wrapper_block(binding, receiver, inputs, output, types);
+
+    quote! {
+        public #static_ #return_ty #name(#( #args ),*)
+        {
+            #block
+        }
+
+        #list_overload
+    }
+}
+
+/// Generates the body of a wrapper function, i.e. an `unsafe` block that converts
+/// the arguments, invokes the raw binding, and converts and returns the result.
+pub fn quote_wrapper_block(
+    binding: &str,
+    receiver: Option<Receiver>,
+    inputs: &[FnArg],
+    output: Option<&Repr>,
+    types: &TypeMap,
+) -> TokenStream {
     // Generate the declaration for the output variable and return expression. We need
     // to treat `void` returns as a special case, since C# won't let you declare values
     // with type `void` (*sigh*).
@@ -41,48 +245,216 @@ pub fn quote_wrapper_fn<'a>(
     let binding_class = binding::bindings_class_ident();
     let from_raw = binding::from_raw_fn_ident();
 
-    let ret_expr = match output {
-        Some(_) => quote! {
-            #binding_class.#from_raw(#ret, out #return_ty __result);
-            return __result;
+    // The raw result is converted before the references added to the receiver and
+    // arguments are released, since the result may borrow from them. Only the `return`
+    // happens after the call completes.
+    let (result_decl, convert_result, ret_expr) = match output {
+        // A function returning `Result<(), E>` has a `void` return type in C#, but we
+        // still need to check the raw result and throw if the function failed.
+        Some(output) if result::is_unit_result(output) => (
+            TokenStream::new(),
+            quote! { #binding_class.#from_raw(#ret); },
+            TokenStream::new(),
+        ),
+
+        // A borrowed handle is only valid as long as the handles it may have been
+        // borrowed from, i.e. the receiver and any handles passed by reference.
+        Some(output @ Repr::Ref(_)) => {
+            let return_ty = quote_cs_type_for_repr(output, types);
+            let owners = receiver
+                .as_ref()
+                .and_then(|receiver| receiver.owner.clone())
+                .into_iter()
+                .chain(
+                    inputs
+                        .iter()
+                        .filter(|arg| is_handle_ref(&arg.repr, types))
+                        .map(|arg| {
+                            let arg_name = arg_ident(arg);
+                            quote! { #arg_name._handle }
+                        }),
+                )
+                .collect::<Vec<_>>();
+            let set_owners = if owners.is_empty() {
+                TokenStream::new()
+            } else {
+                quote! { __result._owners = new RustHandle[] { #( #owners ),* }; }
+            };
+
+            (
+                quote! { #return_ty __result; },
+                quote! {
+                    #binding_class.#from_raw(#ret, out __result);
+                    #set_owners
+                },
+                quote! { return __result; },
+            )
+        }
+
+        Some(output) => {
+            let return_ty = quote_cs_type_for_repr(output, types);
+            (
+                quote! { #return_ty __result; },
+                quote! { #binding_class.#from_raw(#ret, out __result); },
+                quote! { return __result; },
+            )
+        }
+
+        None => Default::default(),
+    };
+
+    let body = quote_wrapper_body(
+        binding,
+        receiver,
+        inputs,
+        output.map(|_| &ret),
+        convert_result,
+        None,
+        types,
+    );
+
+    quote! {
+        unsafe {
+            #ret_decl
+            #result_decl
+            #body
+            #ret_expr
+        }
+    }
+}
+
+/// Generates a wrapper function using the "try" pattern for a function returning
+/// `Result<T, E>`.
+///
+/// The generated function returns `true` if the Rust function succeeded, and
+/// returns the converted success and error values via `out` parameters, i.e.
+/// `bool TryFoo(.., out T value, out E error)`. The `value` parameter is omitted if
+/// the success type is `()`. If the function already has an argument named `value`
+/// or `error`, the corresponding `out` parameter is renamed to `outValue` or
+/// `outError`.
+fn quote_try_wrapper_fn(
+    name: &str,
+    binding: &str,
+    receiver: Option<Receiver>,
+    inputs: &[FnArg],
+    ok: &Repr,
+    err: &Repr,
+    types: &TypeMap,
+) -> TokenStream {
+    let name = format_ident!("Try{}", name);
+
+    let ret = quote! { __raw_result };
+    let raw_return_ty = binding::raw_type_from_repr(
+        &Repr::Result {
+            ok: Box::new(ok.clone()),
+            err: Box::new(err.clone()),
         },
+        types,
+    );
+    let error_ty = quote_cs_type_for_repr(err, types);
 
-        None => quote! {},
+    let binding_class = binding::bindings_class_ident();
+    let from_raw = binding::from_raw_fn_ident();
+
+    let (value, error) = try_out_params(inputs);
+
+    // Generate the `out` parameter and conversions for the success value, if any.
+    // The `out` parameters are also collected so that they can be forwarded by the
+    // `List<T>` overload.
+    let mut out_params = Vec::new();
+    let (value_arg, convert_value, default_value) = if *ok == Repr::Unit {
+        Default::default()
+    } else {
+        let value_ty = quote_cs_type_for_repr(ok, types);
+        out_params.push((quote! { out #value_ty #value }, quote! { out #value }));
+        (
+            quote! { out #value_ty #value, },
+            quote! { #binding_class.#from_raw(#ret.Value.Ok, out #value); },
+            quote! { #value = default(#value_ty); },
+        )
     };
+    out_params.push((quote! { out #error_ty #error }, quote! { out #error }));
 
     // Determine if the function should be static or not based on whether or not it has
     // a receiver.
-    let static_ = if receiver.is_some() {
-        TokenStream::default()
-    } else {
+    let static_ = if Receiver::is_static(receiver.as_ref()) {
         quote! { static }
+    } else {
+        TokenStream::default()
     };
 
-    let args = quote_args(inputs, types);
-    let body = quote_wrapper_body(binding, receiver, &inputs, output.map(|_| &ret), types);
+    let receiver_param = receiver
+        .as_ref()
+        .and_then(|receiver| receiver.param.clone());
+    let list_overload = quote_list_overload(
+        &name,
+        &quote! { bool },
+        true,
+        receiver.as_ref(),
+        inputs,
+        &out_params,
+        types,
+    );
+    let args = receiver_param.into_iter().chain(quote_args(inputs, types));
+    let convert_result = quote! {
+        if (#ret.IsOk != 0)
+        {
+            #convert_value
+            #error = default(#error_ty);
+            __succeeded = true;
+        }
+        else
+        {
+            #default_value
+            #binding_class.#from_raw(#ret.Value.Err, out #error);
+            __succeeded = false;
+        }
+    };
+    let body = quote_wrapper_body(
+        binding,
+        receiver,
+        inputs,
+        Some(&ret),
+        convert_result,
+        None,
+        types,
+    );
 
     quote! {
-        public #static_ #return_ty #name(#( #args ),*)
+        public #static_ bool #name(#( #args, )* #value_arg out #error_ty #error)
         {
             unsafe {
-                #ret_decl
+                #raw_return_ty #ret;
+                bool __succeeded;
                 #body
-                #ret_expr
+                return __succeeded;
             }
         }
+
+        #list_overload
     }
 }
 
-pub fn quote_wrapper_body<'a>(
+/// Generates the statements that convert the arguments, invoke the raw binding, and
+/// check for a panic.
+///
+/// The raw result is assigned to `output`, if specified. `convert_output` runs once the
+/// call has completed, but before the references added to the receiver and to any
+/// handles passed by reference are released. `completion` is passed as the last
+/// argument to the raw binding for an `async` function, see
+/// [`task::quote_async_wrapper_fn`].
+///
+/// [`task::quote_async_wrapper_fn`]: ../task/fn.quote_async_wrapper_fn.html
+pub fn quote_wrapper_body(
     binding_name: &str,
-    receiver: Option<TokenStream>,
+    receiver: Option<Receiver>,
     args: &[FnArg],
     output: Option<&TokenStream>,
+    convert_output: TokenStream,
+    completion: Option<TokenStream>,
     types: &TypeMap,
 ) -> TokenStream {
-    let arg_name = args
-        .iter()
-        .map(|arg| format_ident!("{}", arg.name.to_mixed_case()));
+    let arg_name = args.iter().map(arg_ident);
     let temp_arg_name = args.iter().map(|arg| format_ident!("__{}", arg.name));
     let raw_ty = args
         .iter()
@@ -90,6 +462,7 @@ pub fn quote_wrapper_body<'a>(
 
     let bindings = binding::bindings_class_ident();
     let into_raw = binding::into_raw_fn_ident();
+    let check_panic = binding::check_panic_fn_ident();
 
     // Build the list of arguments to the wrapper function and insert the receiver at
     // the beginning of the list of arguments if necessary.
@@ -97,11 +470,17 @@ pub fn quote_wrapper_body<'a>(
         .clone()
         .map(|name| name.into_token_stream())
         .collect::<Vec<_>>();
-    if let Some(receiver) = receiver {
-        invoke_arg.insert(0, receiver);
-    }
+    let (setup_receiver, write_back_receiver, release_receiver) = match receiver {
+        Some(receiver) => {
+            invoke_arg.insert(0, receiver.arg);
+            (receiver.setup, receiver.write_back, receiver.release)
+        }
+
+        None => Default::default(),
+    };
+    invoke_arg.extend(completion);
 
-    let raw_fn = format_ident!("{}", binding_name);
+    let raw_fn = binding::entry_point_ident(binding_name);
 
     // Generate the expression for invoking the raw function. If
     let invoke = quote! { #bindings.#raw_fn(#( #invoke_arg ),*) };
@@ -111,38 +490,314 @@ pub fn quote_wrapper_body<'a>(
         None => quote! {},
     };
 
-    let body = quote! {
-        #(
-            #bindings.#into_raw(#arg_name, out #raw_ty #temp_arg_name);
-        )*
+    // Convert each argument to its raw representation. Span arguments have already been
+    // pinned by the enclosing `fixed` blocks, so we only need to wrap the pointer in a
+    // `RawSlice`. Borrowed handles have a reference added for the duration of the call
+    // in the same way as a borrowed receiver, see `Receiver::handle`.
+    let mut handle_refs = Vec::new();
+    let convert_arg = args
+        .iter()
+        .zip(arg_name)
+        .zip(temp_arg_name.zip(raw_ty))
+        .filter_map(|((arg, arg_name), (temp_arg_name, raw_ty))| {
+            if span_element(&arg.repr).is_some() {
+                let fixed_ident = format_ident!("__fixed_{}", arg_name);
+                Some(quote! {
+                    RawSlice #temp_arg_name = new RawSlice((IntPtr)#fixed_ident, #arg_name.Length);
+                })
+            } else if is_handle_ref(&arg.repr, types) {
+                handle_refs.push((
+                    quote! { #bindings.#into_raw(#arg_name, out RawRef #temp_arg_name); },
+                    quote! { #arg_name.__Release(); },
+                ));
+                None
+            } else {
+                Some(quote! {
+                    #bindings.#into_raw(#arg_name, out #raw_ty #temp_arg_name);
+                })
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut body = quote! {
+        #( #convert_arg )*
 
         #out_equals #invoke;
+        #bindings.#check_panic();
+        #write_back_receiver
+        #convert_output
     };
 
-    fold_fixed_blocks(body, args)
+    for (setup, release) in handle_refs.into_iter().rev() {
+        body = quote_try_finally(setup, body, release);
+    }
+    let body = quote_try_finally(setup_receiver, body, release_receiver);
+
+    fold_fixed_blocks(body, args, types)
 }
 
-fn fold_fixed_blocks<'a>(base_invoke: TokenStream, args: &[FnArg]) -> TokenStream {
+/// Generates an overload of a wrapper function that takes a `List<T>` in place of each
+/// span argument, so that existing code passing lists can still call the function.
+///
+/// The lists are copied into arrays and the call is forwarded to the span overload.
+/// For a `&mut [T]` argument the array is copied back into the list once the call
+/// completes. Returns an empty token stream if the function has no span arguments.
+///
+/// `out_params` contains the declaration of each `out` parameter of the function,
+/// along with the argument that forwards it.
+fn quote_list_overload(
+    name: &Ident,
+    return_ty: &TokenStream,
+    returns_value: bool,
+    receiver: Option<&Receiver>,
+    inputs: &[FnArg],
+    out_params: &[(TokenStream, TokenStream)],
+    types: &TypeMap,
+) -> TokenStream {
+    if !inputs.iter().any(|arg| span_element(&arg.repr).is_some()) {
+        return TokenStream::new();
+    }
+
+    let static_ = if Receiver::is_static(receiver) {
+        quote! { static }
+    } else {
+        TokenStream::default()
+    };
+
+    let mut params = receiver
+        .and_then(|receiver| receiver.param.clone())
+        .into_iter()
+        .collect::<Vec<_>>();
+    let mut forward_args = receiver
+        .and_then(|receiver| receiver.forward_arg.clone())
+        .into_iter()
+        .collect::<Vec<_>>();
+    let mut setup = TokenStream::new();
+    let mut write_back = TokenStream::new();
+    for (arg, param) in inputs.iter().zip(quote_args(inputs, types)) {
+        let arg_name = format_ident!("{}", arg.name.to_mixed_case());
+        let element = match span_element(&arg.repr) {
+            Some(element) => quote_cs_type_for_repr(element, types),
+            None => {
+                params.push(param);
+                forward_args.push(arg_name.into_token_stream());
+                continue;
+            }
+        };
+
+        let array_name = format_ident!("__array_{}", arg_name);
+        params.push(quote! { List<#element> #arg_name });
+        forward_args.push(array_name.to_token_stream());
+        setup.extend(quote! { var #array_name = #arg_name.ToArray(); });
+
+        if let Repr::SliceMut(_) = arg.repr {
+            write_back.extend(quote! {
+                for (int index = 0; index < #array_name.Length; index += 1)
+                {
+                    #arg_name[index] = #array_name[index];
+                }
+            });
+        }
+    }
+
+    for (param, forward_arg) in out_params {
+        params.push(param.clone());
+        forward_args.push(forward_arg.clone());
+    }
+
+    let return_ = if returns_value {
+        quote! { return }
+    } else {
+        TokenStream::new()
+    };
+    let body = quote_try_finally(
+        setup,
+        quote! { #return_ #name(#( #forward_args ),*); },
+        write_back,
+    );
+
+    quote! {
+        public #static_ #return_ty #name(#( #params ),*)
+        {
+            #body
+        }
+    }
+}
+
+/// Wraps `body` in a `try`/`finally` block that runs `release`, with `setup` run
+/// before the `try` block. If `release` is empty, no `try` block is generated.
+fn quote_try_finally(setup: TokenStream, body: TokenStream, release: TokenStream) -> TokenStream {
+    if release.is_empty() {
+        return quote! {
+            #setup
+            #body
+        };
+    }
+
+    quote! {
+        #setup
+        try
+        {
+            #body
+        }
+        finally
+        {
+            #release
+        }
+    }
+}
+
+/// Generates an interface method declaration for a wrapper function, along with an
+/// implementation of the method that forwards the call to `target`.
+///
+/// `target` is expected to be a static wrapper function that takes the receiver as its
+/// first parameter. The implementation passes `this` as the receiver.
+pub fn quote_forwarding_fn(
+    name: &str,
+    inputs: &[FnArg],
+    output: Option<&Repr>,
+    try_pattern: bool,
+    target: &TokenStream,
+    types: &TypeMap,
+) -> (TokenStream, TokenStream) {
+    let params = quote_args(inputs, types).collect::<Vec<_>>();
+    let mut forward_args = iter::once(quote! { this })
+        .chain(inputs.iter().map(|arg| arg_ident(arg).into_token_stream()))
+        .collect::<Vec<_>>();
+
+    let (name, return_ty, out_params, returns_value) = match output {
+        Some(Repr::Result { ok, err }) if try_pattern => {
+            let (value, error) = try_out_params(inputs);
+            let error_ty = quote_cs_type_for_repr(err, types);
+
+            let mut out_params = Vec::new();
+            if **ok != Repr::Unit {
+                let value_ty = quote_cs_type_for_repr(ok, types);
+                out_params.push(quote! { out #value_ty #value });
+                forward_args.push(quote! { out #value });
+            }
+            out_params.push(quote! { out #error_ty #error });
+            forward_args.push(quote! { out #error });
+
+            (
+                format_ident!("Try{}", name),
+                quote! { bool },
+                out_params,
+                true,
+            )
+        }
+
+        Some(output) => (
+            format_ident!("{}", name),
+            quote_cs_type_for_repr(output, types),
+            Vec::new(),
+            !result::is_unit_result(output),
+        ),
+
+        None => (
+            format_ident!("{}", name),
+            quote! { void },
+            Vec::new(),
+            false,
+        ),
+    };
+
+    let params = params.into_iter().chain(out_params).collect::<Vec<_>>();
+    let return_ = if returns_value {
+        quote! { return }
+    } else {
+        TokenStream::new()
+    };
+
+    let decl = quote! {
+        #return_ty #name(#( #params ),*);
+    };
+
+    let forward = quote! {
+        public #return_ty #name(#( #params ),*)
+        {
+            #return_ #target.#name(#( #forward_args ),*);
+        }
+    };
+
+    (decl, forward)
+}
+
+/// Determines the names of the `out` parameters for a wrapper function using the "try"
+/// pattern, making sure that they don't collide with the names of the regular
+/// arguments.
+fn try_out_params(inputs: &[FnArg]) -> (Ident, Ident) {
+    let out_param_ident = |name: &str| {
+        let collides = inputs.iter().any(|arg| arg_ident(arg) == name);
+        if collides {
+            format_ident!("out{}", name.to_camel_case())
+        } else {
+            format_ident!("{}", name)
+        }
+    };
+
+    (out_param_ident("value"), out_param_ident("error"))
+}
+
+fn fold_fixed_blocks<'a>(base_invoke: TokenStream, args: &[FnArg], types: &TypeMap) -> TokenStream {
     // Wrap the body of the function in `fixed` blocks for any parameters that need to
-    // be passed as pointers to Rust (just strings for now). We use `Iterator::fold` to
+    // be passed as pointers to Rust (strings and spans). We use `Iterator::fold` to
     // generate a series of nested `fixed` blocks. This is very smart code and won't be
     // hard to maintain at all, I'm sure.
     args.iter().fold(base_invoke, |body, arg| {
+        let arg_ident = arg_ident(arg);
+        let fixed_ident = format_ident!("__fixed_{}", arg_ident);
         if arg.repr == Repr::String {
-            let arg_ident = format_ident!("{}", arg.name.to_mixed_case());
-            let fixed_ident = format_ident!("__fixed_{}", arg_ident);
             quote! {
                 fixed (char* #fixed_ident = #arg_ident)
                 {
                     #body
                 }
             }
+        } else if let Some(element) = span_element(&arg.repr) {
+            let element_ty = quote_cs_type_for_repr(element, types);
+            quote! {
+                fixed (#element_ty* #fixed_ident = #arg_ident)
+                {
+                    #body
+                }
+            }
         } else {
             body
         }
     })
 }
 
+/// Returns the element type of a slice argument that is passed as a span.
+///
+/// Slices of primitive numeric types have the same layout in Rust and C#, so the
+/// caller's memory is pinned and passed directly to Rust without copying. `&[T]` is
+/// exposed as `ReadOnlySpan<T>` and `&mut [T]` is exposed as `Span<T>`, which lets
+/// the Rust function write into the caller's buffer.
+pub fn span_element(repr: &Repr) -> Option<&Repr> {
+    let element = match repr {
+        Repr::Slice(element) | Repr::SliceMut(element) => element,
+        _ => return None,
+    };
+
+    match **element {
+        Repr::I8
+        | Repr::I16
+        | Repr::I32
+        | Repr::I64
+        | Repr::ISize
+        | Repr::U8
+        | Repr::U16
+        | Repr::U32
+        | Repr::U64
+        | Repr::USize
+        | Repr::F32
+        | Repr::F64 => Some(element),
+
+        _ => None,
+    }
+}
+
 /// Generates the argument declarations for a C# wrapper function.
 ///
 /// Attempts to use the most idiomatic C# type that corresponds to the original type.
@@ -151,8 +806,40 @@ pub fn quote_args<'a>(
     types: &'a TypeMap<'_>,
 ) -> impl Iterator<Item = TokenStream> + 'a {
     args.iter().map(move |arg| {
-        let ident = format_ident!("{}", arg.name.to_mixed_case());
-        let ty = quote_cs_type_for_repr(&arg.repr, types);
+        let ident = arg_ident(arg);
+        let ty = match (&arg.repr, span_element(&arg.repr)) {
+            (Repr::Slice(_), Some(element)) => {
+                let element = quote_cs_type_for_repr(element, types);
+                quote! { ReadOnlySpan<#element> }
+            }
+
+            (Repr::SliceMut(_), Some(element)) => {
+                let element = quote_cs_type_for_repr(element, types);
+                quote! { Span<#element> }
+            }
+
+            (Repr::Slice(_), None) | (Repr::SliceMut(_), None) => unreachable!(
+                "Slice arguments without a primitive numeric element type should have already \
+                 been rejected"
+            ),
+
+            _ => quote_cs_type_for_repr(&arg.repr, types),
+        };
+
         quote! { #ty #ident }
     })
 }
+
+/// Returns `true` if `repr` is a reference to a type that is marshaled as a handle.
+pub fn is_handle_ref(repr: &Repr, types: &TypeMap) -> bool {
+    match repr {
+        Repr::Ref(inner) => match &**inner {
+            Repr::Named(type_name) => types
+                .get(type_name)
+                .map(|export| export.binding_style == BindingStyle::Handle)
+                .unwrap_or(false),
+            _ => false,
+        },
+        _ => false,
+    }
+}
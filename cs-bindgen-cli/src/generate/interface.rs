@@ -0,0 +1,173 @@
+//! Code generation for exported traits, which are exposed as C# interfaces.
+//!
+//! A C# object implementing the interface is passed to Rust as a raw struct that
+//! matches the layout of `RawTraitObject` in the Rust runtime: A `GCHandle` for the
+//! object, a pointer to the function that frees the handle, and the vtable for the
+//! trait. The vtable contains a pointer to a trampoline for each method of the trait,
+//! in declaration order. The trampolines work the same way as the trampolines for
+//! boxed callbacks, converting the raw arguments and invoking the method on the
+//! object, so exceptions thrown by the object are handled the same way as exceptions
+//! thrown by a callback.
+//!
+//! Exported types can also implement an exported trait in Rust, in which case the
+//! class generated for the type implements the interface. The methods of the trait
+//! impl are generated like any other method, using the names of the trait methods.
+
+use crate::generate::{self, binding, callback, docs, func, TraitExt, TypeMap};
+use cs_bindgen_shared::{Export, Repr, Trait, TypeName};
+use heck::*;
+use proc_macro2::TokenStream;
+use quote::*;
+
+pub fn quote_interface(export: &Trait, types: &TypeMap) -> TokenStream {
+    let ident = export.ident();
+    let ty = types.type_path(&export.type_name, &ident);
+    let raw_ident = binding::raw_ident(&export.type_name);
+    let mangled_name = binding::mangled_type_name(&export.type_name);
+
+    let into_raw = binding::into_raw_fn_ident();
+
+    let mut interface_methods = Vec::new();
+    let mut trampolines = Vec::new();
+    let mut vtable_fields = Vec::new();
+    let mut vtable_ptrs = Vec::new();
+    for method in &export.methods {
+        let name = format_ident!("{}", func::fn_name(&method.name, method.rename.as_deref()));
+        let output = method.output.clone().unwrap_or(Repr::Unit);
+        let output_ty = match &method.output {
+            Some(output) => generate::quote_cs_type_for_repr(output, types),
+            None => quote! { void },
+        };
+        let arg_name = method.inputs.iter().map(func::arg_ident);
+        let input_ty = method
+            .inputs
+            .iter()
+            .map(|arg| generate::quote_cs_type_for_repr(&arg.repr, types))
+            .collect::<Vec<_>>();
+
+        let docs = docs::quote_trait_method_docs(method);
+        interface_methods.push(quote! {
+            #docs
+            #output_ty #name(#( #input_ty #arg_name ),*);
+        });
+
+        // Generate the trampoline for the method, which is the same as the trampoline for
+        // a boxed callback except that it invokes the method on the target object.
+        let delegate = format_ident!("__{}__{}", mangled_name, name);
+        let inputs = method
+            .inputs
+            .iter()
+            .map(|arg| arg.repr.clone())
+            .collect::<Vec<_>>();
+        let (trampoline, ptr_field) = callback::quote_trampoline(
+            &delegate,
+            &ty,
+            &quote! { target.#name },
+            &inputs,
+            &output,
+            types,
+        );
+        trampolines.push(trampoline);
+
+        vtable_fields.push(format_ident!("{}Fn", method.name.to_camel_case()));
+        vtable_ptrs.push(ptr_field);
+    }
+
+    let docs = docs::quote_docs(export.docs.as_deref());
+    let interface = types.in_type_namespace(
+        &export.type_name,
+        quote! {
+            #docs
+            public interface #ident
+            {
+                #( #interface_methods )*
+            }
+        },
+    );
+
+    let bindings = binding::wrap_bindings(quote! {
+        #( #trampolines )*
+
+        internal static void #into_raw(#ty value, out #raw_ident result)
+        {
+            if (value == null)
+            {
+                throw new ArgumentNullException(nameof(value));
+            }
+
+            result = new #raw_ident
+            {
+                Context = GCHandle.ToIntPtr(GCHandle.Alloc(value)),
+                Drop = __DropCallbackPtr,
+                #( #vtable_fields = #vtable_ptrs, )*
+            };
+        }
+    });
+
+    quote! {
+        #interface
+
+        // Raw representation of an object implementing the interface. This needs to match
+        // the layout of `RawTraitObject` on the Rust side, with the vtable inlined.
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct #raw_ident
+        {
+            public IntPtr Context;
+            public IntPtr Drop;
+            #(
+                public IntPtr #vtable_fields;
+            )*
+        }
+
+        #bindings
+    }
+}
+
+/// Generates the declarations that add the interfaces for the exported trait impls to
+/// the generated classes.
+pub fn quote_trait_impls(exports: &[Export], types: &TypeMap) -> TokenStream {
+    trait_impls(exports)
+        .into_iter()
+        .map(|(self_type, trait_name)| {
+            let class_ident = types.type_ident(self_type);
+            let interface = types.type_path(trait_name, types.get_trait(trait_name).ident());
+            types.in_type_namespace(
+                self_type,
+                quote! {
+                    partial class #class_ident : #interface
+                    {
+                    }
+                },
+            )
+        })
+        .collect()
+}
+
+/// Returns the self type and trait of every exported trait impl, in the order that
+/// they're first declared.
+pub fn trait_impls(exports: &[Export]) -> Vec<(&TypeName, &TypeName)> {
+    let mut impls = Vec::new();
+    for export in exports {
+        if let Export::Method(method) = export {
+            if let Some(trait_name) = &method.trait_name {
+                let pair = (&method.self_type, trait_name);
+                if !impls.contains(&pair) {
+                    impls.push(pair);
+                }
+            }
+        }
+    }
+
+    impls
+}
+
+/// Returns the name of the C# method generated for the method of a trait impl, which
+/// is the name of the corresponding interface method.
+pub fn impl_method_name(export: &Trait, name: &str) -> String {
+    let method = export
+        .methods
+        .iter()
+        .find(|method| method.name == name)
+        .unwrap_or_else(|| panic!("No method `{}` found in trait {:?}", name, export.type_name));
+    func::fn_name(&method.name, method.rename.as_deref())
+}
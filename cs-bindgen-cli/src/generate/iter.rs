@@ -0,0 +1,246 @@
+//! Code generation for iterators returned from Rust, which are exposed in C# as an
+//! `IEnumerable<T>`.
+//!
+//! Each function that returns an iterator has its own handle type on the Rust side,
+//! which exports a `next` function alongside the usual drop function. For each handle
+//! type we generate a class that implements both `IEnumerable<T>` and
+//! `IEnumerator<T>`, similar to the classes that the C# compiler generates for
+//! iterator blocks. A Rust iterator can only be traversed once, so the object can
+//! only be enumerated once.
+//!
+//! The iterator is dropped as soon as it's exhausted or the enumerator is disposed,
+//! which `foreach` and LINQ both do once they're done enumerating. As with other
+//! handle types, the handle is owned by a `SafeHandle`, so the iterator is still
+//! dropped if the object is never enumerated. Iterators are always `Send`, so unlike
+//! other handle types they can be dropped from any thread.
+//!
+//! The raw handle is wrapped in a raw struct generated for each handle type, so that
+//! the `__FromRaw` conversions remain distinct for iterators with the same item type.
+
+use crate::generate::{self, binding, TypeMap};
+use cs_bindgen_shared::{schematic::TypeName, Export, Repr};
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::Ident;
+
+/// Generates the enumerator classes, raw structs, and raw bindings for every iterator
+/// returned from the exported items.
+pub fn quote_iterator_bindings(exports: &[Export], types: &TypeMap, dll_name: &str) -> TokenStream {
+    let mut iterators = Vec::new();
+    generate::visit_reprs(exports, |repr| {
+        if let Repr::Iterator { type_name, item } = repr {
+            if !iterators.iter().any(|(existing, _)| existing == type_name) {
+                iterators.push((type_name.clone(), (**item).clone()));
+            }
+        }
+    });
+
+    iterators
+        .iter()
+        .map(|(type_name, item)| quote_iterator(type_name, item, types, dll_name))
+        .collect()
+}
+
+/// Quotes the C# type corresponding to an iterator with the given item type, i.e.
+/// `IEnumerable<T>`.
+pub fn quote_cs_type(item: &Repr, types: &TypeMap) -> TokenStream {
+    let item = generate::quote_cs_type_for_repr(item, types);
+    quote! { IEnumerable<#item> }
+}
+
+/// Quotes a reference to the raw struct used to represent the handle for an
+/// iterator.
+pub fn raw_type_reference(type_name: &TypeName, types: &TypeMap) -> TokenStream {
+    types.root_path(binding::raw_ident(type_name))
+}
+
+/// Returns `true` if `repr` contains an iterator anywhere within it.
+pub fn contains_iterator(repr: &Repr) -> bool {
+    match repr {
+        Repr::Iterator { .. } => true,
+
+        Repr::Box(inner)
+        | Repr::Ref(inner)
+        | Repr::Vec(inner)
+        | Repr::Slice(inner)
+        | Repr::SliceMut(inner)
+        | Repr::Option(inner) => contains_iterator(inner),
+
+        Repr::Array { element, .. } => contains_iterator(element),
+        Repr::Result { ok, err } => contains_iterator(ok) || contains_iterator(err),
+        Repr::Map { key, value } => contains_iterator(key) || contains_iterator(value),
+        Repr::Tuple(elements) => elements.iter().any(contains_iterator),
+        Repr::Callback { inputs, output } | Repr::FnPtr { inputs, output } => {
+            inputs.iter().any(contains_iterator) || contains_iterator(output)
+        }
+
+        _ => false,
+    }
+}
+
+/// Generates the identifier of the enumerator class for an iterator.
+fn enumerator_ident(type_name: &TypeName) -> Ident {
+    format_ident!("__{}__Enumerator", binding::mangled_type_name(type_name))
+}
+
+fn quote_iterator(
+    type_name: &TypeName,
+    item: &Repr,
+    types: &TypeMap,
+    dll_name: &str,
+) -> TokenStream {
+    let ident = enumerator_ident(type_name);
+    let raw_ident = binding::raw_ident(type_name);
+    let cs_ty = quote_cs_type(item, types);
+    let item_ty = generate::quote_cs_type_for_repr(item, types);
+    let raw_item = binding::raw_type_from_repr(item, types);
+
+    let next_entry_point = binding::next_fn_entry_point(type_name);
+    let drop_entry_point = binding::drop_fn_entry_point(type_name);
+    let next_fn = binding::entry_point_ident(&next_entry_point);
+    let drop_fn = binding::entry_point_ident(&drop_entry_point);
+
+    let from_raw = binding::from_raw_fn_ident();
+    let check_panic = binding::check_panic_fn_ident();
+
+    let bindings = binding::wrap_bindings(quote! {
+        [DllImport(
+            #dll_name,
+            EntryPoint = #next_entry_point,
+            CallingConvention = CallingConvention.Cdecl)]
+        internal static extern byte #next_fn(IntPtr self, out #raw_item item);
+
+        [DllImport(
+            #dll_name,
+            EntryPoint = #drop_entry_point,
+            CallingConvention = CallingConvention.Cdecl)]
+        internal static extern void #drop_fn(IntPtr self);
+
+        internal static void #from_raw(#raw_ident raw, out #cs_ty result)
+        {
+            result = new #ident(raw.Ptr);
+        }
+    });
+
+    quote! {
+        #bindings
+
+        // Raw representation of the handle for the iterator. This has the same layout as
+        // a raw pointer.
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct #raw_ident
+        {
+            public IntPtr Ptr;
+        }
+
+        internal sealed class #ident : #cs_ty, IEnumerator<#item_ty>
+        {
+            // Owns the raw handle and drops the iterator when released. The boxed iterator
+            // is `Send`, and advancing it borrows it mutably, so calls don't need to be
+            // serialized.
+            private sealed class __Handle : RustHandle
+            {
+                internal __Handle(IntPtr raw) : base(raw, true, "Rust iterator", false) { }
+
+                // Set if dropping the iterator panicked. `Dispose` rethrows it, whereas a
+                // handle released by the finalizer has nobody to report it to.
+                internal RustPanicException _dropPanic;
+
+                // NOTE: This can run on the finalizer thread, or on the thread that
+                // releases the last reference in `MoveNext`, so it must not throw.
+                protected override bool ReleaseHandle()
+                {
+                    __bindings.#drop_fn(handle);
+                    _dropPanic = __bindings.__TakePanic();
+                    return true;
+                }
+            }
+
+            private readonly __Handle _handle;
+            private bool _enumerated;
+            private #item_ty _current;
+
+            internal #ident(IntPtr raw)
+            {
+                _handle = new __Handle(raw);
+            }
+
+            public #item_ty Current => _current;
+
+            object global::System.Collections.IEnumerator.Current => _current;
+
+            public IEnumerator<#item_ty> GetEnumerator()
+            {
+                if (_enumerated)
+                {
+                    throw new InvalidOperationException(
+                        "An iterator returned from Rust can only be enumerated once");
+                }
+
+                _enumerated = true;
+                return this;
+            }
+
+            global::System.Collections.IEnumerator global::System.Collections.IEnumerable.GetEnumerator()
+            {
+                return GetEnumerator();
+            }
+
+            public bool MoveNext()
+            {
+                if (_handle.IsClosed)
+                {
+                    return false;
+                }
+
+                // NOTE: An exclusive reference is added to the handle for the duration of the
+                // call, so that it can't be released by a concurrent call to `Dispose`, and so
+                // that a concurrent call to `MoveNext` throws instead of advancing the
+                // iterator at the same time.
+                byte hasNext;
+                #raw_item rawItem;
+                _handle.AddMutRef();
+                try
+                {
+                    hasNext = __bindings.#next_fn(_handle.DangerousGetHandle(), out rawItem);
+                }
+                finally
+                {
+                    _handle.ReleaseMut();
+                }
+                __bindings.#check_panic();
+
+                // Drop the iterator as soon as it's exhausted, rather than waiting for the
+                // enumerator to be disposed.
+                if (hasNext == 0)
+                {
+                    Dispose();
+                    return false;
+                }
+
+                __bindings.#from_raw(rawItem, out _current);
+                return true;
+            }
+
+            public void Reset()
+            {
+                throw new NotSupportedException("Iterators returned from Rust can't be reset");
+            }
+
+            public void Dispose()
+            {
+                if (!_handle.IsClosed)
+                {
+                    _handle.Dispose();
+
+                    var panic = _handle._dropPanic;
+                    if (panic != null)
+                    {
+                        _handle._dropPanic = null;
+                        throw panic;
+                    }
+                }
+            }
+        }
+    }
+}
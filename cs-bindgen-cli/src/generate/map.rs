@@ -0,0 +1,78 @@
+//! Code generation for map types.
+//!
+//! Rust map types (i.e. `HashMap<K, V>` and `BTreeMap<K, V>`) are exposed in C# as
+//! `Dictionary<K, V>`.
+//!
+//! The raw representation of a map is a `RawMap`, which is a pair of `RawVec`s
+//! containing the keys and values of the map, matching `RawMap<K, V>` in the Rust
+//! runtime. Since the raw representation is the same for all map types, the keys
+//! and values are converted using the existing `List<T>` conversions for the key and
+//! value types. We generate a separate pair of conversions for each map type that
+//! appears in the exported items.
+
+use crate::generate::{self, binding, TypeMap};
+use cs_bindgen_shared::{Export, Repr};
+use proc_macro2::TokenStream;
+use quote::*;
+
+/// Generates the `__FromRaw`/`__IntoRaw` conversions for every map type used in the
+/// exported items.
+pub fn quote_map_bindings(exports: &[Export], types: &TypeMap) -> TokenStream {
+    let mut map_types = Vec::new();
+    generate::visit_reprs(exports, |repr| {
+        if let Repr::Map { key, value } = repr {
+            let pair = ((**key).clone(), (**value).clone());
+            if !map_types.contains(&pair) {
+                map_types.push(pair);
+            }
+        }
+    });
+
+    let conversions = map_types
+        .iter()
+        .map(|(key, value)| quote_conversions(key, value, types));
+
+    binding::wrap_bindings(quote! {
+        #( #conversions )*
+    })
+}
+
+/// Quotes the `Dictionary<K, V>` type corresponding to a map with the given key and
+/// value types.
+pub fn quote_cs_type(key: &Repr, value: &Repr, types: &TypeMap) -> TokenStream {
+    let key = generate::quote_cs_type_for_repr(key, types);
+    let value = generate::quote_cs_type_for_repr(value, types);
+    quote! { Dictionary<#key, #value> }
+}
+
+fn quote_conversions(key: &Repr, value: &Repr, types: &TypeMap) -> TokenStream {
+    let cs_ty = quote_cs_type(key, value, types);
+    let key_ty = generate::quote_cs_type_for_repr(key, types);
+    let value_ty = generate::quote_cs_type_for_repr(value, types);
+
+    let from_raw = binding::from_raw_fn_ident();
+    let into_raw = binding::into_raw_fn_ident();
+
+    quote! {
+        internal static void #from_raw(RawMap raw, out #cs_ty result)
+        {
+            #from_raw(raw.Keys, out List<#key_ty> keys);
+            #from_raw(raw.Values, out List<#value_ty> values);
+
+            result = new #cs_ty(keys.Count);
+            for (int index = 0; index < keys.Count; index += 1)
+            {
+                result.Add(keys[index], values[index]);
+            }
+        }
+
+        internal static void #into_raw(#cs_ty value, out RawMap result)
+        {
+            // NOTE: The order of `Keys` and `Values` is guaranteed to match, so the
+            // resulting lists are parallel to each other.
+            #into_raw(new List<#key_ty>(value.Keys), out RawVec keys);
+            #into_raw(new List<#value_ty>(value.Values), out RawVec values);
+            result = new RawMap(keys, values);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Code generation for optional values.
+//!
+//! Rust's `Option<T>` is exposed in C# as a nullable type. For value types (i.e.
+//! primitives, structs, C-like enums, and tuples) this means `Nullable<T>` (or
+//! `T?`), and for reference types (classes, interfaces, strings, and lists) we use
+//! `null` directly.
+//!
+//! The raw representation of an optional value is a struct with an `IsSome` flag
+//! followed by the raw representation of the inner value, matching the layout of
+//! `RawOption<T>` in the Rust runtime. Since generic structs can't be used in
+//! P/Invoke signatures, we generate a separate raw struct for each optional type
+//! that appears in the exported items.
+
+use crate::generate::{self, binding, TypeMap};
+use cs_bindgen_shared::{schematic::Schema, BindingStyle, Export, Repr};
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::Ident;
+
+/// Generates the raw structs and `__FromRaw`/`__IntoRaw` conversions for every
+/// optional type used in the exported items.
+pub fn quote_option_bindings(exports: &[Export], types: &TypeMap) -> TokenStream {
+    let mut optional_types = Vec::new();
+    generate::visit_reprs(exports, |repr| {
+        if let Repr::Option(inner) = repr {
+            if !optional_types.contains(&**inner) {
+                optional_types.push((**inner).clone());
+            }
+        }
+    });
+
+    // Multiple optional types may share the same raw representation (e.g. `bool` and
+    // `u8` are both passed as a `byte`), so we only generate each raw struct once.
+    let mut raw_structs = Vec::new();
+    let mut generated_structs = Vec::new();
+    let mut conversions = Vec::new();
+    for inner in &optional_types {
+        let ident = raw_ident(inner, types);
+        if !generated_structs.contains(&ident) {
+            raw_structs.push(quote_raw_struct(&ident, inner, types));
+            generated_structs.push(ident);
+        }
+
+        conversions.push(quote_conversions(inner, types));
+    }
+
+    let conversions = binding::wrap_bindings(quote! {
+        #( #conversions )*
+    });
+
+    quote! {
+        #( #raw_structs )*
+        #conversions
+    }
+}
+
+/// Quotes the nullable C# type corresponding to `Option<inner>`.
+pub fn quote_cs_type(inner: &Repr, types: &TypeMap) -> TokenStream {
+    if let Repr::Option(_) = inner {
+        unreachable!("Nested optional types should have already been rejected");
+    }
+
+    let ty = generate::quote_cs_type_for_repr(inner, types);
+    if is_value_type(inner, types) {
+        quote! { #ty? }
+    } else {
+        ty
+    }
+}
+
+/// Quotes a reference to the raw struct used to represent `Option<inner>`.
+pub fn raw_type_reference(inner: &Repr, types: &TypeMap) -> TokenStream {
+    let ident = raw_ident(inner, types);
+    types.root_path(ident)
+}
+
+/// Generates the identifier of the raw struct used to represent `Option<inner>`.
+///
+/// The identifier is derived from the raw type of the inner value, e.g. the raw
+/// representation of `Option<i32>` is `__Option__int__Raw`.
+fn raw_ident(inner: &Repr, types: &TypeMap) -> Ident {
+    format_ident!("__Option__{}__Raw", binding::mangled_raw_type(inner, types))
+}
+
+/// Determines if the C# type corresponding to `repr` is a value type.
+///
+/// Value types need to be wrapped in `Nullable<T>` in order to represent `None`,
+/// whereas reference types can use `null` directly.
+fn is_value_type(repr: &Repr, types: &TypeMap) -> bool {
+    match repr {
+        Repr::Bool
+        | Repr::Char
+        | Repr::I8
+        | Repr::I16
+        | Repr::I32
+        | Repr::I64
+        | Repr::ISize
+        | Repr::U8
+        | Repr::U16
+        | Repr::U32
+        | Repr::U64
+        | Repr::USize
+        | Repr::F32
+        | Repr::F64
+        | Repr::Tuple(_) => true,
+
+        // Named types are value types if they're exported as a struct or a C-like enum.
+        // Data-carrying enums are exported as an interface, and handle types are
+        // exported as a class.
+        Repr::Named(type_name) => {
+            let export = types
+                .get(type_name)
+                .unwrap_or_else(|| panic!("No export found for named type {:?}", type_name));
+
+            match &export.binding_style {
+                BindingStyle::Handle => false,
+                BindingStyle::Value(Schema::Enum(schema)) => !schema.has_data(),
+                BindingStyle::Value(_) => true,
+            }
+        }
+
+        _ => false,
+    }
+}
+
+fn quote_raw_struct(ident: &Ident, inner: &Repr, types: &TypeMap) -> TokenStream {
+    let raw_inner = binding::raw_type_from_repr(inner, types);
+
+    quote! {
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct #ident
+        {
+            public byte IsSome;
+            public #raw_inner Value;
+
+            public #ident(#raw_inner value)
+            {
+                IsSome = 1;
+                Value = value;
+            }
+        }
+    }
+}
+
+fn quote_conversions(inner: &Repr, types: &TypeMap) -> TokenStream {
+    let raw_ty = raw_type_reference(inner, types);
+    let raw_inner = binding::raw_type_from_repr(inner, types);
+    let cs_ty = quote_cs_type(inner, types);
+    let inner_ty = generate::quote_cs_type_for_repr(inner, types);
+
+    let from_raw = binding::from_raw_fn_ident();
+    let into_raw = binding::into_raw_fn_ident();
+
+    // For nullable value types we need to unwrap the `Nullable<T>` in order to get
+    // the inner value, reference types can be passed to the inner conversion directly.
+    let (has_value, value) = if is_value_type(inner, types) {
+        (quote! { value.HasValue }, quote! { value.Value })
+    } else {
+        (quote! { value != null }, quote! { value })
+    };
+
+    quote! {
+        internal static void #from_raw(#raw_ty raw, out #cs_ty result)
+        {
+            if (raw.IsSome != 0)
+            {
+                #from_raw(raw.Value, out #inner_ty value);
+                result = value;
+            }
+            else
+            {
+                result = null;
+            }
+        }
+
+        internal static void #into_raw(#cs_ty value, out #raw_ty result)
+        {
+            if (#has_value)
+            {
+                #into_raw(#value, out #raw_inner raw);
+                result = new #raw_ty(raw);
+            }
+            else
+            {
+                result = new #raw_ty();
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Code generation for fallible functions.
+//!
+//! Exported functions that return `Result<T, E>` are exposed in C# as functions
+//! returning `T` that throw an exception if the Rust function returns an error.
+//! `Result<(), E>` is exposed as a `void` function.
+//!
+//! The thrown exception depends on the error type:
+//!
+//! * For `String` errors we throw a `RustException` using the error as the message.
+//! * For exported types we generate a `{Name}Exception` that derives from
+//!   `RustException` and carries the converted error value in its `Error` field.
+//! * For any other error type we throw a `RustException<T>`, which likewise carries
+//!   the converted error value in its `Error` field.
+//!
+//! The raw representation of a result is a struct with an `IsOk` flag followed by
+//! a union of the raw success and error values, matching the layout of
+//! `RawResult<T, E>` in the Rust runtime. As with optional values, we generate a
+//! separate raw struct for each result type that appears in the exported items.
+
+use crate::generate::{self, binding, TypeMap};
+use cs_bindgen_shared::{schematic::TypeName, Export, Repr};
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::Ident;
+
+/// Generates the raw structs, `__FromRaw` conversions, and exception types for
+/// every result type used in the exported items.
+pub fn quote_result_bindings(exports: &[Export], types: &TypeMap) -> TokenStream {
+    let mut result_types = Vec::new();
+    generate::visit_reprs(exports, |repr| {
+        if let Repr::Result { ok, err } = repr {
+            let pair = ((**ok).clone(), (**err).clone());
+            if !result_types.contains(&pair) {
+                result_types.push(pair);
+            }
+        }
+    });
+
+    let mut raw_structs = Vec::new();
+    let mut generated_structs = Vec::new();
+    let mut conversions = Vec::new();
+    let mut exceptions = Vec::new();
+    let mut generated_exceptions = Vec::new();
+    for (ok, err) in &result_types {
+        let ident = raw_ident(ok, err, types);
+        if !generated_structs.contains(&ident) {
+            raw_structs.push(quote_raw_struct(&ident, ok, err, types));
+            generated_structs.push(ident);
+        }
+
+        if !generated_exceptions.contains(err) {
+            exceptions.push(quote_exception(err, types));
+            generated_exceptions.push(err.clone());
+        }
+
+        conversions.push(quote_from_raw(ok, err, types));
+    }
+
+    let conversions = binding::wrap_bindings(quote! {
+        #( #conversions )*
+    });
+
+    quote! {
+        public class RustException : Exception
+        {
+            public RustException(string message) : base(message) { }
+        }
+
+        public class RustException<T> : RustException
+        {
+            public readonly T Error;
+
+            public RustException(T error) : base(Convert.ToString(error))
+            {
+                Error = error;
+            }
+        }
+
+        #( #exceptions )*
+        #( #raw_structs )*
+        #conversions
+    }
+}
+
+/// Quotes the C# return type for a function returning `Result<ok, _>`.
+///
+/// The error value is surfaced as an exception, so the C# type is the type of the
+/// success value, or `void` if the success value is `()`.
+pub fn quote_cs_type(ok: &Repr, types: &TypeMap) -> TokenStream {
+    match ok {
+        Repr::Unit => quote! { void },
+        _ => generate::quote_cs_type_for_repr(ok, types),
+    }
+}
+
+/// Quotes a reference to the raw struct used to represent `Result<ok, err>`.
+pub fn raw_type_reference(ok: &Repr, err: &Repr, types: &TypeMap) -> TokenStream {
+    let ident = raw_ident(ok, err, types);
+    types.root_path(ident)
+}
+
+/// Returns `true` if `repr` is a `Result<(), E>`.
+///
+/// The C# wrapper for a function returning `Result<(), E>` has a `void` return
+/// type, so the result is checked for an error without converting the success
+/// value.
+pub fn is_unit_result(repr: &Repr) -> bool {
+    match repr {
+        Repr::Result { ok, .. } => **ok == Repr::Unit,
+        _ => false,
+    }
+}
+
+fn raw_ident(ok: &Repr, err: &Repr, types: &TypeMap) -> Ident {
+    format_ident!(
+        "__Result__{}__{}__Raw",
+        binding::mangled_raw_type(ok, types),
+        binding::mangled_raw_type(err, types),
+    )
+}
+
+fn union_ident(ok: &Repr, err: &Repr, types: &TypeMap) -> Ident {
+    format_ident!("{}_Data", raw_ident(ok, err, types))
+}
+
+/// Returns the identifier of the exception type generated for the exported error
+/// type `type_name`.
+fn exception_ident(type_name: &TypeName, types: &TypeMap) -> Ident {
+    format_ident!("{}Exception", types.type_ident(type_name))
+}
+
+/// Returns a reference to the exception type thrown for the error type `err`.
+///
+/// The exception type for an exported error type is declared in the same namespace
+/// as the error type.
+fn exception_type_reference(err: &Repr, types: &TypeMap) -> TokenStream {
+    match err {
+        Repr::String | Repr::Str => types.root_path(format_ident!("RustException")),
+        Repr::Named(type_name) => types.type_path(type_name, exception_ident(type_name, types)),
+        _ => {
+            let exception = types.root_path(format_ident!("RustException"));
+            let error_ty = generate::quote_cs_type_for_repr(err, types);
+            quote! { #exception<#error_ty> }
+        }
+    }
+}
+
+fn quote_exception(err: &Repr, types: &TypeMap) -> TokenStream {
+    // Only exported error types get their own exception type, other errors use
+    // `RustException` or `RustException<T>` directly.
+    let type_name = match err {
+        Repr::Named(type_name) => type_name,
+        _ => return quote! {},
+    };
+
+    let ident = exception_ident(type_name, types);
+    let error_ty = generate::quote_cs_type_for_repr(err, types);
+
+    types.in_type_namespace(
+        type_name,
+        quote! {
+            public class #ident : RustException
+            {
+                public readonly #error_ty Error;
+
+                public #ident(#error_ty error) : base(error.ToString())
+                {
+                    Error = error;
+                }
+            }
+        },
+    )
+}
+
+fn quote_raw_struct(ident: &Ident, ok: &Repr, err: &Repr, types: &TypeMap) -> TokenStream {
+    let union_ident = union_ident(ok, err, types);
+    let raw_ok = binding::raw_type_from_repr(ok, types);
+    let raw_err = binding::raw_type_from_repr(err, types);
+
+    quote! {
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct #ident
+        {
+            public byte IsOk;
+            public #union_ident Value;
+        }
+
+        [StructLayout(LayoutKind.Explicit)]
+        internal struct #union_ident
+        {
+            [FieldOffset(0)]
+            public #raw_ok Ok;
+
+            [FieldOffset(0)]
+            public #raw_err Err;
+        }
+    }
+}
+
+fn quote_from_raw(ok: &Repr, err: &Repr, types: &TypeMap) -> TokenStream {
+    let raw_ty = raw_type_reference(ok, err, types);
+    let error_ty = generate::quote_cs_type_for_repr(err, types);
+    let exception = exception_type_reference(err, types);
+    let from_raw = binding::from_raw_fn_ident();
+
+    let throw_if_err = quote! {
+        if (raw.IsOk == 0)
+        {
+            #from_raw(raw.Value.Err, out #error_ty error);
+            throw new #exception(error);
+        }
+    };
+
+    // For `Result<(), E>` there's no success value to convert, so we generate an
+    // overload that only checks for the error case.
+    if *ok == Repr::Unit {
+        return quote! {
+            internal static void #from_raw(#raw_ty raw)
+            {
+                #throw_if_err
+            }
+        };
+    }
+
+    let ok_ty = generate::quote_cs_type_for_repr(ok, types);
+    quote! {
+        internal static void #from_raw(#raw_ty raw, out #ok_ty result)
+        {
+            #throw_if_err
+            #from_raw(raw.Value.Ok, out result);
+        }
+    }
+}
@@ -1,9 +1,13 @@
 //! Code generation for exported struct types.
 
-use crate::generate::{self, binding, TypeMap, TypeNameExt};
+use crate::generate::{
+    self, binding, docs,
+    func::{self, Receiver},
+    task, NamedTypeExt, TypeMap,
+};
 use cs_bindgen_shared::{
     schematic::{Field, StructLike},
-    BindingStyle, NamedType,
+    BindingStyle, Method, NamedType, Repr,
 };
 use heck::CamelCase;
 use proc_macro2::TokenStream;
@@ -17,56 +21,68 @@ pub fn quote_struct(export: &NamedType, schema: StructLike<'_>, types: &TypeMap)
         export.type_name,
     );
 
-    let ident = export.type_name.ident();
+    let ident = export.ident();
+    let ty = types.type_path(&export.type_name, &ident);
     let raw_ident = binding::raw_ident(&export.type_name);
 
-    let field_ident = schema
-        .fields
-        .iter()
-        .enumerate()
-        .map(|(index, field)| field_ident(field.name, index))
-        .collect::<Vec<_>>();
+    let field_ident = field_idents(&schema.fields, |index| export.field_rename(index));
 
-    let struct_fields = struct_fields(&schema.fields, types);
-    let basic_constructor = struct_constructor(&ident, &schema.fields, types);
-    let raw_fields = binding::raw_struct_fields(&schema.fields, types);
+    let struct_fields = struct_fields(
+        &schema.fields,
+        &field_ident,
+        |index| export.field_doc(index),
+        types,
+    );
+    let basic_constructor = struct_constructor(&ident, &schema.fields, &field_ident, types);
+    let raw_fields = binding::raw_struct_fields(&schema.fields, &field_ident, types);
 
     let bindings = binding::bindings_class_ident();
     let from_raw = binding::from_raw_fn_ident();
     let into_raw = binding::into_raw_fn_ident();
 
     let raw_conversions = binding::wrap_bindings(quote! {
-        internal static void #from_raw(#raw_ident raw, out #ident result)
+        internal static void #from_raw(#raw_ident raw, out #ty result)
         {
-            result = new #ident(raw);
+            result = new #ty(raw);
         }
 
-        internal static void #into_raw(#ident self, out #raw_ident result)
+        internal static void #into_raw(#ty self, out #raw_ident result)
         {
             result = new #raw_ident(self);
         }
     });
 
-    quote! {
-        public struct #ident
-        {
-            #struct_fields
-            #basic_constructor
-
-            // Generate a constructor that can initialize the struct from its raw version.
-            internal #ident(#raw_ident raw)
+    // The user-facing struct is generated in the namespace for the type, but the raw
+    // struct is kept in the root namespace along with the other raw types.
+    let docs = docs::quote_docs(export.docs.as_deref());
+    let user_struct = types.in_type_namespace(
+        &export.type_name,
+        quote! {
+            #docs
+            public partial struct #ident
             {
-                #(
-                    #bindings.#from_raw(raw.#field_ident, out this.#field_ident);
-                )*
+                #struct_fields
+                #basic_constructor
+
+                // Generate a constructor that can initialize the struct from its raw version.
+                internal #ident(#raw_ident raw)
+                {
+                    #(
+                        #bindings.#from_raw(raw.#field_ident, out this.#field_ident);
+                    )*
+                }
             }
-        }
+        },
+    );
+
+    quote! {
+        #user_struct
 
         internal struct #raw_ident
         {
             #raw_fields
 
-            internal #raw_ident(#ident self)
+            internal #raw_ident(#ty self)
             {
                 #(
                     #bindings.#into_raw(self.#field_ident, out this.#field_ident);
@@ -78,20 +94,77 @@ pub fn quote_struct(export: &NamedType, schema: StructLike<'_>, types: &TypeMap)
     }
 }
 
+/// Generates a method on a struct that is marshaled by value.
+///
+/// The method is added to the generated struct using a partial struct declaration.
+/// The struct is copied into Rust for each call, and `&mut self` methods copy the
+/// modified value back into `this` once the call completes.
+pub fn quote_method(item: &Method, export: &NamedType, types: &TypeMap) -> TokenStream {
+    let ident = export.ident();
+    let raw_ty = binding::raw_type_from_repr(&Repr::Named(item.self_type.clone()), types);
+    let receiver = item
+        .receiver
+        .as_ref()
+        .map(|style| Receiver::value(style, quote! { this }, None, raw_ty));
+
+    // NOTE: Only static methods of value types can be `async`, which is verified before
+    // the bindings are generated.
+    let name = func::fn_name(&item.name, item.rename.as_deref());
+    let docs = docs::quote_method_docs(item);
+    let wrapper_fn = if item.is_async {
+        task::quote_async_wrapper_fn(
+            &name,
+            &item.binding,
+            receiver,
+            &item.inputs,
+            item.output.as_ref(),
+            types,
+        )
+    } else {
+        func::quote_wrapper_fn(
+            &name,
+            &item.binding,
+            receiver,
+            &item.inputs,
+            item.output.as_ref(),
+            item.try_pattern,
+            types,
+        )
+    };
+
+    types.in_type_namespace(
+        &export.type_name,
+        quote! {
+            partial struct #ident
+            {
+                #docs
+                #wrapper_fn
+            }
+        },
+    )
+}
+
 /// Quotes the field declarations for the generated C# struct corresponding to an
 /// exported Rust type.
-pub fn struct_fields(fields: &[Field<'_>], types: &TypeMap) -> TokenStream {
-    let field_ident = fields
-        .iter()
-        .enumerate()
-        .map(|(index, field)| field_ident(field.name, index));
-
+///
+/// `field_ident` contains the C# ident for each field, as returned by [`field_idents`].
+/// `docs` returns the doc comment for the field at the given index, if any.
+///
+/// [`field_idents`]: fn.field_idents.html
+pub fn struct_fields<'a>(
+    fields: &[Field<'_>],
+    field_ident: &[Ident],
+    docs: impl Fn(usize) -> Option<&'a str>,
+    types: &TypeMap,
+) -> TokenStream {
     let field_ty = fields
         .iter()
         .map(|field| generate::quote_cs_type_for_schema(&field.schema, types));
+    let field_docs = (0..fields.len()).map(|index| docs::quote_docs(docs(index)));
 
     quote! {
         #(
+            #field_docs
             public #field_ty #field_ident;
         )*
     }
@@ -101,12 +174,12 @@ pub fn struct_fields(fields: &[Field<'_>], types: &TypeMap) -> TokenStream {
 ///
 /// The basic constructor has a parameter for each field in the struct, and directly
 /// assigns each field.
-pub fn struct_constructor(ident: &Ident, fields: &[Field<'_>], types: &TypeMap) -> TokenStream {
-    let field_ident = fields
-        .iter()
-        .enumerate()
-        .map(|(index, field)| field_ident(field.name, index));
-
+pub fn struct_constructor(
+    ident: &Ident,
+    fields: &[Field<'_>],
+    field_ident: &[Ident],
+    types: &TypeMap,
+) -> TokenStream {
     let arg_ident = fields
         .iter()
         .enumerate()
@@ -127,6 +200,24 @@ pub fn struct_constructor(ident: &Ident, fields: &[Field<'_>], types: &TypeMap)
     }
 }
 
+/// Determines the C# idents for the fields of a struct or enum variant.
+///
+/// `rename` returns the name specified with `#[cs_bindgen(rename = "...")]` for the
+/// field at the given index, if any.
+pub fn field_idents<'a>(
+    fields: &[Field<'_>],
+    rename: impl Fn(usize) -> Option<&'a str>,
+) -> Vec<Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match rename(index) {
+            Some(rename) => format_ident!("{}", rename),
+            None => field_ident(field.name, index),
+        })
+        .collect()
+}
+
 /// Converts the specified field name into a C#-appropriate ident, or generates an
 /// ident based on the index of the field if the field is unnamed.
 pub fn field_ident(name: Option<&str>, index: usize) -> Ident {
@@ -0,0 +1,307 @@
+//! Code generation for exported `async` functions, which are exposed in C# as
+//! functions returning a `Task`.
+//!
+//! The binding function for an `async fn` spawns the future onto the executor in the
+//! Rust runtime and returns immediately. In place of a return value, it takes a
+//! `RawCompletion`, which matches the layout of `RawCompletion` in the Rust runtime:
+//! A `GCHandle` for a `TaskCompletionSource` and a pointer to a trampoline that
+//! completes it. We generate a trampoline for each output type used by an `async`
+//! function, which is invoked from the executor's worker thread once the future
+//! completes.
+//!
+//! If the future panics, the trampoline is still invoked, and the panic is reported
+//! the same way as for a regular call into Rust. The trampoline checks for a panic
+//! before converting the output, and fails the task with the resulting exception.
+//! Errors returned as a `Result` likewise fail the task.
+//!
+//! The task's continuations are run asynchronously, so that awaiting code never runs
+//! on the executor's worker thread.
+
+use crate::generate::{
+    self, binding,
+    func::{self, Receiver},
+    result, TypeMap,
+};
+use cs_bindgen_shared::{Export, FnArg, Repr};
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::Ident;
+
+/// Generates the trampolines for every output type used by an exported `async`
+/// function, along with the built-in types used by all `async` functions.
+pub fn quote_task_bindings(exports: &[Export], types: &TypeMap) -> TokenStream {
+    let mut generated = Vec::new();
+    let mut bindings = Vec::new();
+    for export in exports {
+        let output = match export {
+            Export::Fn(export) if export.is_async => export.output.as_ref(),
+            Export::Method(export) if export.is_async => export.output.as_ref(),
+            _ => continue,
+        };
+
+        let ident = completion_ident(output, types);
+        if generated.contains(&ident) {
+            continue;
+        }
+
+        bindings.push(quote_completion(&ident, output, types));
+        generated.push(ident);
+    }
+
+    let bindings = binding::wrap_bindings(quote! {
+        // Frees the `GCHandle` for a completion that was never passed to Rust, i.e.
+        // because the call threw before the future was spawned.
+        internal static void __DropCompletion(RawCompletion completion)
+        {
+            GCHandle.FromIntPtr(completion.Context).Free();
+        }
+
+        #( #bindings )*
+    });
+
+    quote! {
+        #bindings
+
+        // Raw representation of the completion for a call to an `async` function. This
+        // needs to match the layout of `RawCompletion` on the Rust side.
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct RawCompletion
+        {
+            public IntPtr Context;
+            public IntPtr Complete;
+
+            public RawCompletion(IntPtr context, IntPtr complete)
+            {
+                Context = context;
+                Complete = complete;
+            }
+        }
+    }
+}
+
+/// Quotes the C# return type for an `async` function with the given output, i.e.
+/// `Task<T>`, or `Task` if the function doesn't return a value.
+pub fn quote_cs_type(output: Option<&Repr>, types: &TypeMap) -> TokenStream {
+    match output {
+        Some(output) if !is_void(output) => {
+            let output = generate::quote_cs_type_for_repr(output, types);
+            quote! { Task<#output> }
+        }
+        _ => quote! { Task },
+    }
+}
+
+/// Quotes the declaration of the completion argument for the raw binding to an
+/// `async` function.
+pub fn quote_completion_arg() -> TokenStream {
+    quote! { RawCompletion completion }
+}
+
+/// Generates a C# wrapper function for an `async` function, which starts the call
+/// and returns a `Task` for its result.
+///
+/// A borrowed receiver has to stay alive until the future completes, so the
+/// reference added to it is only released once the task completes, and `&mut self`
+/// calls are refused until then. The returned task only completes once the
+/// reference has been released.
+pub fn quote_async_wrapper_fn(
+    name: &str,
+    binding: &str,
+    mut receiver: Option<Receiver>,
+    inputs: &[FnArg],
+    output: Option<&Repr>,
+    types: &TypeMap,
+) -> TokenStream {
+    let name = format_ident!("{}", name);
+    let return_ty = quote_cs_type(output, types);
+    let static_ = if Receiver::is_static(receiver.as_ref()) {
+        quote! { static }
+    } else {
+        TokenStream::default()
+    };
+
+    let receiver_param = receiver
+        .as_ref()
+        .and_then(|receiver| receiver.param.clone());
+    let args = receiver_param
+        .into_iter()
+        .chain(func::quote_args(inputs, types));
+
+    // A borrowed receiver is borrowed by the task rather than by the call itself, so its
+    // setup and release are run outside of the call. A receiver that is moved into Rust
+    // is left for the call to take once the arguments have been converted, see
+    // `func::quote_wrapper_body`.
+    let (setup_receiver, release_receiver) = match &mut receiver {
+        Some(receiver) if !receiver.release.is_empty() => {
+            receiver.setup = TokenStream::new();
+            receiver.release = TokenStream::new();
+            (
+                quote! { IntPtr __self = this.__AddTaskRef(); },
+                quote! { this.__ReleaseTaskRef(); },
+            )
+        }
+        _ => Default::default(),
+    };
+    let return_task = if release_receiver.is_empty() {
+        quote! { return __tcs.Task; }
+    } else {
+        quote! {
+            return __tcs.Task.ContinueWith(
+                __task =>
+                {
+                    #release_receiver
+                    return __task;
+                },
+                TaskContinuationOptions.ExecuteSynchronously).Unwrap();
+        }
+    };
+
+    let bindings = binding::bindings_class_ident();
+    let ptr_field = format_ident!("{}__Ptr", completion_ident(output, types));
+    let (value_ty, _) = completion_value(output, types);
+    let body = func::quote_wrapper_body(
+        binding,
+        receiver,
+        inputs,
+        None,
+        TokenStream::new(),
+        Some(quote! { __completion }),
+        types,
+    );
+
+    quote! {
+        public #static_ #return_ty #name(#( #args ),*)
+        {
+            #setup_receiver
+            var __tcs = new TaskCompletionSource<#value_ty>(
+                TaskCreationOptions.RunContinuationsAsynchronously);
+            var __completion = new RawCompletion(
+                GCHandle.ToIntPtr(GCHandle.Alloc(__tcs)),
+                #bindings.#ptr_field);
+
+            try
+            {
+                unsafe
+                {
+                    #body
+                }
+            }
+            catch
+            {
+                #bindings.__DropCompletion(__completion);
+                #release_receiver
+                throw;
+            }
+
+            #return_task
+        }
+    }
+}
+
+/// Returns `true` if an `async` function with the given output completes without a
+/// value, i.e. if it's exposed as returning a non-generic `Task`.
+fn is_void(output: &Repr) -> bool {
+    *output == Repr::Unit || result::is_unit_result(output)
+}
+
+/// Returns the type argument of the `TaskCompletionSource` for an `async` function,
+/// along with the statements that convert the raw output and complete the task.
+///
+/// `TaskCompletionSource` has no non-generic version, so functions that complete
+/// without a value use `TaskCompletionSource<bool>` and return it as a `Task`.
+fn completion_value(output: Option<&Repr>, types: &TypeMap) -> (TokenStream, TokenStream) {
+    let from_raw = binding::from_raw_fn_ident();
+    match output {
+        None | Some(Repr::Unit) => (quote! { bool }, quote! { tcs.SetResult(true); }),
+        Some(output) if result::is_unit_result(output) => (
+            quote! { bool },
+            quote! {
+                #from_raw(result);
+                tcs.SetResult(true);
+            },
+        ),
+        Some(output) => {
+            let output_ty = generate::quote_cs_type_for_repr(output, types);
+            (
+                output_ty.clone(),
+                quote! {
+                    #from_raw(result, out #output_ty __result);
+                    tcs.SetResult(__result);
+                },
+            )
+        }
+    }
+}
+
+/// Generates the identifier of the completion trampoline for an `async` function
+/// with the given output.
+///
+/// The identifier is derived from both the raw type and the C# type of the output,
+/// since different C# types may share a raw type (e.g. `bool` and `byte`), and
+/// `Result<(), E>` completes a non-generic `Task` for any error type.
+fn completion_ident(output: Option<&Repr>, types: &TypeMap) -> Ident {
+    let output = match output {
+        Some(output) if *output != Repr::Unit => output,
+        _ => return format_ident!("__Completion__Void"),
+    };
+
+    let raw_ty = binding::mangled_raw_type(output, types);
+    let raw_ty = raw_ty.trim_start_matches('_');
+    if result::is_unit_result(output) {
+        return format_ident!("__Completion__{}", raw_ty);
+    }
+
+    // References to exported types are fully-qualified, so strip the root namespace
+    // in order to keep the identifiers reasonably short.
+    let root = types.root_path(TokenStream::new()).to_string();
+    let cs_ty = generate::quote_cs_type_for_repr(output, types)
+        .to_string()
+        .replace(&root, "")
+        .replace("global ::", "");
+    let mangled = binding::mangle(&cs_ty.replace(' ', ""));
+    format_ident!(
+        "__Completion__{}__{}",
+        raw_ty,
+        mangled.trim_end_matches('_'),
+    )
+}
+
+/// Generates the completion trampoline for an `async` function with the given
+/// output.
+fn quote_completion(ident: &Ident, output: Option<&Repr>, types: &TypeMap) -> TokenStream {
+    let raw_output = binding::raw_type_from_repr(output.unwrap_or(&Repr::Unit), types);
+    let (value_ty, set_result) = completion_value(output, types);
+    let check_panic = binding::check_panic_fn_ident();
+
+    let invoke_fn = format_ident!("__Invoke{}", ident);
+    let delegate_field = format_ident!("{}__Delegate", ident);
+    let ptr_field = format_ident!("{}__Ptr", ident);
+
+    quote! {
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        internal delegate void #ident(IntPtr context, #raw_output result);
+
+        [MonoPInvokeCallback(typeof(#ident))]
+        private static void #invoke_fn(IntPtr context, #raw_output result)
+        {
+            var handle = GCHandle.FromIntPtr(context);
+            var tcs = (TaskCompletionSource<#value_ty>)handle.Target;
+            handle.Free();
+
+            // NOTE: Exceptions can't unwind into Rust, so a panic in the future or an
+            // error converting the result fails the task instead.
+            try
+            {
+                #check_panic();
+                #set_result
+            }
+            catch (Exception e)
+            {
+                tcs.SetException(e);
+            }
+        }
+
+        private static readonly #ident #delegate_field = #invoke_fn;
+        internal static readonly IntPtr #ptr_field = Marshal.GetFunctionPointerForDelegate(#delegate_field);
+    }
+}
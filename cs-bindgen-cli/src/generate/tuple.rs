@@ -0,0 +1,172 @@
+//! Code generation for tuples.
+//!
+//! Rust tuples are exposed in C# as `ValueTuple`, e.g. `(i32, String)` becomes
+//! `(int, string)`. Single-element tuples don't have a literal syntax in C#, so
+//! they're exposed as `ValueTuple<T>`.
+//!
+//! The raw representation of a tuple is a struct with a field for the raw
+//! representation of each element, matching the layout of the `RawTupleN` structs
+//! in the Rust runtime. As with optional values, we generate a separate raw struct
+//! for each tuple type that appears in the exported items.
+
+use crate::generate::{self, binding, TypeMap};
+use cs_bindgen_shared::{Export, Repr};
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::Ident;
+
+/// Generates the raw structs and `__FromRaw`/`__IntoRaw` conversions for every
+/// tuple type used in the exported items.
+pub fn quote_tuple_bindings(exports: &[Export], types: &TypeMap) -> TokenStream {
+    let mut tuple_types = Vec::new();
+    generate::visit_reprs(exports, |repr| {
+        if let Repr::Tuple(elements) = repr {
+            if !tuple_types.contains(elements) {
+                tuple_types.push(elements.clone());
+            }
+        }
+    });
+
+    // Multiple tuple types may share the same raw representation (e.g. `(bool, u8)`
+    // and `(u8, u8)` are both passed as a pair of `byte`s), so we only generate each
+    // raw struct once.
+    let mut raw_structs = Vec::new();
+    let mut generated_structs = Vec::new();
+    let mut conversions = Vec::new();
+    for elements in &tuple_types {
+        let ident = raw_ident(elements, types);
+        if !generated_structs.contains(&ident) {
+            raw_structs.push(quote_raw_struct(&ident, elements, types));
+            generated_structs.push(ident);
+        }
+
+        conversions.push(quote_conversions(elements, types));
+    }
+
+    let conversions = binding::wrap_bindings(quote! {
+        #( #conversions )*
+    });
+
+    quote! {
+        #( #raw_structs )*
+        #conversions
+    }
+}
+
+/// Quotes the `ValueTuple` type corresponding to a tuple with the given elements.
+pub fn quote_cs_type(elements: &[Repr], types: &TypeMap) -> TokenStream {
+    let element = elements
+        .iter()
+        .map(|repr| generate::quote_cs_type_for_repr(repr, types));
+
+    // NOTE: C# doesn't have a literal syntax for single-element tuples, so we have to
+    // name the `ValueTuple` type explicitly.
+    if elements.len() == 1 {
+        quote! { ValueTuple<#( #element )*> }
+    } else {
+        quote! { ( #( #element ),* ) }
+    }
+}
+
+/// Quotes a reference to the raw struct used to represent a tuple with the given
+/// elements.
+pub fn raw_type_reference(elements: &[Repr], types: &TypeMap) -> TokenStream {
+    let ident = raw_ident(elements, types);
+    types.root_path(ident)
+}
+
+/// Generates the identifier of the raw struct used to represent a tuple.
+///
+/// The identifier is derived from the raw types of the elements, e.g. the raw
+/// representation of `(i32, f32)` is `__Tuple__int__float__Raw`.
+fn raw_ident(elements: &[Repr], types: &TypeMap) -> Ident {
+    let mangled = elements
+        .iter()
+        .map(|repr| binding::mangled_raw_type(repr, types))
+        .collect::<Vec<_>>();
+    format_ident!("__Tuple__{}__Raw", mangled.join("__"))
+}
+
+/// Generates the names of the fields of a tuple, i.e. `Item1`, `Item2`, etc.
+///
+/// These match the names of the fields of `ValueTuple`, so the same identifiers are
+/// used for the fields of the raw struct.
+fn item_idents(elements: &[Repr]) -> Vec<Ident> {
+    (1..=elements.len())
+        .map(|index| format_ident!("Item{}", index))
+        .collect()
+}
+
+fn quote_raw_struct(ident: &Ident, elements: &[Repr], types: &TypeMap) -> TokenStream {
+    let item = item_idents(elements);
+    let arg = (1..=elements.len())
+        .map(|index| format_ident!("item{}", index))
+        .collect::<Vec<_>>();
+    let raw_element = elements
+        .iter()
+        .map(|repr| binding::raw_type_from_repr(repr, types))
+        .collect::<Vec<_>>();
+
+    quote! {
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct #ident
+        {
+            #(
+                public #raw_element #item;
+            )*
+
+            public #ident(#( #raw_element #arg ),*)
+            {
+                #(
+                    #item = #arg;
+                )*
+            }
+        }
+    }
+}
+
+fn quote_conversions(elements: &[Repr], types: &TypeMap) -> TokenStream {
+    let raw_ty = raw_type_reference(elements, types);
+    let cs_ty = quote_cs_type(elements, types);
+    let item = item_idents(elements);
+    let temp = (1..=elements.len())
+        .map(|index| format_ident!("item{}", index))
+        .collect::<Vec<_>>();
+    let element_ty = elements
+        .iter()
+        .map(|repr| generate::quote_cs_type_for_repr(repr, types))
+        .collect::<Vec<_>>();
+    let raw_element = elements
+        .iter()
+        .map(|repr| binding::raw_type_from_repr(repr, types))
+        .collect::<Vec<_>>();
+
+    let from_raw = binding::from_raw_fn_ident();
+    let into_raw = binding::into_raw_fn_ident();
+
+    // Build the tuple from the converted elements. Single-element tuples have to be
+    // constructed explicitly, since `(item1)` is just a parenthesized expression.
+    let build_tuple = if elements.len() == 1 {
+        quote! { new #cs_ty(#( #temp )*) }
+    } else {
+        quote! { ( #( #temp ),* ) }
+    };
+
+    quote! {
+        internal static void #from_raw(#raw_ty raw, out #cs_ty result)
+        {
+            #(
+                #from_raw(raw.#item, out #element_ty #temp);
+            )*
+            result = #build_tuple;
+        }
+
+        internal static void #into_raw(#cs_ty value, out #raw_ty result)
+        {
+            #(
+                #into_raw(value.#item, out #raw_element #temp);
+            )*
+            result = new #raw_ty(#( #temp ),*);
+        }
+    }
+}
@@ -44,4 +44,15 @@ pub struct Opt {
 
     #[structopt(short, long, parse(from_os_str))]
     output: Option<PathBuf>,
+
+    /// The namespace to generate the bindings in, e.g. `MyGame.Bindings`. By default
+    /// the bindings are generated in the global namespace.
+    #[structopt(short, long)]
+    namespace: Option<String>,
+
+    /// Generate each exported type in a namespace based on the Rust module that it's
+    /// declared in, e.g. `my_crate::foo::Bar` is generated as `Foo.Bar` within the
+    /// root namespace.
+    #[structopt(long)]
+    module_namespaces: bool,
 }
@@ -0,0 +1,334 @@
+//! Parsing for the options that can be specified in the `#[cs_bindgen]` attribute.
+//!
+//! Options can be specified directly on the annotated item, e.g.
+//! `#[cs_bindgen(try_pattern)]` on a free function. Methods in an `impl` block are
+//! configured by adding a `#[cs_bindgen(...)]` attribute to the method itself,
+//! which is removed from the original impl block when the bindings are generated.
+//! The methods of an exported trait can likewise be renamed with a
+//! `#[cs_bindgen(rename = "...")]` attribute on the method.
+//!
+//! Fields, enum variants and function arguments can similarly be renamed with a
+//! `#[cs_bindgen(rename = "...")]` attribute, which is also removed from the original
+//! item.
+
+use syn::*;
+
+/// The options that can be specified for an exported function or method.
+#[derive(Debug, Clone, Default)]
+pub struct FnAttrs {
+    /// Generate a `bool TryFoo(.., out T value, out E error)` wrapper for a function
+    /// returning `Result<T, E>`, rather than throwing an exception on error.
+    pub try_pattern: bool,
+
+    /// Expose the returned value as an `IEnumerable<T>`, as specified by the `iterator`
+    /// option. Only needed for functions returning a concrete iterator type, since
+    /// functions returning `impl Iterator<Item = T>` are detected automatically.
+    pub iterator: bool,
+
+    /// Whether a static method is exposed as a constructor (`Some(true)`) or as a
+    /// static factory method (`Some(false)`), as specified by the `constructor` and
+    /// `factory` options. Only valid for methods.
+    pub constructor: Option<bool>,
+
+    /// Whether the method is exposed as a property accessor, as specified by the
+    /// `getter` and `setter` options. Only valid for methods.
+    pub accessor: Option<Accessor>,
+
+    /// The name to use for the function in the generated bindings, as specified by the
+    /// `rename` option.
+    pub rename: Option<String>,
+}
+
+/// The kind of property accessor specified for a method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accessor {
+    Getter,
+    Setter,
+}
+
+impl FnAttrs {
+    /// Parses the arguments of the `#[cs_bindgen]` attribute on a free function.
+    pub fn from_args(args: AttributeArgs) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for arg in args {
+            match &arg {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("try_pattern") => {
+                    result.try_pattern = true;
+                }
+
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("iterator") => {
+                    result.iterator = true;
+                }
+
+                NestedMeta::Meta(Meta::Path(path))
+                    if path.is_ident("constructor") || path.is_ident("factory") =>
+                {
+                    if result.constructor.is_some() {
+                        return Err(Error::new_spanned(
+                            arg,
+                            "Only one of `constructor` or `factory` may be specified",
+                        ));
+                    }
+
+                    result.constructor = Some(path.is_ident("constructor"));
+                }
+
+                NestedMeta::Meta(Meta::Path(path))
+                    if path.is_ident("getter") || path.is_ident("setter") =>
+                {
+                    if result.accessor.is_some() {
+                        return Err(Error::new_spanned(
+                            arg,
+                            "Only one of `getter` or `setter` may be specified",
+                        ));
+                    }
+
+                    result.accessor = Some(if path.is_ident("getter") {
+                        Accessor::Getter
+                    } else {
+                        Accessor::Setter
+                    });
+                }
+
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("rename") => {
+                    result.rename = Some(parse_rename(meta)?);
+                }
+
+                _ => {
+                    return Err(Error::new_spanned(
+                        arg,
+                        "Unrecognized option for `#[cs_bindgen]`",
+                    ))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Parses the `#[cs_bindgen(...)]` attributes on a method.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        Self::from_args(collect_args(attrs)?)
+    }
+}
+
+/// The options that can be specified for an exported struct or enum, or for the
+/// fields and variants of an exported type and the arguments of an exported function.
+#[derive(Debug, Clone, Default)]
+pub struct RenameAttrs {
+    /// The name to use in the generated bindings, as specified by the `rename` option.
+    pub rename: Option<String>,
+}
+
+impl RenameAttrs {
+    /// Parses the arguments of the `#[cs_bindgen]` attribute on a struct or enum.
+    pub fn from_args(args: AttributeArgs) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for arg in args {
+            match &arg {
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.path.is_ident("rename") => {
+                    result.rename = Some(parse_rename(meta)?);
+                }
+
+                _ => {
+                    return Err(Error::new_spanned(
+                        arg,
+                        "Unrecognized option for `#[cs_bindgen]`",
+                    ))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Parses the `#[cs_bindgen(...)]` attributes on a field, variant, or argument.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        Self::from_args(collect_args(attrs)?)
+    }
+}
+
+/// The names specified for an exported type and its members.
+#[derive(Debug, Clone, Default)]
+pub struct TypeRenames {
+    pub rename: Option<String>,
+
+    /// The names specified for the fields of a struct, in declaration order.
+    pub fields: Vec<Option<String>>,
+
+    /// The names specified for the variants of an enum, in declaration order.
+    pub variants: Vec<VariantRenames>,
+}
+
+/// The names specified for an enum variant and its fields.
+#[derive(Debug, Clone, Default)]
+pub struct VariantRenames {
+    pub rename: Option<String>,
+    pub fields: Vec<Option<String>>,
+}
+
+impl TypeRenames {
+    pub fn from_struct(attrs: RenameAttrs, item: &ItemStruct) -> syn::Result<Self> {
+        Ok(Self {
+            rename: attrs.rename,
+            fields: field_renames(&item.fields)?,
+            variants: Vec::new(),
+        })
+    }
+
+    pub fn from_enum(attrs: RenameAttrs, item: &ItemEnum) -> syn::Result<Self> {
+        let variants = item
+            .variants
+            .iter()
+            .map(|variant| {
+                Ok(VariantRenames {
+                    rename: RenameAttrs::from_attrs(&variant.attrs)?.rename,
+                    fields: field_renames(&variant.fields)?,
+                })
+            })
+            .collect::<syn::Result<_>>()?;
+
+        Ok(Self {
+            rename: attrs.rename,
+            fields: Vec::new(),
+            variants,
+        })
+    }
+}
+
+fn field_renames(fields: &Fields) -> syn::Result<Vec<Option<String>>> {
+    fields
+        .iter()
+        .map(|field| RenameAttrs::from_attrs(&field.attrs).map(|attrs| attrs.rename))
+        .collect()
+}
+
+/// Collects the options from all of the `#[cs_bindgen(...)]` attributes in `attrs`.
+fn collect_args(attrs: &[Attribute]) -> syn::Result<AttributeArgs> {
+    let mut args = AttributeArgs::new();
+    for attr in attrs.iter().filter(|attr| is_cs_bindgen_attr(attr)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => args.extend(list.nested),
+
+            // A bare `#[cs_bindgen]` doesn't specify any options.
+            Meta::Path(_) => {}
+
+            meta @ Meta::NameValue(_) => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "Expected options list, e.g. `#[cs_bindgen(try_pattern)]`",
+                ))
+            }
+        }
+    }
+
+    Ok(args)
+}
+
+/// Parses the value of a `rename = "..."` option, verifying that the new name is a
+/// valid identifier.
+fn parse_rename(meta: &MetaNameValue) -> syn::Result<String> {
+    let name = match &meta.lit {
+        Lit::Str(lit) => lit.value(),
+        lit => {
+            return Err(Error::new_spanned(
+                lit,
+                "Expected a string, e.g. `rename = \"Foo\"`",
+            ))
+        }
+    };
+
+    let mut chars = name.chars();
+    let is_valid = chars
+        .next()
+        .map(|c| c.is_alphabetic() || c == '_')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    if !is_valid {
+        return Err(Error::new_spanned(
+            &meta.lit,
+            format!("`{}` is not a valid identifier", name),
+        ));
+    }
+
+    Ok(name)
+}
+
+/// Generates an error if any options were specified for an item that doesn't
+/// support them.
+pub fn reject_args(args: &AttributeArgs) -> syn::Result<()> {
+    match args.first() {
+        Some(arg) => Err(Error::new_spanned(
+            arg,
+            "Options are not supported for this item with `#[cs_bindgen]`",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Removes the `#[cs_bindgen(...)]` attributes from the methods in an impl block,
+/// and the `#[cs_bindgen(...)]` attributes and doc comments from the arguments of
+/// those methods.
+///
+/// The original impl block is emitted as part of the macro output, so any helper
+/// attributes need to be removed to avoid having them expanded as separate
+/// invocations of the macro.
+pub fn strip_method_attrs(item: &mut ItemImpl) {
+    for item in &mut item.items {
+        if let ImplItem::Method(method) = item {
+            strip_attrs(&mut method.attrs);
+            strip_arg_attrs(&mut method.sig);
+        }
+    }
+}
+
+/// Removes the `#[cs_bindgen(...)]` attributes from the methods of a trait, and the
+/// `#[cs_bindgen(...)]` attributes and doc comments from the arguments of those
+/// methods.
+pub fn strip_trait_method_attrs(item: &mut ItemTrait) {
+    for item in &mut item.items {
+        if let TraitItem::Method(method) = item {
+            strip_attrs(&mut method.attrs);
+            strip_arg_attrs(&mut method.sig);
+        }
+    }
+}
+
+/// Removes the `#[cs_bindgen(...)]` attributes from the arguments of a function.
+///
+/// Doc comments are also removed, since rustc doesn't allow doc comments on function
+/// arguments.
+pub fn strip_arg_attrs(signature: &mut Signature) {
+    for arg in &mut signature.inputs {
+        if let FnArg::Typed(arg) = arg {
+            strip_attrs(&mut arg.attrs);
+            arg.attrs.retain(|attr| !attr.path.is_ident("doc"));
+        }
+    }
+}
+
+/// Removes the `#[cs_bindgen(...)]` attributes from the fields of a struct or variant.
+pub fn strip_field_attrs(fields: &mut Fields) {
+    for field in fields {
+        strip_attrs(&mut field.attrs);
+    }
+}
+
+/// Removes the `#[cs_bindgen(...)]` attributes from the variants of an enum, and from
+/// the fields of those variants.
+pub fn strip_variant_attrs(item: &mut ItemEnum) {
+    for variant in &mut item.variants {
+        strip_attrs(&mut variant.attrs);
+        strip_field_attrs(&mut variant.fields);
+    }
+}
+
+fn strip_attrs(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| !is_cs_bindgen_attr(attr));
+}
+
+fn is_cs_bindgen_attr(attr: &Attribute) -> bool {
+    attr.path.is_ident("cs_bindgen")
+}
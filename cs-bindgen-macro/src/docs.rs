@@ -0,0 +1,145 @@
+//! Extraction of doc comments from exported items.
+//!
+//! Doc comments are captured in the exported metadata so that they can be included
+//! in the generated bindings. Doc comments on function arguments are also supported,
+//! even though rustc doesn't normally allow them, since they're removed from the
+//! original function along with the other helper attributes.
+
+use syn::*;
+
+/// The doc comment for an exported function or method.
+#[derive(Debug, Clone, Default)]
+pub struct FnDocs {
+    /// The doc comment, not including the `# Returns` section.
+    pub docs: Option<String>,
+
+    /// The contents of the `# Returns` section of the doc comment.
+    pub returns: Option<String>,
+}
+
+impl FnDocs {
+    pub fn from_attrs(attrs: &[Attribute]) -> Self {
+        let docs = match extract_docs(attrs) {
+            Some(docs) => docs,
+            None => return Self::default(),
+        };
+
+        // Split out the `# Returns` section, which ends at the next heading. Lines in
+        // code blocks are skipped, since a line starting with `#` in a code block is
+        // a hidden line rather than a heading.
+        let mut remaining = Vec::new();
+        let mut returns = Vec::new();
+        let mut in_returns = false;
+        let mut in_code_block = false;
+        for line in docs.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            } else if !in_code_block && line.starts_with('#') {
+                in_returns = line.trim_start_matches('#').trim() == "Returns";
+                if in_returns {
+                    continue;
+                }
+            }
+
+            if in_returns {
+                returns.push(line);
+            } else {
+                remaining.push(line);
+            }
+        }
+
+        Self {
+            docs: join_lines(&remaining),
+            returns: join_lines(&returns),
+        }
+    }
+}
+
+/// The doc comments for an exported type and its members.
+#[derive(Debug, Clone, Default)]
+pub struct TypeDocs {
+    pub docs: Option<String>,
+
+    /// The doc comments for the fields of a struct, in declaration order.
+    pub fields: Vec<Option<String>>,
+
+    /// The doc comments for the variants of an enum, in declaration order.
+    pub variants: Vec<VariantDocs>,
+}
+
+/// The doc comments for an enum variant and its fields.
+#[derive(Debug, Clone, Default)]
+pub struct VariantDocs {
+    pub docs: Option<String>,
+    pub fields: Vec<Option<String>>,
+}
+
+impl TypeDocs {
+    pub fn from_struct(item: &ItemStruct) -> Self {
+        Self {
+            docs: extract_docs(&item.attrs),
+            fields: field_docs(&item.fields),
+            variants: Vec::new(),
+        }
+    }
+
+    pub fn from_enum(item: &ItemEnum) -> Self {
+        let variants = item
+            .variants
+            .iter()
+            .map(|variant| VariantDocs {
+                docs: extract_docs(&variant.attrs),
+                fields: field_docs(&variant.fields),
+            })
+            .collect();
+
+        Self {
+            docs: extract_docs(&item.attrs),
+            fields: Vec::new(),
+            variants,
+        }
+    }
+}
+
+fn field_docs(fields: &Fields) -> Vec<Option<String>> {
+    fields
+        .iter()
+        .map(|field| extract_docs(&field.attrs))
+        .collect()
+}
+
+/// Collects the contents of all of the `#[doc = "..."]` attributes in `attrs`.
+///
+/// Each `///` line is desugared into a separate attribute with a leading space, which
+/// is removed. Returns `None` if there is no doc comment.
+pub fn extract_docs(attrs: &[Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("doc")) {
+        // Ignore other uses of the `doc` attribute, e.g. `#[doc(hidden)]`, as well as
+        // docs that aren't a string literal, e.g. `#[doc = include_str!("...")]`.
+        if let Ok(Meta::NameValue(MetaNameValue {
+            lit: Lit::Str(lit), ..
+        })) = attr.parse_meta()
+        {
+            // NOTE: `split` is used instead of `lines` so that a blank `///` line,
+            // which separates paragraphs, is kept as an empty line.
+            let value = lit.value();
+            lines.extend(
+                value
+                    .split('\n')
+                    .map(|line| line.strip_prefix(' ').unwrap_or(line).to_string()),
+            );
+        }
+    }
+
+    let lines = lines.iter().map(String::as_str).collect::<Vec<_>>();
+    join_lines(&lines)
+}
+
+/// Joins `lines` into a single string, trimming any leading and trailing blank lines.
+fn join_lines(lines: &[&str]) -> Option<String> {
+    let is_blank = |line: &&str| line.trim().is_empty();
+    let start = lines.iter().position(|line| !is_blank(line))?;
+    let end = lines.iter().rposition(|line| !is_blank(line))?;
+    Some(lines[start..=end].join("\n"))
+}
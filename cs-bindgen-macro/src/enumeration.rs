@@ -1,17 +1,24 @@
 use crate::{
-    describe_named_type, impl_named, quote_convert_list_fn, quote_index_fn, quote_vec_drop_fn,
-    reject_generics, repr_impl, value, BindingStyle,
+    attr::{RenameAttrs, TypeRenames},
+    describe_named_type,
+    docs::TypeDocs,
+    func::reject_callbacks,
+    impl_named, quote_convert_list_fn, quote_index_fn, quote_vec_drop_fn, reject_generics,
+    repr_impl, value, BindingStyle,
 };
 use proc_macro2::{Literal, TokenStream};
 use quote::*;
 use syn::*;
 
-pub fn quote_enum_item(item: ItemEnum) -> syn::Result<TokenStream> {
+pub fn quote_enum_item(item: ItemEnum, attrs: RenameAttrs) -> syn::Result<TokenStream> {
     reject_generics(
         &item.generics,
         "Generic enums not supported with `#[cs_bindgen]`",
     )?;
 
+    let renames = TypeRenames::from_enum(attrs, &item)?;
+    let docs = TypeDocs::from_enum(&item);
+
     let named_impl = impl_named(&item.ident);
 
     // Derive `Describe` for the enum.
@@ -34,7 +41,7 @@ pub fn quote_enum_item(item: ItemEnum) -> syn::Result<TokenStream> {
 
     // Export a function that describes the exported type.
     let ident = &item.ident;
-    let describe_fn = describe_named_type(&ident, BindingStyle::Value);
+    let describe_fn = describe_named_type(&ident, BindingStyle::Value, &renames, &docs);
 
     Ok(quote! {
         #named_impl
@@ -113,6 +120,7 @@ fn quote_simple_enum(item: &ItemEnum) -> syn::Result<TokenStream> {
     let index_fn = quote_index_fn(&ident);
     let convert_list_fn = quote_convert_list_fn(&ident);
     let drop_vec_fn = quote_vec_drop_fn(&ident);
+    let receiver_impl = value::quote_receiver_impl(ident);
 
     Ok(quote! {
         #(
@@ -151,6 +159,7 @@ fn quote_simple_enum(item: &ItemEnum) -> syn::Result<TokenStream> {
             }
         }
 
+        #receiver_impl
         #index_fn
         #convert_list_fn
         #drop_vec_fn
@@ -161,6 +170,14 @@ fn quote_complex_enum(item: &ItemEnum) -> syn::Result<TokenStream> {
     let ident = &item.ident;
     let abi_union_ty = format_binding_ident!(ident);
 
+    // Callbacks can only be passed from C# to Rust, so they can't be carried by a type
+    // that's marshaled by value.
+    for variant in &item.variants {
+        for field in &variant.fields {
+            reject_callbacks(&field.ty)?;
+        }
+    }
+
     // TODO: Check the repr of the enum to determine the actual discriminant type.
     let discriminant_ty = quote! { isize };
 
@@ -303,6 +320,7 @@ fn quote_complex_enum(item: &ItemEnum) -> syn::Result<TokenStream> {
     let index_fn = quote_index_fn(ident);
     let convert_list_fn = quote_convert_list_fn(ident);
     let vec_drop_fn = quote_vec_drop_fn(ident);
+    let receiver_impl = value::quote_receiver_impl(ident);
 
     Ok(quote! {
         #[repr(C)]
@@ -343,6 +361,7 @@ fn quote_complex_enum(item: &ItemEnum) -> syn::Result<TokenStream> {
             }
         }
 
+        #receiver_impl
         #index_fn
         #convert_list_fn
         #vec_drop_fn
@@ -1,8 +1,14 @@
 //! Helper functions for generating raw bindings and descriptor functions.
 
-use proc_macro2::TokenStream;
+use crate::{attr::RenameAttrs, docs};
+use proc_macro2::{Span, TokenStream};
 use quote::*;
-use syn::{punctuated::Punctuated, token::Comma, *};
+use syn::{
+    punctuated::Punctuated,
+    token::Comma,
+    visit_mut::{self, VisitMut},
+    *,
+};
 
 type FnInput = (Ident, Box<Type>);
 
@@ -33,7 +39,74 @@ pub fn extract_inputs(inputs: Punctuated<FnArg, Comma>) -> syn::Result<Vec<FnInp
                 _ => format_ident!("__arg{}", index),
             };
 
-            Ok((ident, arg.ty))
+            Ok((ident, box_impl_fn(arg.ty)))
+        })
+        .collect()
+}
+
+/// Replaces an `impl Fn(..) -> R` argument type with a boxed callback.
+///
+/// The binding function can't be generic, so a callback passed as `impl Fn` (or
+/// `impl FnMut`/`impl FnOnce`) is received as `Box<dyn Fn(..) -> R + Send + Sync>`
+/// instead. The boxed callback implements all of the `Fn` traits, as well as `Send`
+/// and `Sync`, so it can be passed to the original function regardless of what
+/// bounds the argument has.
+fn box_impl_fn(ty: Box<Type>) -> Box<Type> {
+    let bounds = match &*ty {
+        Type::ImplTrait(impl_trait) => &impl_trait.bounds,
+        _ => return ty,
+    };
+
+    let fn_args = bounds.iter().find_map(|bound| match bound {
+        TypeParamBound::Trait(bound) => {
+            let segment = bound.path.segments.last()?;
+            match &segment.arguments {
+                PathArguments::Parenthesized(args)
+                    if segment.ident == "Fn"
+                        || segment.ident == "FnMut"
+                        || segment.ident == "FnOnce" =>
+                {
+                    Some(args)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    });
+
+    match fn_args {
+        Some(ParenthesizedGenericArguments { inputs, output, .. }) => Box::new(parse_quote! {
+            std::boxed::Box<dyn Fn(#inputs) #output + Send + Sync>
+        }),
+        None => ty,
+    }
+}
+
+/// Extracts the names specified for the non-receiver arguments with
+/// `#[cs_bindgen(rename = "...")]`, in the same order as [`extract_inputs`].
+///
+/// [`extract_inputs`]: fn.extract_inputs.html
+pub fn extract_arg_renames(inputs: &Punctuated<FnArg, Comma>) -> syn::Result<Vec<Option<String>>> {
+    inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(arg) => Some(RenameAttrs::from_attrs(&arg.attrs)),
+            _ => None,
+        })
+        .map(|attrs| attrs.map(|attrs| attrs.rename))
+        .collect()
+}
+
+/// Extracts the doc comments for the non-receiver arguments, in the same order as
+/// [`extract_inputs`].
+///
+/// [`extract_inputs`]: fn.extract_inputs.html
+pub fn extract_arg_docs(inputs: &Punctuated<FnArg, Comma>) -> Vec<Option<String>> {
+    inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(arg) => Some(docs::extract_docs(&arg.attrs)),
+            _ => None,
         })
         .collect()
 }
@@ -51,8 +124,237 @@ pub fn quote_binding_inputs<T: ToTokens>(ident: &Ident, ty: T) -> TokenStream {
 }
 
 /// Generates the call to `Abi::from_abi` to convert the raw binding argument.
-pub fn quote_input_conversion(ident: &Ident) -> TokenStream {
+///
+/// The type of the converted argument is specified explicitly, since it can't be
+/// inferred from the original function when the argument was declared as `impl Fn`.
+pub fn quote_input_conversion<T: ToTokens>(ident: &Ident, ty: T) -> TokenStream {
+    quote! {
+        let #ident: #ty = cs_bindgen::abi::Abi::from_abi(#ident);
+    }
+}
+
+/// Generates the output portion of the binding function declaration.
+///
+/// The return type of the binding function is the `Abi` type of the original
+/// return type. Any lifetimes in the return type are replaced with `'static`,
+/// since a method may return a reference borrowed from `self` (e.g. `&str`) but
+/// the binding function receives `self` as a raw pointer and so has no input
+/// lifetime for the output to borrow from. This doesn't affect the generated code,
+/// since the raw representation of a type never depends on its lifetimes.
+pub fn quote_return_decl(output: &ReturnType) -> TokenStream {
+    match output {
+        ReturnType::Default => quote! {},
+        ReturnType::Type(_, return_type) => {
+            let mut return_type = (**return_type).clone();
+            StaticLifetimes::default().visit_type_mut(&mut return_type);
+            quote! {
+                -> <#return_type as cs_bindgen::abi::Abi>::Abi
+            }
+        }
+    }
+}
+
+/// Returns `true` if the return type contains any lifetimes, i.e. if the returned
+/// value may borrow from one of the arguments.
+pub fn returns_borrow(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, return_type) => has_lifetimes(return_type),
+    }
+}
+
+/// Returns `true` if the type contains any lifetimes, including elided ones.
+fn has_lifetimes(ty: &Type) -> bool {
+    let mut visitor = StaticLifetimes::default();
+    visitor.visit_type_mut(&mut ty.clone());
+    visitor.replaced
+}
+
+/// Generates the declaration for the completion argument of the binding function
+/// for an `async fn`.
+///
+/// The binding function returns as soon as the future has been spawned, so the
+/// future must be `'static`. Borrowed arguments and outputs are rejected here, since
+/// the error for the `'static` bound on the spawned future is much less clear. A
+/// `&self` receiver is still allowed, since the C# bindings keep the handle alive
+/// until the future completes.
+pub fn quote_completion_input(signature: &Signature) -> syn::Result<TokenStream> {
+    for arg in &signature.inputs {
+        if let FnArg::Typed(arg) = arg {
+            if has_lifetimes(&arg.ty) {
+                return Err(Error::new_spanned(
+                    &arg.ty,
+                    "Arguments to `async` functions exported with `#[cs_bindgen]` can't be borrowed",
+                ));
+            }
+        }
+    }
+
+    if returns_borrow(&signature.output) {
+        return Err(Error::new_spanned(
+            &signature.output,
+            "`async` functions exported with `#[cs_bindgen]` can't return borrowed values",
+        ));
+    }
+
+    let output = match &signature.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => ty.to_token_stream(),
+    };
+    let completion = completion_ident();
+    Ok(quote! {
+        #completion: cs_bindgen::executor::RawCompletion<<#output as cs_bindgen::abi::Abi>::Abi>
+    })
+}
+
+/// Generates the expression that spawns the future returned by `invoke`, passing it
+/// the completion argument declared by [`quote_completion_input`].
+///
+/// [`quote_completion_input`]: fn.quote_completion_input.html
+pub fn quote_spawn(invoke: TokenStream) -> TokenStream {
+    let completion = completion_ident();
     quote! {
-        let #ident = cs_bindgen::abi::Abi::from_abi(#ident);
+        cs_bindgen::executor::spawn_with_completion(#invoke, #completion)
+    }
+}
+
+fn completion_ident() -> Ident {
+    format_ident!("__completion")
+}
+
+/// Rejects callbacks and trait objects that aren't used directly as the type of an
+/// argument.
+///
+/// Callbacks and trait objects can only be passed from C# to Rust, so they can't be
+/// returned or nested within another type. The arguments of a boxed callback are
+/// passed from Rust to C#, so they can't contain callbacks either.
+pub fn reject_nested_callbacks(inputs: &[FnInput], output: &ReturnType) -> syn::Result<()> {
+    for (_, ty) in inputs {
+        // A callback passed directly as an argument is allowed, so only its signature
+        // is checked.
+        let callback = match &**ty {
+            Type::BareFn(_) => Some(&**ty),
+            _ => boxed_trait_object(ty),
+        };
+
+        let mut visitor = FindCallback::default();
+        match callback {
+            Some(callback) => visit_mut::visit_type_mut(&mut visitor, &mut callback.clone()),
+            None => visitor.visit_type_mut(&mut ty.clone()),
+        }
+        visitor.into_result()?;
+    }
+
+    if let ReturnType::Type(_, ty) = output {
+        reject_callbacks(ty)?;
+    }
+
+    Ok(())
+}
+
+/// Rejects any callback or trait object within a type that's passed from Rust to C#.
+pub fn reject_callbacks(ty: &Type) -> syn::Result<()> {
+    let mut visitor = FindCallback::default();
+    visitor.visit_type_mut(&mut ty.clone());
+    visitor.into_result()
+}
+
+/// Returns the trait object in a `Box<dyn Trait>`, which is how boxed callbacks and
+/// exported traits are passed to Rust.
+fn boxed_trait_object(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if segment.ident == "Box" && args.args.len() == 1 => {
+            match args.args.first()? {
+                GenericArgument::Type(inner @ Type::TraitObject(_)) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Finds the first callback or trait object within a type, i.e. a function pointer,
+/// a `dyn Trait` or an `impl Trait`.
+#[derive(Default)]
+struct FindCallback {
+    found: Option<Type>,
+}
+
+impl FindCallback {
+    fn into_result(self) -> syn::Result<()> {
+        match self.found {
+            Some(ty) => Err(Error::new_spanned(
+                ty,
+                "Callbacks and trait objects can only be used directly as the type of an argument, since they can only be passed from C# to Rust",
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl VisitMut for FindCallback {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if self.found.is_some() {
+            return;
+        }
+
+        match ty {
+            Type::BareFn(_) | Type::TraitObject(_) | Type::ImplTrait(_) => {
+                self.found = Some(ty.clone());
+            }
+
+            _ => visit_mut::visit_type_mut(self, ty),
+        }
+    }
+}
+
+/// Replaces all lifetimes in a type, including elided ones, with `'static`.
+#[derive(Default)]
+struct StaticLifetimes {
+    /// Whether any lifetimes were replaced.
+    replaced: bool,
+}
+
+impl VisitMut for StaticLifetimes {
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        reference.lifetime = Some(Lifetime::new("'static", Span::call_site()));
+        self.replaced = true;
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        *lifetime = Lifetime::new("'static", Span::call_site());
+        self.replaced = true;
+    }
+}
+
+/// Replaces any use of `Self` in the signature of a method with the self type of the
+/// `impl` block.
+///
+/// The generated binding functions are declared outside of the `impl` block, so
+/// `Self` can't be used in their signatures. Note that only `Self` as a complete type
+/// is replaced, e.g. `Self` in `Option<Self>`, but not in `Self::Output`.
+pub fn replace_self(signature: &mut Signature, self_ty: &Type) {
+    ReplaceSelf { self_ty }.visit_signature_mut(signature);
+}
+
+struct ReplaceSelf<'a> {
+    self_ty: &'a Type,
+}
+
+impl VisitMut for ReplaceSelf<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        match ty {
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self") => {
+                *ty = self.self_ty.clone();
+            }
+
+            _ => visit_mut::visit_type_mut(self, ty),
+        }
     }
 }
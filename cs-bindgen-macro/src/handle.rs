@@ -1,17 +1,44 @@
 //! Utilities for generating the bindings for types that should be marshaled as a handle.
 
-use crate::{describe_named_type, impl_named, repr_impl, BindingStyle};
+use crate::{
+    attr::TypeRenames, describe_named_type, docs::TypeDocs, export_name, impl_named, repr_impl,
+    BindingStyle, DROP_PREFIX,
+};
 use proc_macro2::TokenStream;
 use quote::*;
 use syn::*;
 
-pub fn quote_type_as_handle(ident: &Ident) -> syn::Result<TokenStream> {
-    let drop_ident = format_drop_ident!(ident);
-    let describe_fn = describe_named_type(ident, BindingStyle::Handle);
-    let repr_fn = repr_impl(ident);
+pub fn quote_type_as_handle(
+    ident: &Ident,
+    renames: &TypeRenames,
+    docs: &TypeDocs,
+) -> syn::Result<TokenStream> {
+    let describe_fn = describe_named_type(ident, BindingStyle::Handle, renames, docs);
+    let handle_impls = quote_handle_impls(ident, repr_impl(ident));
     let named_impl = impl_named(ident);
 
     Ok(quote! {
+        #handle_impls
+
+        // Export a function that describes the exported type.
+        #describe_fn
+
+        // Implement the `Named` trait for the type.
+        #named_impl
+    })
+}
+
+/// Generates the `Abi` and `AbiReceiver` impls for a type that's marshaled as a
+/// handle, along with the exported function for dropping a handle.
+///
+/// `repr_fn` is the impl of `Abi::repr` for the type itself, which allows handle types
+/// that aren't exported as named types (e.g. the handles for returned iterators) to
+/// describe themselves differently.
+pub fn quote_handle_impls(ident: &Ident, repr_fn: TokenStream) -> TokenStream {
+    let drop_ident = format_drop_ident!(ident);
+    let drop_name = export_name(DROP_PREFIX, ident);
+
+    quote! {
         // Implement `Abi` for the type and references to the type.
 
         impl cs_bindgen::abi::Abi for #ident {
@@ -72,14 +99,28 @@ pub fn quote_type_as_handle(ident: &Ident) -> syn::Result<TokenStream> {
             }
         }
 
-        // Export a function that describes the exported type.
-        #describe_fn
+        // Methods borrow the value through the handle pointer directly.
+        impl cs_bindgen::abi::AbiReceiver for #ident {
+            type RefAbi = *const #ident;
+            type RefMutAbi = *const #ident;
 
-        // Implement the `Named` trait for the type.
-        #named_impl
+            unsafe fn with_ref<R>(abi: Self::RefAbi, func: impl FnOnce(&Self) -> R) -> R {
+                func(&*abi)
+            }
 
-        // Export a function that can be used for dropping an instance of the type.
-        #[no_mangle]
-        pub unsafe extern "C" fn #drop_ident(_: <#ident as cs_bindgen::abi::Abi>::Abi) {}
-    })
+            unsafe fn with_mut<R>(abi: Self::RefMutAbi, func: impl FnOnce(&mut Self) -> R) -> R {
+                func(&mut *(abi as *mut _))
+            }
+        }
+
+        // Export a function that can be used for dropping an instance of the type. The
+        // value is dropped within `catch_panic` since its `Drop` impl may panic.
+        #[export_name = #drop_name]
+        #[allow(bad_style)]
+        pub unsafe extern "C" fn #drop_ident(handle: <#ident as cs_bindgen::abi::Abi>::Abi) {
+            cs_bindgen::panic::catch_panic(move || {
+                let _ = <#ident as cs_bindgen::abi::Abi>::from_abi(handle);
+            })
+        }
+    }
 }
@@ -0,0 +1,187 @@
+//! Generates the bindings for functions that return iterators, which are exposed in
+//! C# as an `IEnumerable<T>`.
+//!
+//! The binding function can't return the iterator directly, since its type often
+//! can't be named (e.g. `impl Iterator<Item = T>`). Instead, we generate a handle type
+//! for each function that returns an iterator, which boxes the returned iterator and
+//! is marshaled the same way as any other handle. Along with the drop function for
+//! the handle, we export a `next` function that advances the iterator, which the
+//! generated C# enumerator calls until the iterator is exhausted.
+//!
+//! The handle type isn't exported as a named type. Instead, it describes itself as
+//! `Repr::Iterator`, which tells the CLI to generate the enumerator for it.
+
+use crate::{
+    attr::FnAttrs,
+    export_name,
+    func::{reject_callbacks, returns_borrow},
+    handle, impl_named, NEXT_PREFIX,
+};
+use proc_macro2::TokenStream;
+use quote::*;
+use std::fmt::Display;
+use syn::*;
+
+/// The iterator returned by an exported function.
+pub struct IteratorOutput {
+    /// The identifier of the handle type generated for the iterator.
+    ident: Ident,
+
+    /// The item type of the iterator.
+    item: Type,
+}
+
+impl IteratorOutput {
+    /// Determines if a function returns an iterator, i.e. if its return type is
+    /// `impl Iterator<Item = T>` or if it was exported with `#[cs_bindgen(iterator)]`.
+    ///
+    /// `name` is the mangled name of the function, which is used to generate the name of
+    /// the handle type.
+    pub fn from_output(
+        output: &ReturnType,
+        attrs: &FnAttrs,
+        is_async: bool,
+        name: impl Display,
+    ) -> syn::Result<Option<Self>> {
+        let return_type = match output {
+            ReturnType::Type(_, return_type) => return_type,
+            ReturnType::Default if attrs.iterator => {
+                return Err(Error::new_spanned(
+                    output,
+                    "A function exported with `iterator` must return an iterator",
+                ))
+            }
+            ReturnType::Default => return Ok(None),
+        };
+
+        let item = match impl_iterator_item(return_type) {
+            Some(item) => item.clone(),
+            None if attrs.iterator => parse_quote! { <#return_type as std::iter::Iterator>::Item },
+            None => return Ok(None),
+        };
+        reject_callbacks(&item)?;
+
+        if is_async || attrs.try_pattern || attrs.constructor.is_some() || attrs.accessor.is_some()
+        {
+            return Err(Error::new_spanned(
+                output,
+                "`async`, `try_pattern`, `constructor`, `factory`, `getter` and `setter` are not supported for functions returning iterators",
+            ));
+        }
+
+        // NOTE: The iterator outlives the call that returned it, so it can't borrow from
+        // the arguments or the receiver.
+        if returns_borrow(output) {
+            return Err(Error::new_spanned(
+                output,
+                "Iterators returned from functions exported with `#[cs_bindgen]` can't borrow from the arguments",
+            ));
+        }
+
+        Ok(Some(Self {
+            ident: format_binding_ident!(format!("{}__Iter", name)),
+            item,
+        }))
+    }
+
+    /// Returns the return type for the binding function, which returns the handle for
+    /// the boxed iterator rather than the iterator itself.
+    pub fn handle_output(&self) -> ReturnType {
+        let ident = &self.ident;
+        parse_quote! { -> #ident }
+    }
+
+    /// Wraps the expression that invokes the original function, boxing the returned
+    /// iterator into the handle type.
+    pub fn quote_into_handle(&self, invoke: TokenStream) -> TokenStream {
+        let ident = &self.ident;
+        quote! { #ident(std::boxed::Box::new(#invoke)) }
+    }
+
+    /// Generates the handle type for the iterator, along with the exported function
+    /// that advances the iterator.
+    ///
+    /// The boxed iterator must be `Send`, since the C# enumerator may drop it from the
+    /// finalizer thread.
+    pub fn quote_handle(&self) -> TokenStream {
+        let ident = &self.ident;
+        let item = &self.item;
+        let next_ident = format_ident!("{}{}", NEXT_PREFIX, ident);
+        let next_name = export_name(NEXT_PREFIX, ident);
+
+        let repr_fn = quote! {
+            fn repr() -> cs_bindgen::shared::Repr {
+                cs_bindgen::shared::Repr::Iterator {
+                    type_name: <#ident as cs_bindgen::shared::Named>::type_name(),
+                    item: std::boxed::Box::new(<#item as cs_bindgen::abi::Abi>::repr()),
+                }
+            }
+        };
+        let handle_impls = handle::quote_handle_impls(ident, repr_fn);
+        let named_impl = impl_named(ident);
+
+        quote! {
+            #[doc(hidden)]
+            #[allow(bad_style)]
+            pub struct #ident(std::boxed::Box<dyn std::iter::Iterator<Item = #item> + Send>);
+
+            #handle_impls
+
+            #named_impl
+
+            // Export a function that advances the iterator. The raw item is written to
+            // `item` if the iterator returned one, and the return value indicates whether
+            // it did.
+            #[export_name = #next_name]
+            #[allow(bad_style)]
+            pub unsafe extern "C" fn #next_ident(
+                handle: <#ident as cs_bindgen::abi::Abi>::Abi,
+                item: *mut <#item as cs_bindgen::abi::Abi>::Abi,
+            ) -> <bool as cs_bindgen::abi::Abi>::Abi {
+                cs_bindgen::panic::catch_panic(move || {
+                    let next = <#ident as cs_bindgen::abi::AbiReceiver>::with_mut(handle, |iter| {
+                        iter.0.next()
+                    });
+
+                    let has_next = next.is_some();
+                    if let Some(next) = next {
+                        item.write(cs_bindgen::abi::Abi::into_abi(next));
+                    }
+
+                    cs_bindgen::abi::Abi::into_abi(has_next)
+                })
+            }
+        }
+    }
+}
+
+/// Returns the item type if `ty` is `impl Iterator<Item = T>`.
+///
+/// `DoubleEndedIterator`, `ExactSizeIterator`, and `FusedIterator` are also
+/// recognized, as long as they specify the item type.
+fn impl_iterator_item(ty: &Type) -> Option<&Type> {
+    let bounds = match ty {
+        Type::ImplTrait(impl_trait) => &impl_trait.bounds,
+        _ => return None,
+    };
+
+    bounds.iter().find_map(|bound| {
+        let segment = match bound {
+            TypeParamBound::Trait(bound) => bound.path.segments.last()?,
+            TypeParamBound::Lifetime(_) => return None,
+        };
+
+        match &*segment.ident.to_string() {
+            "Iterator" | "DoubleEndedIterator" | "ExactSizeIterator" | "FusedIterator" => {}
+            _ => return None,
+        }
+
+        match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Binding(binding) if binding.ident == "Item" => Some(&binding.ty),
+                _ => None,
+            }),
+            _ => None,
+        }
+    })
+}
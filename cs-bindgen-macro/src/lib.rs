@@ -1,36 +1,58 @@
-use crate::{enumeration::*, func::*, strukt::*};
+use crate::{attr::*, docs::*, enumeration::*, func::*, iter::*, strukt::*, trait_object::*};
 use proc_macro2::TokenStream;
 use quote::*;
 use std::fmt::Display;
 use syn::*;
 
+/// Prefix for the generated binding functions.
+const BINDING_PREFIX: &str = "__cs_bindgen_generated__";
+
+/// Prefix for the generated describe functions. The CLI finds the exported items by
+/// looking for exported functions with this prefix.
+const DESCRIBE_PREFIX: &str = "__cs_bindgen_describe__";
+
+/// Prefix for the generated drop functions for handle types.
+const DROP_PREFIX: &str = "__cs_bindgen_drop__";
+
+/// Prefix for the generated functions that advance a returned iterator.
+const NEXT_PREFIX: &str = "__cs_bindgen_next__";
+
+// Prefixes for the list conversion functions generated for each exported type.
+const INDEX_FN_PREFIX: &str = "__cs_bindgen_generated_index_";
+const DROP_VEC_FN_PREFIX: &str = "__cs_bindgen_generated_drop_vec_";
+const CONVERT_LIST_FN_PREFIX: &str = "__cs_bindgen_generated_convert_vec__";
+
 macro_rules! format_binding_ident {
     ($ident:expr) => {
-        format_ident!("__cs_bindgen_generated__{}", $ident);
+        format_ident!("{}{}", crate::BINDING_PREFIX, $ident)
     };
 }
 
 macro_rules! format_describe_ident {
     ($ident:expr) => {
-        format_ident!("__cs_bindgen_describe__{}", $ident);
+        format_ident!("{}{}", crate::DESCRIBE_PREFIX, $ident)
     };
 }
 
 macro_rules! format_drop_ident {
     ($ident:expr) => {
-        format_ident!("__cs_bindgen_drop__{}", $ident);
+        format_ident!("{}{}", crate::DROP_PREFIX, $ident)
     };
 }
 
+mod attr;
+mod docs;
 mod enumeration;
 mod func;
 mod handle;
+mod iter;
 mod strukt;
+mod trait_object;
 mod value;
 
 #[proc_macro_attribute]
 pub fn cs_bindgen(
-    _attr: proc_macro::TokenStream,
+    attr: proc_macro::TokenStream,
     tokens: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
     // Create a copy of the input token stream that we can later extend with the
@@ -38,13 +60,48 @@ pub fn cs_bindgen(
     // manually reconstruct the original input later when returning the result.
     let mut result: TokenStream = tokens.clone().into();
 
+    let args = parse_macro_input!(attr as AttributeArgs);
+
     // Generate the bindings for the annotated item, or generate an error if the
     // item/attribute is invalid.
+    //
+    // NOTE: Fields, variants, arguments, and methods may have their own `#[cs_bindgen(...)]`
+    // attributes, which need to be removed from the original item.
     let generated = match parse_macro_input!(tokens as Item) {
-        Item::Fn(item) => quote_fn_item(item),
-        Item::Struct(item) => quote_struct_item(item),
-        Item::Impl(item) => quote_impl_item(item),
-        Item::Enum(item) => quote_enum_item(item),
+        Item::Fn(mut item) => FnAttrs::from_args(args).and_then(|attrs| {
+            let generated = quote_fn_item(item.clone(), attrs);
+            strip_arg_attrs(&mut item.sig);
+            result = item.into_token_stream();
+            generated
+        }),
+
+        Item::Struct(mut item) => RenameAttrs::from_args(args).and_then(|attrs| {
+            let generated = quote_struct_item(item.clone(), attrs);
+            strip_field_attrs(&mut item.fields);
+            result = item.into_token_stream();
+            generated
+        }),
+
+        Item::Enum(mut item) => RenameAttrs::from_args(args).and_then(|attrs| {
+            let generated = quote_enum_item(item.clone(), attrs);
+            strip_variant_attrs(&mut item);
+            result = item.into_token_stream();
+            generated
+        }),
+
+        Item::Impl(mut item) => reject_args(&args).and_then(|_| {
+            let generated = quote_impl_item(item.clone())?;
+            strip_method_attrs(&mut item);
+            result = item.into_token_stream();
+            Ok(generated)
+        }),
+
+        Item::Trait(mut item) => RenameAttrs::from_args(args).and_then(|attrs| {
+            let generated = quote_trait_item(item.clone(), attrs);
+            strip_trait_method_attrs(&mut item);
+            result = item.into_token_stream();
+            generated
+        }),
 
         // Generate an error for any unknown item types.
         item @ _ => Err(Error::new_spanned(
@@ -66,7 +123,9 @@ enum BindingStyle {
     Value,
 }
 
-fn quote_fn_item(item: ItemFn) -> syn::Result<TokenStream> {
+fn quote_fn_item(item: ItemFn, attrs: FnAttrs) -> syn::Result<TokenStream> {
+    let fn_docs = FnDocs::from_attrs(&item.attrs);
+
     // Extract the signature, which contains the bulk of the information we care about.
     let signature = item.sig;
 
@@ -76,29 +135,62 @@ fn quote_fn_item(item: ItemFn) -> syn::Result<TokenStream> {
         "Generic functions not supported with `#[cs_bindgen]`",
     )?;
 
+    if attrs.constructor.is_some() || attrs.accessor.is_some() {
+        return Err(Error::new_spanned(
+            &signature.ident,
+            "`constructor`, `factory`, `getter` and `setter` are only supported for methods",
+        ));
+    }
+
+    // An `async fn` completes its result through a callback instead of returning it.
+    let is_async = signature.asyncness.is_some();
+    if is_async && attrs.try_pattern {
+        return Err(Error::new_spanned(
+            signature.asyncness,
+            "`try_pattern` is not supported for `async` functions",
+        ));
+    }
+    let completion_input = if is_async {
+        Some(quote_completion_input(&signature)?)
+    } else {
+        None
+    };
+
     // Determine the name of the generated function.
     let ident = signature.ident;
     let binding_ident = format_binding_ident!(ident);
+    let binding_name = export_name(BINDING_PREFIX, &ident);
+
+    // A returned iterator is boxed into a handle type generated for the function, so the
+    // binding function returns the handle instead.
+    let iterator = IteratorOutput::from_output(&signature.output, &attrs, is_async, &ident)?;
+    let output = match &iterator {
+        Some(iterator) => iterator.handle_output(),
+        None => signature.output,
+    };
 
     // Process the arguments to the function.
+    let arg_renames = extract_arg_renames(&signature.inputs)?;
+    let arg_docs = extract_arg_docs(&signature.inputs);
     let inputs = extract_inputs(signature.inputs)?;
+    reject_nested_callbacks(&inputs, &output)?;
     let binding_inputs = inputs
         .iter()
-        .map(|(ident, ty)| quote_binding_inputs(ident, ty));
+        .map(|(ident, ty)| quote_binding_inputs(ident, ty))
+        .chain(completion_input);
     let convert_inputs = inputs
         .iter()
-        .map(|(ident, _)| quote_input_conversion(ident));
+        .map(|(ident, ty)| quote_input_conversion(ident, ty));
 
     // Generate the output portion of the binding function declaration.
-    let return_decl = match &signature.output {
-        ReturnType::Default => quote! {},
-        ReturnType::Type(_, return_type) => quote! {
-            -> <#return_type as cs_bindgen::abi::Abi>::Abi
-        },
+    let return_decl = if is_async {
+        TokenStream::new()
+    } else {
+        quote_return_decl(&output)
     };
 
     // Generate the expression for describing the output of the function.
-    let describe_output = match &signature.output {
+    let describe_output = match &output {
         ReturnType::Default => quote! { None },
         ReturnType::Type(_, return_type) => quote! {
             Some(<#return_type as cs_bindgen::abi::Abi>::repr())
@@ -110,40 +202,66 @@ fn quote_fn_item(item: ItemFn) -> syn::Result<TokenStream> {
     let arg_names = inputs.iter().map(|(ident, _)| ident);
 
     let invoke_expr = quote! { #ident(#( #arg_names, )*) };
-    let return_expr = match &signature.output {
+    let invoke_expr = match &iterator {
+        Some(iterator) => iterator.quote_into_handle(invoke_expr),
+        None => invoke_expr,
+    };
+    let return_expr = match &output {
+        _ if is_async => quote_spawn(invoke_expr),
         ReturnType::Default => invoke_expr,
         ReturnType::Type(..) => quote! { cs_bindgen::abi::Abi::into_abi(#invoke_expr) },
     };
 
     // Compose the various pieces together into the final binding function.
     let binding = quote! {
-        #[no_mangle]
+        #[export_name = #binding_name]
         #[allow(bad_style)]
         pub unsafe extern "C" fn #binding_ident(
             #( #binding_inputs, )*
         ) #return_decl {
-            #( #convert_inputs )*
-            #return_expr
+            // NOTE: User code is invoked within `catch_panic` because unwinding across the
+            // FFI boundary is undefined behavior.
+            cs_bindgen::panic::catch_panic(move || {
+                #( #convert_inputs )*
+                #return_expr
+            })
         }
     };
 
     // Generate the name of the describe function.
     let describe_ident = format_describe_ident!(ident);
 
-    // Generate string versions of the two function idents.
+    // Generate the exported symbol name for the describe function.
     let name = ident.to_string();
-    let binding_name = binding_ident.to_string();
+    let describe_name = export_name(DESCRIBE_PREFIX, &ident);
 
-    let describe_args = inputs.iter().map(|(ident, ty)| {
-        let name = ident.to_string();
-        quote! {
-            cs_bindgen::shared::FnArg::new(#name, <#ty as cs_bindgen::abi::Abi>::repr())
-        }
-    });
+    let describe_args =
+        inputs
+            .iter()
+            .zip(&arg_renames)
+            .zip(&arg_docs)
+            .map(|(((ident, ty), rename), docs)| {
+                let name = ident.to_string();
+                let with_rename = rename
+                    .as_ref()
+                    .map(|rename| quote! { .with_rename(#rename) });
+                let with_docs = docs.as_ref().map(|docs| quote! { .with_docs(#docs) });
+                quote! {
+                    cs_bindgen::shared::FnArg::new(#name, <#ty as cs_bindgen::abi::Abi>::repr())
+                        #with_rename
+                        #with_docs
+                }
+            });
+
+    let try_pattern = attrs.try_pattern;
+    let rename = quote_optional_string(&attrs.rename);
+    let docs = quote_optional_string(&fn_docs.docs);
+    let return_docs = quote_optional_string(&fn_docs.returns);
 
     // Generate the describe function.
     let describe = quote! {
-        #[no_mangle]
+        #[export_name = #describe_name]
+        #[allow(bad_style)]
         pub unsafe extern "C" fn #describe_ident() -> Box<cs_bindgen::abi::RawString> {
             use cs_bindgen::shared::{schematic::describe, Func};
 
@@ -154,15 +272,23 @@ fn quote_fn_item(item: ItemFn) -> syn::Result<TokenStream> {
                     #describe_args,
                 )*],
                 output: #describe_output,
+                try_pattern: #try_pattern,
+                is_async: #is_async,
+                rename: #rename,
+                docs: #docs,
+                return_docs: #return_docs,
             };
 
             std::boxed::Box::new(cs_bindgen::shared::serialize_export(export).into())
         }
     };
 
+    let iterator_handle = iterator.as_ref().map(IteratorOutput::quote_handle);
+
     Ok(quote! {
         #binding
         #describe
+        #iterator_handle
     })
 }
 
@@ -173,13 +299,19 @@ fn quote_impl_item(item: ItemImpl) -> syn::Result<TokenStream> {
         "Generic `impl` blocks are not supported with `#[cs_bindgen]`",
     )?;
 
-    // Generate an error for trait impls. Only inherent impls are allowed for now.
-    if let Some((_, trait_, _)) = item.trait_ {
-        return Err(Error::new_spanned(
-            trait_,
-            "Trait impls not supported with `#[cs_bindgen]`",
-        ));
-    }
+    // Trait impls are supported for traits that are themselves exported with
+    // `#[cs_bindgen]`, which is checked when describing the methods. Negative impls
+    // don't have any methods to export.
+    let trait_ = match item.trait_ {
+        Some((Some(bang), _, _)) => {
+            return Err(Error::new_spanned(
+                bang,
+                "Negative trait impls not supported with `#[cs_bindgen]`",
+            ))
+        }
+        Some((None, trait_, _)) => Some(trait_),
+        None => None,
+    };
 
     let self_ty = item.self_ty;
 
@@ -189,7 +321,7 @@ fn quote_impl_item(item: ItemImpl) -> syn::Result<TokenStream> {
         .into_iter()
         .filter_map(|item| {
             match item {
-                ImplItem::Method(item) => Some(quote_method_item(item, &self_ty)),
+                ImplItem::Method(item) => Some(quote_method_item(item, &self_ty, trait_.as_ref())),
 
                 // Ignore all other unsupported associated item types. We don't generate bindings
                 // for them, but it's otherwise not an error to include them in an `impl` block
@@ -200,12 +332,37 @@ fn quote_impl_item(item: ItemImpl) -> syn::Result<TokenStream> {
         .collect::<syn::Result<TokenStream>>()
 }
 
-fn quote_method_item(item: ImplItemMethod, self_ty: &Type) -> syn::Result<TokenStream> {
+fn quote_method_item(
+    item: ImplItemMethod,
+    self_ty: &Type,
+    trait_: Option<&Path>,
+) -> syn::Result<TokenStream> {
+    let attrs = FnAttrs::from_attrs(&item.attrs)?;
+    let fn_docs = FnDocs::from_attrs(&item.attrs);
+
+    // The methods of a trait impl implement the methods of the C# interface generated
+    // for the trait, so their names and options are determined by the trait.
+    if trait_.is_some() {
+        if let Some(attr) = item
+            .attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("cs_bindgen"))
+        {
+            return Err(Error::new_spanned(
+                attr,
+                "Options are not supported for methods in a trait impl, specify them on the trait instead",
+            ));
+        }
+    }
+
     // Generate the binding function
     // =============================
 
     // Extract the signature, which contains the bulk of the information we care about.
-    let signature = item.sig;
+    // Any use of `Self` is replaced with the concrete self type, since the binding
+    // function is declared outside of the `impl` block.
+    let mut signature = item.sig;
+    replace_self(&mut signature, self_ty);
 
     // Generate an error for any generic parameters.
     reject_generics(
@@ -213,118 +370,270 @@ fn quote_method_item(item: ImplItemMethod, self_ty: &Type) -> syn::Result<TokenS
         "Generic functions not supported with `#[cs_bindgen]`",
     )?;
 
-    // Process the receiver for the method, if any:
-    //
-    // * For the binding function, we need to add the additional input to the list of
-    //   inputs.
-    // * For the descriptor function, we need generate the value of the `receiver` field
-    //   on the created `Method` object.
-    //
-    // TODO: Rewrite all this it's very bad and super hard to follow. Probably the thing
-    // to do would be to first parse out the receiver style as an enum, then do a
-    // separate `match` on it for each of the values we want to generate.
-    let (mut binding_args, describe_receiver) = match signature.receiver() {
-        Some(arg) => {
-            let (self_ty, describe) = match arg {
-                // Expand the full self type based on how the receiver was declared:
-                //
-                // * `self` -> `self_ty`
-                // * `&self` -> `&self_ty`
-                // * `&mut self` -> `&mut self_ty`
-                FnArg::Receiver(arg) => {
-                    if arg.reference.is_some() {
-                        if arg.mutability.is_some() {
-                            (
-                                quote! { &mut #self_ty },
-                                quote! { Some(ReceiverStyle::RefMut) },
-                            )
-                        } else {
-                            (quote! { & #self_ty }, quote! { Some(ReceiverStyle::Ref) })
-                        }
-                    } else {
-                        (
-                            self_ty.to_token_stream(),
-                            quote! { Some(ReceiverStyle::Move) },
-                        )
-                    }
-                }
+    // Validate the `constructor` and `factory` options, which only apply to static
+    // methods. A constructor must also return the self type.
+    if let Some(constructor) = attrs.constructor {
+        if let Some(receiver) = signature.receiver() {
+            return Err(Error::new_spanned(
+                receiver,
+                "`constructor` and `factory` are only supported for static methods",
+            ));
+        }
 
-                // If the method was declared using an arbitrary self type (e.g. `self: Foo`), directly
-                // used the declared type.
-                //
-                // TODO: There's likely some extra work needed here in order to fully support arbitrary
-                // self types: While the macro won't generate an error, we're probably not going to
-                // generate the ideal bindings in all cases.
-                //
-                // We probably want to treat arbitrary self type functions more like static functions
-                // in C# than methods. So maybe convert it to a regular function with a normal self type,
-                // i.e. treat it as if there were no receiver?
-                FnArg::Typed(arg) => (arg.ty.to_token_stream(), quote! { None }),
-            };
+        let returns_self = match &signature.output {
+            ReturnType::Type(_, ty) => is_same_type_name(ty, self_ty),
+            ReturnType::Default => false,
+        };
+        if constructor && !returns_self {
+            return Err(Error::new_spanned(
+                &signature.output,
+                "A `constructor` method must return `Self`",
+            ));
+        }
+    }
+
+    // An `async` method completes its result through a callback instead of returning
+    // it, so it can't be used as a constructor or accessor.
+    let is_async = signature.asyncness.is_some();
+    if is_async && (attrs.try_pattern || attrs.constructor.is_some() || attrs.accessor.is_some()) {
+        return Err(Error::new_spanned(
+            signature.asyncness,
+            "`try_pattern`, `constructor`, `factory`, `getter` and `setter` are not supported for `async` methods",
+        ));
+    }
+    let completion_input = if is_async {
+        Some(quote_completion_input(&signature)?)
+    } else {
+        None
+    };
+
+    // Determine how the receiver for the method, if any, is passed to the binding
+    // function.
+    let receiver = signature.receiver().map(|arg| match arg {
+        FnArg::Receiver(arg) => match (&arg.reference, &arg.mutability) {
+            (None, _) => Receiver::Move,
+            (Some(_), None) => Receiver::Ref,
+            (Some(_), Some(_)) => Receiver::RefMut,
+        },
+
+        // If the method was declared using an arbitrary self type (e.g. `self: Foo`), directly
+        // used the declared type.
+        //
+        // TODO: There's likely some extra work needed here in order to fully support arbitrary
+        // self types: While the macro won't generate an error, we're probably not going to
+        // generate the ideal bindings in all cases.
+        //
+        // We probably want to treat arbitrary self type functions more like static functions
+        // in C# than methods. So maybe convert it to a regular function with a normal self type,
+        // i.e. treat it as if there were no receiver?
+        FnArg::Typed(arg) => Receiver::Typed(arg.ty.clone()),
+    });
 
-            (vec![(format_ident!("self_"), self_ty)], describe)
+    // The future returned by an `async` method borrows `self` until it completes, while
+    // C# can still call other methods on the object. The bindings refuse `&mut self` calls
+    // until the future completes, but they can't make the future's own borrow exclusive.
+    if is_async && matches!(receiver, Some(Receiver::RefMut)) {
+        return Err(Error::new_spanned(
+            signature.receiver(),
+            "`async` methods can't take `&mut self`, take `&self` or `self` instead",
+        ));
+    }
+
+    // Validate the `getter` and `setter` options. A getter takes no arguments and
+    // returns the value of the property, and a setter takes the new value as its only
+    // argument. Neither may consume the receiver.
+    if let Some(accessor) = attrs.accessor {
+        if attrs.constructor.is_some() || attrs.try_pattern {
+            return Err(Error::new_spanned(
+                &signature.ident,
+                "`getter` and `setter` can't be combined with other options",
+            ));
         }
 
-        None => (Default::default(), quote! { None }),
+        let num_inputs = signature.inputs.len() - receiver.iter().count();
+        let valid = match accessor {
+            Accessor::Getter => {
+                matches!(receiver, None | Some(Receiver::Ref))
+                    && num_inputs == 0
+                    && !matches!(signature.output, ReturnType::Default)
+            }
+            Accessor::Setter => {
+                matches!(
+                    receiver,
+                    None | Some(Receiver::Ref) | Some(Receiver::RefMut)
+                ) && num_inputs == 1
+                    && matches!(signature.output, ReturnType::Default)
+            }
+        };
+
+        if !valid {
+            let message = match accessor {
+                Accessor::Getter => "A `getter` must take `&self` (or no receiver) and no arguments, and must return a value",
+                Accessor::Setter => "A `setter` must take `&self` or `&mut self` (or no receiver) and exactly one argument, and must not return a value",
+            };
+            return Err(Error::new_spanned(&signature, message));
+        }
+    }
+
+    // Generate the binding argument for the receiver and the value of the `receiver`
+    // field on the created `Method` object:
+    //
+    // * A `self` receiver (or an arbitrary self type) is converted like any other
+    //   argument.
+    // * `&self` and `&mut self` use the raw representation from the `AbiReceiver` impl
+    //   for the self type, since value types need to be copied into a temporary.
+    // * If the method returns a value that borrows from `self`, the receiver is instead
+    //   converted directly into a reference. Only handle types implement `Abi` for
+    //   references, which ensures that the returned value can't borrow from a
+    //   temporary copy. The future returned by an `async` method borrows `self` in the
+    //   same way.
+    let self_ident = format_ident!("self_");
+    let borrows_self = returns_borrow(&signature.output) || is_async;
+    let (receiver_arg, describe_receiver) = match &receiver {
+        Some(Receiver::Move) => (
+            Some(quote_binding_inputs(&self_ident, self_ty)),
+            quote! { Some(ReceiverStyle::Move) },
+        ),
+        Some(Receiver::Ref) if borrows_self => (
+            Some(quote_binding_inputs(&self_ident, quote! { &#self_ty })),
+            quote! { Some(ReceiverStyle::Ref) },
+        ),
+        Some(Receiver::RefMut) if borrows_self => (
+            Some(quote_binding_inputs(&self_ident, quote! { &mut #self_ty })),
+            quote! { Some(ReceiverStyle::RefMut) },
+        ),
+        Some(Receiver::Ref) => (
+            Some(quote! {
+                #self_ident: <#self_ty as cs_bindgen::abi::AbiReceiver>::RefAbi
+            }),
+            quote! { Some(ReceiverStyle::Ref) },
+        ),
+        Some(Receiver::RefMut) => (
+            Some(quote! {
+                #self_ident: <#self_ty as cs_bindgen::abi::AbiReceiver>::RefMutAbi
+            }),
+            quote! { Some(ReceiverStyle::RefMut) },
+        ),
+        Some(Receiver::Typed(ty)) => (Some(quote_binding_inputs(&self_ident, ty)), quote! { None }),
+        None => (None, quote! { None }),
     };
 
     // Determine the name of the generated function.
     let ident = signature.ident;
-    let self_ident = extract_type_ident(self_ty)?;
-    let mangled_name = format!("{}__{}", ident, self_ident);
+    let self_ident_name = extract_type_ident(self_ty)?;
+    // The full path of the trait is included, so that implementing two traits with the
+    // same name doesn't generate the same binding function.
+    let mangled_name = match trait_ {
+        Some(trait_) => {
+            let trait_path = trait_
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("__");
+            format!("{}__{}__{}", ident, self_ident_name, trait_path)
+        }
+        None => format!("{}__{}", ident, self_ident_name),
+    };
     let binding_ident = format_binding_ident!(mangled_name);
+    let binding_name = export_name(BINDING_PREFIX, &mangled_name);
+
+    // A returned iterator is boxed into a handle type generated for the method, so the
+    // binding function returns the handle instead.
+    let iterator = IteratorOutput::from_output(&signature.output, &attrs, is_async, &mangled_name)?;
+    let output = match &iterator {
+        Some(iterator) => iterator.handle_output(),
+        None => signature.output,
+    };
 
     // Process the arguments to the function.
+    let arg_renames = extract_arg_renames(&signature.inputs)?;
+    let arg_docs = extract_arg_docs(&signature.inputs);
     let inputs = extract_inputs(signature.inputs)?;
-    binding_args.extend(
-        inputs
-            .iter()
-            .map(|(ident, ty)| (ident.clone(), ty.into_token_stream())),
-    );
-    let binding_inputs = binding_args
-        .iter()
-        .map(|(ident, ty)| quote_binding_inputs(ident, ty));
-    let convert_inputs = binding_args
+    reject_nested_callbacks(&inputs, &output)?;
+    let binding_inputs = receiver_arg
+        .into_iter()
+        .chain(
+            inputs
+                .iter()
+                .map(|(ident, ty)| quote_binding_inputs(ident, ty)),
+        )
+        .chain(completion_input);
+    let convert_inputs = inputs
         .iter()
-        .map(|(ident, _)| quote_input_conversion(ident));
+        .map(|(ident, ty)| quote_input_conversion(ident, ty));
 
     // Generate the list of argument names. Used both for forwarding arguments into the
     // original function, and for populating the metadata item.
-    let arg_names = binding_args
-        .iter()
-        .map(|(ident, _)| ident.to_token_stream());
+    let arg_names = inputs.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
 
     // Generate the output portion of the binding function declaration.
-    let return_decl = match &signature.output {
-        ReturnType::Default => quote! {},
-        ReturnType::Type(_, return_type) => quote! {
-            -> <#return_type as cs_bindgen::abi::Abi>::Abi
-        },
+    let return_decl = if is_async {
+        TokenStream::new()
+    } else {
+        quote_return_decl(&output)
     };
 
     // Generate the expression for describing the output of the function.
-    let describe_output = match &signature.output {
+    let describe_output = match &output {
         ReturnType::Default => quote! { None },
         ReturnType::Type(_, return_type) => quote! {
             Some(<#return_type as cs_bindgen::abi::Abi>::repr())
         },
     };
 
-    let invoke = quote! { #self_ty::#ident(#( #arg_names, )*) };
-    let return_expr = match &signature.output {
+    // Generate the expression for invoking the original method, borrowing the receiver
+    // through its `AbiReceiver` impl if necessary. The methods of a trait impl are
+    // invoked through the trait, so that they don't resolve to an inherent method with
+    // the same name.
+    let method_path = match trait_ {
+        Some(trait_) => quote! { <#self_ty as #trait_>::#ident },
+        None => quote! { #self_ty::#ident },
+    };
+    let invoke = match &receiver {
+        Some(Receiver::Ref) | Some(Receiver::RefMut) if borrows_self => quote! {{
+            let #self_ident = cs_bindgen::abi::Abi::from_abi(#self_ident);
+            #method_path(#self_ident, #( #arg_names, )*)
+        }},
+        Some(Receiver::Move) | Some(Receiver::Typed(_)) => quote! {{
+            let #self_ident = cs_bindgen::abi::Abi::from_abi(#self_ident);
+            #method_path(#self_ident, #( #arg_names, )*)
+        }},
+        Some(Receiver::Ref) => quote! {
+            <#self_ty as cs_bindgen::abi::AbiReceiver>::with_ref(#self_ident, move |#self_ident| {
+                #method_path(#self_ident, #( #arg_names, )*)
+            })
+        },
+        Some(Receiver::RefMut) => quote! {
+            <#self_ty as cs_bindgen::abi::AbiReceiver>::with_mut(#self_ident, move |#self_ident| {
+                #method_path(#self_ident, #( #arg_names, )*)
+            })
+        },
+        None => quote! { #method_path(#( #arg_names, )*) },
+    };
+    let invoke = match &iterator {
+        Some(iterator) => iterator.quote_into_handle(invoke),
+        None => invoke,
+    };
+    let return_expr = match &output {
+        _ if is_async => quote_spawn(invoke),
         ReturnType::Default => invoke,
         ReturnType::Type(..) => quote! { cs_bindgen::abi::Abi::into_abi(#invoke) },
     };
 
     // Compose the various pieces together into the final binding function.
     let binding = quote! {
-        #[no_mangle]
+        #[export_name = #binding_name]
         #[allow(bad_style)]
         pub unsafe extern "C" fn #binding_ident(
             #( #binding_inputs, )*
         ) #return_decl {
-            #( #convert_inputs )*
-            #return_expr
+            // NOTE: User code is invoked within `catch_panic` because unwinding across the
+            // FFI boundary is undefined behavior.
+            cs_bindgen::panic::catch_panic(move || {
+                #( #convert_inputs )*
+                #return_expr
+            })
         }
     };
 
@@ -334,19 +643,54 @@ fn quote_method_item(item: ImplItemMethod, self_ty: &Type) -> syn::Result<TokenS
     // Generate the name of the describe function.
     let describe_ident = format_describe_ident!(mangled_name);
 
-    // Generate string versions of the two function idents.
+    // Generate the exported symbol name for the describe function.
     let name = ident.to_string();
-    let binding_name = binding_ident.to_string();
+    let describe_name = export_name(DESCRIBE_PREFIX, &mangled_name);
 
-    let describe_args = inputs.iter().map(|(ident, ty)| {
-        let name = ident.to_string();
-        quote! {
-            cs_bindgen::shared::FnArg::new(#name, <#ty as cs_bindgen::abi::Abi>::repr())
-        }
-    });
+    let describe_args =
+        inputs
+            .iter()
+            .zip(&arg_renames)
+            .zip(&arg_docs)
+            .map(|(((ident, ty), rename), docs)| {
+                let name = ident.to_string();
+                let with_rename = rename
+                    .as_ref()
+                    .map(|rename| quote! { .with_rename(#rename) });
+                let with_docs = docs.as_ref().map(|docs| quote! { .with_docs(#docs) });
+                quote! {
+                    cs_bindgen::shared::FnArg::new(#name, <#ty as cs_bindgen::abi::Abi>::repr())
+                        #with_rename
+                        #with_docs
+                }
+            });
+
+    let try_pattern = attrs.try_pattern;
+    let rename = quote_optional_string(&attrs.rename);
+    let docs = quote_optional_string(&fn_docs.docs);
+    let return_docs = quote_optional_string(&fn_docs.returns);
+    let constructor = match attrs.constructor {
+        Some(constructor) => quote! { Some(#constructor) },
+        None => quote! { None },
+    };
+    let accessor = match attrs.accessor {
+        Some(Accessor::Getter) => quote! { Some(cs_bindgen::shared::Accessor::Getter) },
+        Some(Accessor::Setter) => quote! { Some(cs_bindgen::shared::Accessor::Setter) },
+        None => quote! { None },
+    };
+
+    // NOTE: The trait name is looked up through the `Named` impl generated for the trait
+    // object type, which also verifies that the trait is exported.
+    let trait_name = match trait_ {
+        Some(trait_) => quote! {
+            Some(<dyn #trait_ as cs_bindgen::shared::Named>::type_name())
+        },
+        None => quote! { None },
+    };
 
     let describe = quote! {
-        #[no_mangle]
+        #[export_name = #describe_name]
+        #[allow(bad_style)]
         pub unsafe extern "C" fn #describe_ident() -> Box<cs_bindgen::abi::RawString> {
             use cs_bindgen::shared::{schematic::describe, Method, ReceiverStyle};
 
@@ -359,18 +703,44 @@ fn quote_method_item(item: ImplItemMethod, self_ty: &Type) -> syn::Result<TokenS
                     #describe_args,
                 )*],
                 output: #describe_output,
+                try_pattern: #try_pattern,
+                is_async: #is_async,
+                constructor: #constructor,
+                accessor: #accessor,
+                trait_name: #trait_name,
+                rename: #rename,
+                docs: #docs,
+                return_docs: #return_docs,
             };
 
             std::boxed::Box::new(cs_bindgen::shared::serialize_export(export).into())
         }
     };
 
+    let iterator_handle = iterator.as_ref().map(IteratorOutput::quote_handle);
+
     Ok(quote! {
         #binding
         #describe
+        #iterator_handle
     })
 }
 
+/// The style of receiver for an exported method.
+enum Receiver {
+    /// `self`
+    Move,
+
+    /// `&self`
+    Ref,
+
+    /// `&mut self`
+    RefMut,
+
+    /// An arbitrary self type, e.g. `self: Box<Self>`.
+    Typed(Box<Type>),
+}
+
 /// Returns `true` if any of the specified attributes are a `derive()` containing `Copy`.
 fn has_derive_copy(attributes: &[Attribute]) -> syn::Result<bool> {
     // Get the `#[derive(..)]` attribute, or return `false` if none is present.
@@ -425,11 +795,43 @@ fn reject_generics<M: Display>(generics: &Generics, message: M) -> syn::Result<(
     }
 }
 
-fn describe_named_type(ident: &Ident, style: BindingStyle) -> TokenStream {
+fn describe_named_type(
+    ident: &Ident,
+    style: BindingStyle,
+    renames: &TypeRenames,
+    docs: &TypeDocs,
+) -> TokenStream {
     let describe_ident = format_describe_ident!(ident);
-    let index_fn = index_fn_ident(ident).to_string();
-    let convert_list_fn = convert_list_fn_ident(ident).to_string();
-    let drop_vec_fn = drop_vec_fn_ident(ident).to_string();
+    let describe_name = export_name(DESCRIBE_PREFIX, ident);
+    let index_fn = export_name(INDEX_FN_PREFIX, ident);
+    let convert_list_fn = export_name(CONVERT_LIST_FN_PREFIX, ident);
+    let drop_vec_fn = export_name(DROP_VEC_FN_PREFIX, ident);
+
+    let rename = quote_optional_string(&renames.rename);
+    let field_renames = renames.fields.iter().map(quote_optional_string);
+    let variant_renames = renames.variants.iter().map(|variant| {
+        let rename = quote_optional_string(&variant.rename);
+        let field_renames = variant.fields.iter().map(quote_optional_string);
+        quote! {
+            cs_bindgen::shared::VariantRenames {
+                rename: #rename,
+                field_renames: vec![#( #field_renames ),*],
+            }
+        }
+    });
+
+    let field_docs = docs.fields.iter().map(quote_optional_string);
+    let variant_docs = docs.variants.iter().map(|variant| {
+        let docs = quote_optional_string(&variant.docs);
+        let field_docs = variant.fields.iter().map(quote_optional_string);
+        quote! {
+            cs_bindgen::shared::VariantDocs {
+                docs: #docs,
+                field_docs: vec![#( #field_docs ),*],
+            }
+        }
+    });
+    let docs = quote_optional_string(&docs.docs);
 
     let style = match style {
         BindingStyle::Handle => quote! {
@@ -442,18 +844,31 @@ fn describe_named_type(ident: &Ident, style: BindingStyle) -> TokenStream {
     };
 
     quote! {
-        #[no_mangle]
+        #[export_name = #describe_name]
+        #[allow(bad_style)]
         pub unsafe extern "C" fn #describe_ident() -> std::boxed::Box<cs_bindgen::abi::RawString> {
             // NOTE: We need to import `schematic` so that usage of the `type_name!` macro
             // resolves correctly, since the expanded code references `schematic` directly.
             use cs_bindgen::shared::schematic;
 
+            // NOTE: The `Fallback` trait needs to be in scope for the `Probe` methods to
+            // resolve when the type doesn't implement `Send` or `Sync`.
+            use cs_bindgen::thread_safety::{Fallback as _, Probe};
+
             let export = cs_bindgen::shared::NamedType {
                 type_name: <#ident as cs_bindgen::shared::Named>::type_name(),
                 binding_style: cs_bindgen::shared::BindingStyle::#style,
                 index_fn: #index_fn.into(),
                 convert_list_fn: #convert_list_fn.into(),
                 drop_vec_fn: #drop_vec_fn.into(),
+                rename: #rename,
+                field_renames: vec![#( #field_renames ),*],
+                variant_renames: vec![#( #variant_renames ),*],
+                docs: #docs,
+                field_docs: vec![#( #field_docs ),*],
+                variant_docs: vec![#( #variant_docs ),*],
+                is_send: Probe::<#ident>::new().is_send(),
+                is_sync: Probe::<#ident>::new().is_sync(),
             };
 
             std::boxed::Box::new(cs_bindgen::shared::serialize_export(export).into())
@@ -461,6 +876,29 @@ fn describe_named_type(ident: &Ident, style: BindingStyle) -> TokenStream {
     }
 }
 
+/// Generates the exported symbol name for a generated function.
+///
+/// The symbol name is `{prefix}{module_path}::{name}`, using the path of the module
+/// that the function is generated in. Including the module path means that items
+/// with the same name in different modules don't produce conflicting symbols. The
+/// generated expression evaluates to a `&'static str`, so it can be used both in an
+/// `#[export_name]` attribute and in the description of the exported item.
+fn export_name(prefix: &str, name: impl Display) -> TokenStream {
+    let name = name.to_string();
+    quote! {
+        concat!(#prefix, module_path!(), "::", #name)
+    }
+}
+
+/// Generates the expression for an optional string in the generated metadata, e.g.
+/// the name specified with `rename` or the doc comment for an item.
+fn quote_optional_string(value: &Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value.into()) },
+        None => quote! { None },
+    }
+}
+
 /// Generates an impl of `Named` for the specified type.
 fn impl_named(ident: &Ident) -> TokenStream {
     quote! {
@@ -503,51 +941,76 @@ fn extract_type_ident(ty: &Type) -> syn::Result<Ident> {
     Ok(format_ident!("{}", ident_string))
 }
 
+/// Returns `true` if `ty` and `other` name the same type.
+///
+/// Only the last segment of each path is compared, so that e.g. `crate::Foo` is
+/// recognized as the same type as `Foo`.
+fn is_same_type_name(ty: &Type, other: &Type) -> bool {
+    fn last_ident(ty: &Type) -> Option<&Ident> {
+        match ty {
+            Type::Path(path) if path.qself.is_none() => {
+                path.path.segments.last().map(|segment| &segment.ident)
+            }
+            _ => None,
+        }
+    }
+
+    match (last_ident(ty), last_ident(other)) {
+        (Some(ident), Some(other)) => ident == other,
+        _ => false,
+    }
+}
+
 fn index_fn_ident(ty: &Ident) -> Ident {
-    format_ident!("__cs_bindgen_generated_index_{}", ty)
+    format_ident!("{}{}", INDEX_FN_PREFIX, ty)
 }
 
 /// Generates a function for converting an element in a slice.
 fn quote_index_fn(ty: &Ident) -> TokenStream {
     let fn_ident = index_fn_ident(ty);
+    let export_name = export_name(INDEX_FN_PREFIX, ty);
     quote! {
-        #[no_mangle]
+        #[export_name = #export_name]
         #[allow(bad_style)]
         pub unsafe extern "C" fn #fn_ident(
             slice: cs_bindgen::abi::RawSlice<#ty>,
             index: usize,
         ) -> <#ty as cs_bindgen::abi::Abi>::Abi {
-            slice.convert_element(index)
+            cs_bindgen::panic::catch_panic(move || slice.convert_element(index))
         }
     }
 }
 
 fn drop_vec_fn_ident(ty: &Ident) -> Ident {
-    format_ident!("__cs_bindgen_generated_drop_vec_{}", ty)
+    format_ident!("{}{}", DROP_VEC_FN_PREFIX, ty)
 }
 
 fn quote_vec_drop_fn(ty: &Ident) -> TokenStream {
     let fn_ident = drop_vec_fn_ident(ty);
+    let export_name = export_name(DROP_VEC_FN_PREFIX, ty);
     quote! {
-        #[no_mangle]
+        #[export_name = #export_name]
         #[allow(bad_style)]
         pub unsafe extern "C" fn #fn_ident(raw: cs_bindgen::abi::RawVec<#ty>) {
-            let _ = raw.into_vec();
+            cs_bindgen::panic::catch_panic(move || {
+                let _ = raw.into_vec();
+            })
         }
     }
 }
 
 fn convert_list_fn_ident(ty: &Ident) -> Ident {
-    format_ident!("__cs_bindgen_generated_convert_vec__{}", ty)
+    format_ident!("{}{}", CONVERT_LIST_FN_PREFIX, ty)
 }
 
 fn quote_convert_list_fn(ty: &Ident) -> TokenStream {
     let fn_ident = convert_list_fn_ident(ty);
+    let export_name = export_name(CONVERT_LIST_FN_PREFIX, ty);
     quote! {
-        #[no_mangle]
+        #[export_name = #export_name]
         #[allow(bad_style)]
         pub unsafe extern "C" fn #fn_ident(raw: cs_bindgen::abi::RawSlice<<#ty as cs_bindgen::abi::Abi>::Abi>) -> cs_bindgen::abi::RawVec<#ty> {
-            cs_bindgen::abi::convert_list(raw)
+            cs_bindgen::panic::catch_panic(move || cs_bindgen::abi::convert_list(raw))
         }
     }
 }
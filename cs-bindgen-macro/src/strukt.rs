@@ -1,22 +1,35 @@
 use crate::{
-    describe_named_type, handle, has_derive_copy, impl_named, quote_convert_list_fn,
-    quote_index_fn, quote_vec_drop_fn, reject_generics, repr_impl, value, BindingStyle,
+    attr::{RenameAttrs, TypeRenames},
+    describe_named_type,
+    docs::TypeDocs,
+    func::reject_callbacks,
+    handle, has_derive_copy, impl_named, quote_convert_list_fn, quote_index_fn, quote_vec_drop_fn,
+    reject_generics, repr_impl, value, BindingStyle,
 };
 use proc_macro2::{Literal, TokenStream};
 use quote::*;
 use syn::*;
 
 /// Generates the bindings for an exported struct.
-pub fn quote_struct_item(item: ItemStruct) -> syn::Result<TokenStream> {
+pub fn quote_struct_item(item: ItemStruct, attrs: RenameAttrs) -> syn::Result<TokenStream> {
     reject_generics(
         &item.generics,
         "Generic structs are not supported with `#[cs_bindgen]`",
     )?;
 
+    let renames = TypeRenames::from_struct(attrs, &item)?;
+    let docs = TypeDocs::from_struct(&item);
+
     let repr_fn = repr_impl(&item.ident);
 
     // Determine whether we should marshal the type as a handle or by value.
     if has_derive_copy(&item.attrs)? {
+        // Callbacks can only be passed from C# to Rust, so they can't be stored in a type
+        // that's marshaled by value.
+        for field in &item.fields {
+            reject_callbacks(&field.ty)?;
+        }
+
         let named_impl = impl_named(&item.ident);
         let describe_impl = describe_struct(&item);
 
@@ -30,10 +43,11 @@ pub fn quote_struct_item(item: ItemStruct) -> syn::Result<TokenStream> {
 
         let abi_struct_ident = format_binding_ident!(item.ident);
         let abi_struct = value::quote_abi_struct(&abi_struct_ident, &item.fields);
-        let describe_fn = describe_named_type(&item.ident, BindingStyle::Value);
+        let describe_fn = describe_named_type(&item.ident, BindingStyle::Value, &renames, &docs);
         let index_fn = quote_index_fn(&item.ident);
         let convert_list_fn = quote_convert_list_fn(&item.ident);
         let vec_drop_fn = quote_vec_drop_fn(&item.ident);
+        let receiver_impl = value::quote_receiver_impl(&item.ident);
 
         let into_abi_fields = value::into_abi_fields(&item.fields, |index, field| {
             let accessor = field_accessor(index, field);
@@ -80,6 +94,7 @@ pub fn quote_struct_item(item: ItemStruct) -> syn::Result<TokenStream> {
                 }
             }
 
+            #receiver_impl
             #named_impl
             #describe_impl
             #describe_fn
@@ -88,7 +103,7 @@ pub fn quote_struct_item(item: ItemStruct) -> syn::Result<TokenStream> {
             #vec_drop_fn
         })
     } else {
-        handle::quote_type_as_handle(&item.ident)
+        handle::quote_type_as_handle(&item.ident, &renames, &docs)
     }
 }
 
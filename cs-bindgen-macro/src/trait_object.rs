@@ -0,0 +1,305 @@
+//! Generates the bindings for exported traits, which can be implemented by C# objects.
+//!
+//! A C# object implementing the trait is passed to Rust as a `RawTraitObject`, which
+//! contains the vtable struct generated for the trait. The vtable has a pointer to a
+//! trampoline for each method of the trait, which invokes the method on the C#
+//! object. We generate a type that implements the trait by calling through the
+//! vtable, and implement `Abi` for `Box<dyn Trait>` by boxing that type.
+//!
+//! All methods are called through the vtable, including ones that have a default
+//! implementation in Rust, so the C# object has to implement every method.
+
+use crate::{
+    attr::RenameAttrs,
+    docs::{extract_docs, FnDocs},
+    export_name,
+    func::*,
+    quote_optional_string, reject_generics, DESCRIBE_PREFIX,
+};
+use proc_macro2::TokenStream;
+use quote::*;
+use syn::*;
+
+pub fn quote_trait_item(item: ItemTrait, attrs: RenameAttrs) -> syn::Result<TokenStream> {
+    reject_generics(
+        &item.generics,
+        "Generic traits are not supported with `#[cs_bindgen]`",
+    )?;
+
+    if let Some(unsafety) = &item.unsafety {
+        return Err(Error::new_spanned(
+            unsafety,
+            "Unsafe traits are not supported with `#[cs_bindgen]`",
+        ));
+    }
+
+    // The generated type can only implement the trait itself, so any supertraits other
+    // than the auto traits are rejected.
+    for bound in &item.supertraits {
+        let is_supported = match bound {
+            TypeParamBound::Trait(bound) => {
+                bound.path.is_ident("Send") || bound.path.is_ident("Sync")
+            }
+            TypeParamBound::Lifetime(lifetime) => lifetime.ident == "static",
+        };
+
+        if !is_supported {
+            return Err(Error::new_spanned(
+                bound,
+                "Supertraits other than `Send`, `Sync`, and `'static` are not supported with `#[cs_bindgen]`",
+            ));
+        }
+    }
+
+    let methods = item
+        .items
+        .iter()
+        .map(|item| match item {
+            TraitItem::Method(method) => Ok(method),
+            _ => Err(Error::new_spanned(
+                item,
+                "Only methods are supported in traits exported with `#[cs_bindgen]`",
+            )),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    if methods.is_empty() {
+        return Err(Error::new_spanned(
+            &item.ident,
+            "Traits exported with `#[cs_bindgen]` must have at least one method",
+        ));
+    }
+
+    let ident = &item.ident;
+    let vtable_ident = format_binding_ident!(format!("{}__VTable", ident));
+    let impl_ident = format_binding_ident!(format!("{}__Impl", ident));
+
+    let mut vtable_fields = Vec::new();
+    let mut impl_methods = Vec::new();
+    let mut describe_methods = Vec::new();
+    for method in methods {
+        let attrs = RenameAttrs::from_attrs(&method.attrs)?;
+        let fn_docs = FnDocs::from_attrs(&method.attrs);
+        let signature = &method.sig;
+
+        reject_generics(
+            &signature.generics,
+            "Generic trait methods are not supported with `#[cs_bindgen]`",
+        )?;
+
+        if signature.unsafety.is_some() || signature.asyncness.is_some() {
+            return Err(Error::new_spanned(
+                signature,
+                "`unsafe` and `async` trait methods are not supported with `#[cs_bindgen]`",
+            ));
+        }
+
+        match signature.receiver() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {}
+            _ => {
+                return Err(Error::new_spanned(
+                    signature,
+                    "Trait methods must take `&self` or `&mut self` to be exported with `#[cs_bindgen]`",
+                ))
+            }
+        }
+
+        // The returned value is converted from a value owned by C#, so there's nothing
+        // for it to borrow from.
+        if returns_borrow(&signature.output) {
+            return Err(Error::new_spanned(
+                &signature.output,
+                "Trait methods exported with `#[cs_bindgen]` can't return borrowed values",
+            ));
+        }
+
+        let method_ident = &signature.ident;
+        let arg_renames = extract_arg_renames(&signature.inputs)?;
+        let arg_docs = extract_arg_docs(&signature.inputs);
+        let inputs = extract_inputs(signature.inputs.clone())?;
+
+        // The arguments are passed from Rust to C#, so they can't contain callbacks. The
+        // return type is rejected as well, which matches the bindings generated for
+        // the interface.
+        for (_, ty) in &inputs {
+            reject_callbacks(ty)?;
+        }
+        if let ReturnType::Type(_, ty) = &signature.output {
+            reject_callbacks(ty)?;
+        }
+        let arg_ident = inputs.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+        let arg_ty = inputs.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+        let output_ty = match &signature.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => ty.to_token_stream(),
+        };
+
+        // Generate the implementation of the method, which passes the arguments to the
+        // C# object through the trampoline in the vtable. Arguments declared with a
+        // pattern are replaced with the idents generated for them.
+        let mut impl_signature = signature.clone();
+        let typed_args = impl_signature
+            .inputs
+            .iter_mut()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(arg) => Some(arg),
+                FnArg::Receiver(_) => None,
+            });
+        for (arg, ident) in typed_args.zip(&arg_ident) {
+            arg.attrs.clear();
+            *arg.pat = parse_quote! { #ident };
+        }
+
+        let panic_message = format!(
+            "C# implementation of `{}::{}` threw an exception",
+            ident, method_ident,
+        );
+        impl_methods.push(quote! {
+            #impl_signature {
+                unsafe {
+                    let invoke: unsafe extern "C" fn(
+                        *const std::ffi::c_void,
+                        *mut u8,
+                        #( <#arg_ty as cs_bindgen::abi::Abi>::Abi, )*
+                    ) -> <#output_ty as cs_bindgen::abi::Abi>::Abi =
+                        std::mem::transmute(self.0.vtable().#method_ident);
+
+                    cs_bindgen::abi::call_trampoline::<#output_ty>(#panic_message, |threw| {
+                        invoke(
+                            self.0.context(),
+                            threw,
+                            #( cs_bindgen::abi::Abi::into_abi(#arg_ident), )*
+                        )
+                    })
+                }
+            }
+        });
+
+        vtable_fields.push(quote! {
+            pub #method_ident: *const std::ffi::c_void
+        });
+
+        let describe_args = arg_ident
+            .iter()
+            .zip(&arg_ty)
+            .zip(&arg_renames)
+            .zip(&arg_docs)
+            .map(|(((ident, ty), rename), docs)| {
+                let name = ident.to_string();
+                let with_rename = rename
+                    .as_ref()
+                    .map(|rename| quote! { .with_rename(#rename) });
+                let with_docs = docs.as_ref().map(|docs| quote! { .with_docs(#docs) });
+                quote! {
+                    cs_bindgen::shared::FnArg::new(#name, <#ty as cs_bindgen::abi::Abi>::repr())
+                        #with_rename
+                        #with_docs
+                }
+            });
+
+        let describe_output = match &signature.output {
+            ReturnType::Default => quote! { None },
+            ReturnType::Type(_, return_type) => quote! {
+                Some(<#return_type as cs_bindgen::abi::Abi>::repr())
+            },
+        };
+
+        let name = method_ident.to_string();
+        let rename = quote_optional_string(&attrs.rename);
+        let docs = quote_optional_string(&fn_docs.docs);
+        let return_docs = quote_optional_string(&fn_docs.returns);
+        describe_methods.push(quote! {
+            cs_bindgen::shared::TraitMethod {
+                name: #name.into(),
+                inputs: vec![#( #describe_args, )*],
+                output: #describe_output,
+                rename: #rename,
+                docs: #docs,
+                return_docs: #return_docs,
+            }
+        });
+    }
+
+    // Implement `Abi` for boxed trait objects, with and without the auto traits. The
+    // generated type is always `Send` and `Sync`, since the C# object can be used from
+    // any thread.
+    let abi_impls = [
+        quote! { dyn #ident },
+        quote! { dyn #ident + Send },
+        quote! { dyn #ident + Send + Sync },
+    ]
+    .iter()
+    .map(|dyn_ty| {
+        quote! {
+            impl cs_bindgen::abi::Abi for std::boxed::Box<#dyn_ty> {
+                type Abi = cs_bindgen::abi::RawTraitObject<#vtable_ident>;
+
+                fn repr() -> cs_bindgen::shared::Repr {
+                    cs_bindgen::shared::Repr::TraitObject(
+                        <dyn #ident as cs_bindgen::shared::Named>::type_name(),
+                    )
+                }
+
+                fn as_abi(&self) -> Self::Abi {
+                    unreachable!("Trait objects passed from Rust to C# should have already been rejected");
+                }
+
+                fn into_abi(self) -> Self::Abi {
+                    unreachable!("Trait objects passed from Rust to C# should have already been rejected");
+                }
+
+                unsafe fn from_abi(abi: Self::Abi) -> Self {
+                    std::boxed::Box::new(#impl_ident(cs_bindgen::abi::TraitObject::from_raw(abi)))
+                }
+            }
+        }
+    })
+    .collect::<Vec<_>>();
+
+    let describe_ident = format_describe_ident!(ident);
+    let describe_name = export_name(DESCRIBE_PREFIX, ident);
+    let rename = quote_optional_string(&attrs.rename);
+    let docs = quote_optional_string(&extract_docs(&item.attrs));
+
+    Ok(quote! {
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy)]
+        #[allow(bad_style)]
+        #[doc(hidden)]
+        pub struct #vtable_ident {
+            #( #vtable_fields, )*
+        }
+
+        unsafe impl cs_bindgen::abi::AbiPrimitive for #vtable_ident {}
+
+        #[allow(bad_style)]
+        struct #impl_ident(cs_bindgen::abi::TraitObject<#vtable_ident>);
+
+        impl #ident for #impl_ident {
+            #( #impl_methods )*
+        }
+
+        #( #abi_impls )*
+
+        // NOTE: `Named` is implemented for the trait object type so that trait impls can
+        // look up the name of the exported trait.
+        impl cs_bindgen::shared::Named for dyn #ident {
+            fn type_name() -> cs_bindgen::shared::TypeName {
+                cs_bindgen::shared::TypeName::new(stringify!(#ident), module_path!())
+            }
+        }
+
+        #[export_name = #describe_name]
+        #[allow(bad_style)]
+        pub unsafe extern "C" fn #describe_ident() -> std::boxed::Box<cs_bindgen::abi::RawString> {
+            let export = cs_bindgen::shared::Trait {
+                type_name: <dyn #ident as cs_bindgen::shared::Named>::type_name(),
+                methods: vec![#( #describe_methods, )*],
+                rename: #rename,
+                docs: #docs,
+            };
+
+            std::boxed::Box::new(cs_bindgen::shared::serialize_export(export).into())
+        }
+    })
+}
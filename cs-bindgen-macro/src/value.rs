@@ -131,3 +131,29 @@ pub fn raw_field_ident(index: usize, field: &Field) -> Ident {
         .map(Clone::clone)
         .unwrap_or_else(|| format_ident!("element_{}", index))
 }
+
+/// Generates the `AbiReceiver` impl for a type that is marshaled by value.
+///
+/// Methods operate on a copy of the value that is converted from its raw
+/// representation. For `&mut self` methods the modified copy is converted back and
+/// written through the raw pointer, so that the C# side can update its value.
+pub fn quote_receiver_impl(ident: &Ident) -> TokenStream {
+    quote! {
+        impl cs_bindgen::abi::AbiReceiver for #ident {
+            type RefAbi = <Self as cs_bindgen::abi::Abi>::Abi;
+            type RefMutAbi = *mut <Self as cs_bindgen::abi::Abi>::Abi;
+
+            unsafe fn with_ref<R>(abi: Self::RefAbi, func: impl FnOnce(&Self) -> R) -> R {
+                let value = <Self as cs_bindgen::abi::Abi>::from_abi(abi);
+                func(&value)
+            }
+
+            unsafe fn with_mut<R>(abi: Self::RefMutAbi, func: impl FnOnce(&mut Self) -> R) -> R {
+                let mut value = <Self as cs_bindgen::abi::Abi>::from_abi(*abi);
+                let result = func(&mut value);
+                *abi = cs_bindgen::abi::Abi::into_abi(value);
+                result
+            }
+        }
+    }
+}
@@ -17,6 +17,7 @@ pub enum Export {
     Fn(Func),
     Method(Method),
     Named(NamedType),
+    Trait(Trait),
 }
 
 /// A free function exported from the Rust lib.
@@ -47,6 +48,33 @@ pub struct Func {
     /// Note that this is the return type of the original function, NOT the generated
     /// binding function.
     pub output: Option<Repr>,
+
+    /// Whether the function should use the "try" pattern in the generated bindings.
+    ///
+    /// Only valid for functions returning `Result<T, E>`. The generated C# function
+    /// returns a `bool` indicating success, and returns the value and error via `out`
+    /// parameters instead of throwing an exception.
+    pub try_pattern: bool,
+
+    /// Whether the function is an `async fn`.
+    ///
+    /// The binding function for an `async fn` takes a completion callback as its last
+    /// argument instead of returning the output, and the generated C# function returns
+    /// a `Task` that's completed by the callback. `output` is the output of the future.
+    pub is_async: bool,
+
+    /// The name of the function in the generated bindings, as specified with
+    /// `#[cs_bindgen(rename = "...")]`.
+    ///
+    /// If specified, the name is used as-is instead of converting `name` to the naming
+    /// conventions of the target language.
+    pub rename: Option<Cow<'static, str>>,
+
+    /// The doc comment for the function, not including the `# Returns` section.
+    pub docs: Option<Cow<'static, str>>,
+
+    /// The contents of the `# Returns` section of the function's doc comment.
+    pub return_docs: Option<Cow<'static, str>>,
 }
 
 /// A user-defined type (i.e. a struct or an enum).
@@ -65,6 +93,36 @@ pub struct NamedType {
     pub index_fn: Cow<'static, str>,
     pub drop_vec_fn: Cow<'static, str>,
     pub convert_list_fn: Cow<'static, str>,
+
+    /// The name of the type in the generated bindings, as specified with
+    /// `#[cs_bindgen(rename = "...")]`.
+    pub rename: Option<Cow<'static, str>>,
+
+    /// The names specified for the fields of a struct, in declaration order. `None` for
+    /// any field that isn't renamed.
+    pub field_renames: Vec<Option<Cow<'static, str>>>,
+
+    /// The names specified for the variants of an enum, and the fields of those
+    /// variants, in declaration order.
+    pub variant_renames: Vec<VariantRenames>,
+
+    /// The doc comment for the type.
+    pub docs: Option<Cow<'static, str>>,
+
+    /// The doc comments for the fields of a struct, in declaration order.
+    pub field_docs: Vec<Option<Cow<'static, str>>>,
+
+    /// The doc comments for the variants of an enum, and the fields of those
+    /// variants, in declaration order.
+    pub variant_docs: Vec<VariantDocs>,
+
+    /// Whether the type implements `Send`, i.e. whether values of the type can be
+    /// used from a thread other than the one that created them.
+    pub is_send: bool,
+
+    /// Whether the type implements `Sync`, i.e. whether values of the type can be
+    /// used from multiple threads at once.
+    pub is_sync: bool,
 }
 
 impl NamedType {
@@ -74,6 +132,102 @@ impl NamedType {
             BindingStyle::Handle => None,
         }
     }
+
+    /// Returns the name specified for the field at `index` of a struct, if any.
+    pub fn field_rename(&self, index: usize) -> Option<&str> {
+        self.field_renames.get(index)?.as_deref()
+    }
+
+    /// Returns the renames for the variant at `index` of an enum, if any.
+    pub fn variant_renames(&self, index: usize) -> Option<&VariantRenames> {
+        self.variant_renames.get(index)
+    }
+
+    /// Returns the doc comment for the field at `index` of a struct, if any.
+    pub fn field_doc(&self, index: usize) -> Option<&str> {
+        self.field_docs.get(index)?.as_deref()
+    }
+
+    /// Returns the doc comments for the variant at `index` of an enum, if any.
+    pub fn variant_docs(&self, index: usize) -> Option<&VariantDocs> {
+        self.variant_docs.get(index)
+    }
+}
+
+/// The names specified for an enum variant and its fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VariantRenames {
+    pub rename: Option<Cow<'static, str>>,
+    pub field_renames: Vec<Option<Cow<'static, str>>>,
+}
+
+impl VariantRenames {
+    /// Returns the name specified for the field at `index` of the variant, if any.
+    pub fn field_rename(&self, index: usize) -> Option<&str> {
+        self.field_renames.get(index)?.as_deref()
+    }
+}
+
+/// The doc comments for an enum variant and its fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VariantDocs {
+    pub docs: Option<Cow<'static, str>>,
+    pub field_docs: Vec<Option<Cow<'static, str>>>,
+}
+
+impl VariantDocs {
+    /// Returns the doc comment for the field at `index` of the variant, if any.
+    pub fn field_doc(&self, index: usize) -> Option<&str> {
+        self.field_docs.get(index)?.as_deref()
+    }
+}
+
+/// A trait that can be implemented by C# objects.
+///
+/// A C# object implementing the trait can be passed to Rust as a trait object, e.g.
+/// `Box<dyn Trait>`, in which case Rust calls back into C# to invoke its methods.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trait {
+    pub type_name: TypeName,
+
+    /// The methods of the trait, in declaration order.
+    ///
+    /// The vtable passed from C# contains a function pointer for each method, in the
+    /// same order.
+    pub methods: Vec<TraitMethod>,
+
+    /// The name of the trait in the generated bindings, as specified with
+    /// `#[cs_bindgen(rename = "...")]`.
+    pub rename: Option<Cow<'static, str>>,
+
+    /// The doc comment for the trait.
+    pub docs: Option<Cow<'static, str>>,
+}
+
+/// A method of an exported trait.
+///
+/// Trait methods always take `&self` or `&mut self`, since they're called on a trait
+/// object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraitMethod {
+    pub name: Cow<'static, str>,
+    pub inputs: Vec<FnArg>,
+    pub output: Option<Repr>,
+
+    /// See [`Func::rename`].
+    ///
+    /// [`Func::rename`]: struct.Func.html#structfield.rename
+    pub rename: Option<Cow<'static, str>>,
+
+    /// See [`Func::docs`].
+    ///
+    /// [`Func::docs`]: struct.Func.html#structfield.docs
+    pub docs: Option<Cow<'static, str>>,
+
+    /// See [`Func::return_docs`].
+    ///
+    /// [`Func::return_docs`]: struct.Func.html#structfield.return_docs
+    pub return_docs: Option<Cow<'static, str>>,
 }
 
 #[derive(Debug, Clone, From, Serialize, Deserialize)]
@@ -84,12 +238,75 @@ pub struct Method {
     pub receiver: Option<ReceiverStyle>,
     pub inputs: Vec<FnArg>,
     pub output: Option<Repr>,
+
+    /// See [`Func::try_pattern`].
+    ///
+    /// [`Func::try_pattern`]: struct.Func.html#structfield.try_pattern
+    pub try_pattern: bool,
+
+    /// See [`Func::is_async`].
+    ///
+    /// [`Func::is_async`]: struct.Func.html#structfield.is_async
+    pub is_async: bool,
+
+    /// Whether a static method should be exposed as a constructor.
+    ///
+    /// `Some(true)` and `Some(false)` are specified with `#[cs_bindgen(constructor)]`
+    /// and `#[cs_bindgen(factory)]`, respectively. If `None`, a static method that
+    /// returns the self type is treated as a constructor.
+    pub constructor: Option<bool>,
+
+    /// Whether the method is exposed as an accessor for a C# property, as specified
+    /// with `#[cs_bindgen(getter)]` or `#[cs_bindgen(setter)]`.
+    ///
+    /// The name of the property is the name of the method without any `get_` or
+    /// `set_` prefix, so that a getter and setter pair can be merged into a single
+    /// property.
+    pub accessor: Option<Accessor>,
+
+    /// The exported trait that the method implements, if the method is declared in a
+    /// trait impl.
+    ///
+    /// The generated class implements the interface generated for the trait, and the
+    /// method implements the corresponding method of the interface.
+    pub trait_name: Option<TypeName>,
+
+    /// See [`Func::rename`].
+    ///
+    /// For property accessors, this is the name of the generated property.
+    ///
+    /// [`Func::rename`]: struct.Func.html#structfield.rename
+    pub rename: Option<Cow<'static, str>>,
+
+    /// See [`Func::docs`].
+    ///
+    /// [`Func::docs`]: struct.Func.html#structfield.docs
+    pub docs: Option<Cow<'static, str>>,
+
+    /// See [`Func::return_docs`].
+    ///
+    /// [`Func::return_docs`]: struct.Func.html#structfield.return_docs
+    pub return_docs: Option<Cow<'static, str>>,
+}
+
+/// The kind of property accessor generated for a method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Accessor {
+    Getter,
+    Setter,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FnArg {
     pub name: Cow<'static, str>,
     pub repr: Repr,
+
+    /// The name of the argument in the generated bindings, as specified with
+    /// `#[cs_bindgen(rename = "...")]`.
+    pub rename: Option<Cow<'static, str>>,
+
+    /// The doc comment for the argument.
+    pub docs: Option<Cow<'static, str>>,
 }
 
 impl FnArg {
@@ -100,8 +317,28 @@ impl FnArg {
         Self {
             name: name.into(),
             repr,
+            rename: None,
+            docs: None,
         }
     }
+
+    /// Sets the name used for the argument in the generated bindings.
+    pub fn with_rename<N>(mut self, rename: N) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        self.rename = Some(rename.into());
+        self
+    }
+
+    /// Sets the doc comment for the argument.
+    pub fn with_docs<D>(mut self, docs: D) -> Self
+    where
+        D: Into<Cow<'static, str>>,
+    {
+        self.docs = Some(docs.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -162,6 +399,9 @@ pub enum Repr {
     /// A borrowed array of elements.
     Slice(Box<Repr>),
 
+    /// A mutably borrowed array of elements.
+    SliceMut(Box<Repr>),
+
     /// An array of elements
     Array {
         element: Box<Repr>,
@@ -182,6 +422,53 @@ pub enum Repr {
         ok: Box<Repr>,
         err: Box<Repr>,
     },
+
+    /// A map of key-value pairs, e.g. `HashMap<K, V>` or `BTreeMap<K, V>`.
+    Map {
+        key: Box<Repr>,
+        value: Box<Repr>,
+    },
+
+    /// A tuple of values.
+    ///
+    /// Note that the unit type `()` is represented by [`Repr::Unit`], so tuples
+    /// always have at least one element.
+    Tuple(Vec<Repr>),
+
+    /// A callback passed in from C#, e.g. `Box<dyn Fn(i32) -> bool>`.
+    ///
+    /// The C# delegate is kept alive until the callback is dropped.
+    Callback {
+        inputs: Vec<Repr>,
+        output: Box<Repr>,
+    },
+
+    /// A function pointer, e.g. `extern "C" fn(i32) -> i32`.
+    ///
+    /// The C# bindings don't support function pointers, since a C# delegate can't be
+    /// marshaled as a plain function pointer under IL2CPP, and C# can't tell when Rust
+    /// is done with one.
+    FnPtr {
+        inputs: Vec<Repr>,
+        output: Box<Repr>,
+    },
+
+    /// A C# object implementing an exported trait, e.g. `Box<dyn Trait>`.
+    ///
+    /// The referenced trait must be included in the set of exported traits. The C#
+    /// object is kept alive until the trait object is dropped.
+    TraitObject(TypeName),
+
+    /// An iterator returned from Rust, e.g. `impl Iterator<Item = T>`.
+    ///
+    /// The iterator is boxed into a handle of the type identified by `type_name`, which
+    /// is generated for the function that returns the iterator. The handle type has its
+    /// own exported functions for advancing and dropping the iterator, rather than
+    /// being exported as a named type.
+    Iterator {
+        type_name: TypeName,
+        item: Box<Repr>,
+    },
 }
 
 impl Repr {
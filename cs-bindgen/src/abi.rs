@@ -17,7 +17,14 @@
 
 use core::mem::MaybeUninit;
 use cs_bindgen_shared::Repr;
-use std::{convert::TryInto, mem, slice, str};
+use std::{
+    array,
+    collections::{BTreeMap, HashMap},
+    convert::TryInto,
+    ffi::c_void,
+    hash::{BuildHasher, Hash},
+    mem, slice, str,
+};
 
 /// The ABI-compatible equivalent to [`String`].
 ///
@@ -79,6 +86,43 @@ pub trait Abi: Sized {
     unsafe fn from_abi(abi: Self::Abi) -> Self;
 }
 
+/// A type that can be used as the receiver of an exported method.
+///
+/// Types that are marshaled as handles pass a pointer to the original value, so
+/// `&self` and `&mut self` methods can borrow the value directly. Types that are
+/// marshaled by value are copied across the FFI boundary instead:
+///
+/// * For `&self` methods the raw value is passed by value, and the method borrows a
+///   temporary copy of it.
+/// * For `&mut self` methods C# passes a pointer to the raw value. Once the method
+///   returns, the modified value is written back through the pointer so that the
+///   changes can be copied back into the C# value.
+///
+/// The `#[cs_bindgen]` attribute implements this trait for all exported types.
+pub trait AbiReceiver: Abi {
+    /// The raw representation of `&self`.
+    type RefAbi: AbiPrimitive;
+
+    /// The raw representation of `&mut self`.
+    type RefMutAbi: AbiPrimitive;
+
+    /// Invokes `func` with a reference to the receiver.
+    ///
+    /// # Safety
+    ///
+    /// `abi` must have been created by the generated C# bindings for a `&self`
+    /// receiver of this type.
+    unsafe fn with_ref<R>(abi: Self::RefAbi, func: impl FnOnce(&Self) -> R) -> R;
+
+    /// Invokes `func` with a mutable reference to the receiver.
+    ///
+    /// # Safety
+    ///
+    /// `abi` must have been created by the generated C# bindings for a `&mut self`
+    /// receiver of this type.
+    unsafe fn with_mut<R>(abi: Self::RefMutAbi, func: impl FnOnce(&mut Self) -> R) -> R;
+}
+
 macro_rules! abi_primitives {
     ($($ty:ty => $repr:ident,)*) => {
         $(
@@ -188,8 +232,17 @@ impl Abi for char {
         self.into()
     }
 
+    /// Converts the raw scalar value back into a `char`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `abi` is not a valid Unicode scalar value (i.e. it's a surrogate code
+    /// point or is larger than `char::MAX`). The generated bindings catch the panic
+    /// and rethrow it as an exception in C#, so invalid values are reported rather
+    /// than being silently replaced.
     unsafe fn from_abi(abi: Self::Abi) -> Self {
-        abi.try_into().unwrap_or_default()
+        abi.try_into()
+            .unwrap_or_else(|_| panic!("Invalid char value received from C#: {:#x}", abi))
     }
 }
 
@@ -276,6 +329,171 @@ impl<'a> Abi for &'a str {
     }
 }
 
+// NOTE: As with `Vec<T>`, the elements of the slice are not converted. This allows
+// methods to return a slice borrowed from `self` without having to allocate.
+impl<T> Abi for &[T]
+where
+    T: Abi,
+{
+    type Abi = RawSlice<T>;
+
+    fn repr() -> Repr {
+        Repr::Slice(Box::new(T::repr()))
+    }
+
+    fn as_abi(&self) -> Self::Abi {
+        RawSlice::from_slice(self)
+    }
+
+    fn into_abi(self) -> Self::Abi {
+        RawSlice::from_slice(self)
+    }
+
+    unsafe fn from_abi(abi: Self::Abi) -> Self {
+        abi.as_slice()
+    }
+}
+
+impl<T> Abi for &mut [T]
+where
+    T: Abi,
+{
+    type Abi = RawSlice<T>;
+
+    fn repr() -> Repr {
+        Repr::SliceMut(Box::new(T::repr()))
+    }
+
+    fn as_abi(&self) -> Self::Abi {
+        RawSlice::from_slice(self)
+    }
+
+    fn into_abi(self) -> Self::Abi {
+        RawSlice::from_slice(self)
+    }
+
+    unsafe fn from_abi(abi: Self::Abi) -> Self {
+        abi.as_slice_mut()
+    }
+}
+
+impl<T> Abi for Option<T>
+where
+    T: Abi,
+{
+    type Abi = RawOption<T::Abi>;
+
+    fn repr() -> Repr {
+        Repr::Option(Box::new(T::repr()))
+    }
+
+    fn as_abi(&self) -> Self::Abi {
+        self.as_ref().map(T::as_abi).into()
+    }
+
+    fn into_abi(self) -> Self::Abi {
+        self.map(T::into_abi).into()
+    }
+
+    unsafe fn from_abi(abi: Self::Abi) -> Self {
+        abi.into_option().map(|value| T::from_abi(value))
+    }
+}
+
+impl<T, E> Abi for Result<T, E>
+where
+    T: Abi,
+    E: Abi,
+{
+    type Abi = RawResult<T::Abi, E::Abi>;
+
+    fn repr() -> Repr {
+        Repr::Result {
+            ok: Box::new(T::repr()),
+            err: Box::new(E::repr()),
+        }
+    }
+
+    fn as_abi(&self) -> Self::Abi {
+        match self {
+            Ok(value) => RawResult::ok(value.as_abi()),
+            Err(err) => RawResult::err(err.as_abi()),
+        }
+    }
+
+    fn into_abi(self) -> Self::Abi {
+        match self {
+            Ok(value) => RawResult::ok(value.into_abi()),
+            Err(err) => RawResult::err(err.into_abi()),
+        }
+    }
+
+    unsafe fn from_abi(abi: Self::Abi) -> Self {
+        abi.into_result()
+            .map(|value| T::from_abi(value))
+            .map_err(|err| E::from_abi(err))
+    }
+}
+
+impl<K, V, S> Abi for HashMap<K, V, S>
+where
+    K: Abi + Clone + Eq + Hash,
+    V: Abi + Clone,
+    S: BuildHasher + Default,
+{
+    type Abi = RawMap<K, V>;
+
+    fn repr() -> Repr {
+        Repr::Map {
+            key: Box::new(K::repr()),
+            value: Box::new(V::repr()),
+        }
+    }
+
+    // NOTE: A map can't be borrowed as a contiguous slice of keys and values, so
+    // `as_abi` has to copy the entries into new vecs. The C# code takes ownership of
+    // the vecs and drops them once the entries have been converted.
+    fn as_abi(&self) -> Self::Abi {
+        RawMap::from_entries(self.iter().map(|(key, value)| (key.clone(), value.clone())))
+    }
+
+    fn into_abi(self) -> Self::Abi {
+        RawMap::from_entries(self)
+    }
+
+    unsafe fn from_abi(abi: Self::Abi) -> Self {
+        abi.into_entries().collect()
+    }
+}
+
+impl<K, V> Abi for BTreeMap<K, V>
+where
+    K: Abi + Clone + Ord,
+    V: Abi + Clone,
+{
+    type Abi = RawMap<K, V>;
+
+    fn repr() -> Repr {
+        Repr::Map {
+            key: Box::new(K::repr()),
+            value: Box::new(V::repr()),
+        }
+    }
+
+    // NOTE: See the `HashMap` impl for why `as_abi` needs to copy the entries.
+    fn as_abi(&self) -> Self::Abi {
+        RawMap::from_entries(self.iter().map(|(key, value)| (key.clone(), value.clone())))
+    }
+
+    fn into_abi(self) -> Self::Abi {
+        RawMap::from_entries(self)
+    }
+
+    unsafe fn from_abi(abi: Self::Abi) -> Self {
+        abi.into_entries().collect()
+    }
+}
+
 /// Raw representation of a [`Vec`] compatible with FFI.
 ///
 /// When converting a `Vec<T>` into a `RawVec<T>`, no conversion is performed for
@@ -372,6 +590,57 @@ impl From<String> for RawVec<u8> {
     }
 }
 
+/// Raw representation of a map type (e.g. [`HashMap`] or [`BTreeMap`]), compatible
+/// with FFI.
+///
+/// The entries of the map are split into a pair of parallel vecs containing the
+/// keys and values, such that the key at a given index corresponds to the value at
+/// the same index. As with [`RawVec`], no conversion is performed for the keys and
+/// values. This allows the generated C# code to convert the keys and values using
+/// the same helper functions that are used for converting a `Vec<T>`.
+///
+/// [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
+/// [`BTreeMap`]: https://doc.rust-lang.org/std/collections/struct.BTreeMap.html
+/// [`RawVec`]: struct.RawVec.html
+#[repr(C)]
+pub struct RawMap<K, V> {
+    pub keys: RawVec<K>,
+    pub values: RawVec<V>,
+}
+
+impl<K, V> RawMap<K, V> {
+    pub fn from_entries<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        Self {
+            keys: keys.into(),
+            values: values.into(),
+        }
+    }
+
+    /// Reconstructs the entries of the original map from their raw parts.
+    ///
+    /// # Safety
+    ///
+    /// `into_entries` must only be called once per map instance. Calling it more than
+    /// once on the same map will result in undefined behavior.
+    pub unsafe fn into_entries(self) -> impl Iterator<Item = (K, V)> {
+        self.keys.into_vec().into_iter().zip(self.values.into_vec())
+    }
+}
+
+impl<K, V> Clone for RawMap<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for RawMap<K, V> {}
+
+unsafe impl<K, V> AbiPrimitive for RawMap<K, V> {}
+
 /// Raw representation of a `&[T]`.
 ///
 /// When converting a `&[T]` into a `RawSlice<T>`, no conversion is performed for
@@ -388,9 +657,39 @@ pub struct RawSlice<T> {
 }
 
 impl<T> RawSlice<T> {
+    /// Creates a raw slice pointing to the elements of `slice`, without converting
+    /// the elements.
+    pub fn from_slice(slice: &[T]) -> Self {
+        Self {
+            ptr: slice.as_ptr(),
+            len: slice.len(),
+        }
+    }
+
     pub unsafe fn as_slice<'a>(self) -> &'a [T] {
+        // NOTE: Pinning an empty span in C# produces a null pointer, which isn't valid
+        // for `slice::from_raw_parts` even when the length is 0.
+        if self.len == 0 {
+            return &[];
+        }
+
         slice::from_raw_parts(self.ptr, self.len)
     }
+
+    /// Reconstructs the original slice as a mutable slice.
+    ///
+    /// # Safety
+    ///
+    /// The raw slice must point to memory that is valid for writes, such as a buffer
+    /// that was pinned by the C# caller, and must not be aliased for the duration of
+    /// `'a`.
+    pub unsafe fn as_slice_mut<'a>(self) -> &'a mut [T] {
+        if self.len == 0 {
+            return &mut [];
+        }
+
+        slice::from_raw_parts_mut(self.ptr as *mut T, self.len)
+    }
 }
 
 impl<'a, T: 'a> RawSlice<T>
@@ -473,96 +772,82 @@ pub unsafe fn convert_list<T: Abi>(raw: RawSlice<T::Abi>) -> RawVec<T> {
         .into()
 }
 
-/// Generates the `Abi` implementation for arrays of different lengths.
-///
-/// For an array of type `T`, it's ABI-compatible representation is an array of the
-/// same length of type `T::Abi`. Conversion is performed directly for each element.
-/// This macro helps cut down on the boilerplate needed for the implementations.
-macro_rules! array_abi {
-    ( $len:expr; $($elem:ident),* ) => {
-        unsafe impl<T: AbiPrimitive> AbiPrimitive for [T; $len] {}
+// For an array of type `T`, its ABI-compatible representation is an array of the
+// same length of type `T::Abi`. Conversion is performed directly for each element.
+unsafe impl<T: AbiPrimitive, const N: usize> AbiPrimitive for [T; N] {}
+
+impl<T: Abi, const N: usize> Abi for [T; N] {
+    type Abi = [T::Abi; N];
+
+    fn repr() -> Repr {
+        Repr::Array {
+            element: Box::new(T::repr()),
+            len: N,
+        }
+    }
+
+    fn as_abi(&self) -> Self::Abi {
+        array::from_fn(|index| self[index].as_abi())
+    }
 
-        impl<T: Abi> Abi for [T; $len] {
-            type Abi = [T::Abi; $len];
+    fn into_abi(self) -> Self::Abi {
+        self.map(T::into_abi)
+    }
+
+    unsafe fn from_abi(abi: Self::Abi) -> Self {
+        abi.map(|element| T::from_abi(element))
+    }
+}
+
+/// Generates the `Abi` implementation for tuples of different lengths.
+///
+/// For a tuple `(A, B, ...)`, its ABI-compatible representation is a `#[repr(C)]`
+/// struct with the fields `(A::Abi, B::Abi, ...)`. Tuples themselves don't have a
+/// guaranteed layout, so we need to define a separate raw struct for each tuple
+/// length. Conversion is performed directly for each element.
+macro_rules! tuple_abi {
+    ( $raw:ident; $( $elem:ident => $index:tt ),* ) => {
+        /// Raw representation of a tuple, compatible with FFI.
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy)]
+        pub struct $raw<$( $elem ),*>( $( pub $elem ),* );
+
+        unsafe impl<$( $elem: AbiPrimitive ),*> AbiPrimitive for $raw<$( $elem ),*> {}
+
+        impl<$( $elem: Abi ),*> Abi for ( $( $elem, )* ) {
+            type Abi = $raw<$( <$elem as Abi>::Abi ),*>;
 
             fn repr() -> Repr {
-                Repr::Array {
-                    element: Box::new(T::repr()),
-                    len: $len,
-                }
+                Repr::Tuple(vec![ $( $elem::repr() ),* ])
             }
 
             fn as_abi(&self) -> Self::Abi {
-                let [
-                    $( $elem, )*
-                ] = self;
-
-                [
-                    $(
-                        $crate::abi::Abi::as_abi($elem),
-                    )*
-                ]
+                $raw( $( self.$index.as_abi() ),* )
             }
 
             fn into_abi(self) -> Self::Abi {
-                let [
-                    $( $elem, )*
-                ] = self;
-
-                [
-                    $(
-                        $crate::abi::Abi::into_abi($elem),
-                    )*
-                ]
+                $raw( $( self.$index.into_abi() ),* )
             }
 
             unsafe fn from_abi(abi: Self::Abi) -> Self {
-                let [
-                    $( $elem, )*
-                ] = abi;
-
-                [
-                    $(
-                        $crate::abi::Abi::from_abi($elem),
-                    )*
-                ]
+                ( $( $elem::from_abi(abi.$index), )* )
             }
         }
     };
 }
 
-array_abi!(1; a);
-array_abi!(2; a, b);
-array_abi!(3; a, b, c);
-array_abi!(4; a, b, c, d);
-array_abi!(5; a, b, c, d, e);
-array_abi!(6; a, b, c, d, e, f);
-array_abi!(7; a, b, c, d, e, f, g);
-array_abi!(8; a, b, c, d, e, f, g, h);
-array_abi!(9; a, b, c, d, e, f, g, h, i);
-array_abi!(10; a, b, c, d, e, f, g, h, i, j);
-array_abi!(11; a, b, c, d, e, f, g, h, i, j, k);
-array_abi!(12; a, b, c, d, e, f, g, h, i, j, k, l);
-array_abi!(13; a, b, c, d, e, f, g, h, i, j, k, l, m);
-array_abi!(14; a, b, c, d, e, f, g, h, i, j, k, l, m, n);
-array_abi!(15; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o);
-array_abi!(16; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p);
-array_abi!(17; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q);
-array_abi!(18; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r);
-array_abi!(19; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s);
-array_abi!(20; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t);
-array_abi!(21; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u);
-array_abi!(22; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v);
-array_abi!(23; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w);
-array_abi!(24; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x);
-array_abi!(25; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y);
-array_abi!(26; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z);
-array_abi!(27; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z, aa);
-array_abi!(28; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z, aa, bb);
-array_abi!(29; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z, aa, bb, cc);
-array_abi!(30; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z, aa, bb, cc, dd);
-array_abi!(31; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z, aa, bb, cc, dd, ee);
-array_abi!(32; a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z, aa, bb, cc, dd, ee, ff);
+tuple_abi!(RawTuple1; A => 0);
+tuple_abi!(RawTuple2; A => 0, B => 1);
+tuple_abi!(RawTuple3; A => 0, B => 1, C => 2);
+tuple_abi!(RawTuple4; A => 0, B => 1, C => 2, D => 3);
+tuple_abi!(RawTuple5; A => 0, B => 1, C => 2, D => 3, E => 4);
+tuple_abi!(RawTuple6; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+tuple_abi!(RawTuple7; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6);
+tuple_abi!(RawTuple8; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7);
+tuple_abi!(RawTuple9; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8);
+tuple_abi!(RawTuple10; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9);
+tuple_abi!(RawTuple11; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10);
+tuple_abi!(RawTuple12; A => 0, B => 1, C => 2, D => 3, E => 4, F => 5, G => 6, H => 7, I => 8, J => 9, K => 10, L => 11);
 
 /// Deconstructed representation of an enum, compatible with FFI.
 ///
@@ -602,3 +887,371 @@ impl<D, V> RawEnum<D, V> {
 }
 
 unsafe impl<D: AbiPrimitive, V: AbiPrimitive> AbiPrimitive for RawEnum<D, V> {}
+
+/// Raw representation of an [`Option`], compatible with FFI.
+///
+/// The raw representation of an optional value is a flag indicating whether the
+/// value is present, followed by the value itself. `value` is only initialized if
+/// `is_some` is non-zero.
+///
+/// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+#[repr(C)]
+#[derive(Debug, Copy)]
+pub struct RawOption<T> {
+    pub is_some: u8,
+    pub value: MaybeUninit<T>,
+}
+
+impl<T: Copy> Clone for RawOption<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> RawOption<T> {
+    pub const fn some(value: T) -> Self {
+        Self {
+            is_some: 1,
+            value: MaybeUninit::new(value),
+        }
+    }
+
+    pub const fn none() -> Self {
+        Self {
+            is_some: 0,
+            value: MaybeUninit::uninit(),
+        }
+    }
+
+    /// Reconstructs the original `Option` from its raw representation.
+    ///
+    /// # Safety
+    ///
+    /// If `is_some` is non-zero then `value` must have been initialized.
+    pub unsafe fn into_option(self) -> Option<T> {
+        if self.is_some != 0 {
+            Some(self.value.assume_init())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> From<Option<T>> for RawOption<T> {
+    fn from(from: Option<T>) -> Self {
+        match from {
+            Some(value) => Self::some(value),
+            None => Self::none(),
+        }
+    }
+}
+
+unsafe impl<T: AbiPrimitive> AbiPrimitive for RawOption<T> {}
+
+/// Raw representation of a [`Result`], compatible with FFI.
+///
+/// The raw representation of a result is a flag indicating whether the operation
+/// succeeded, followed by a union of the success and error values. If `is_ok` is
+/// non-zero then `value.ok` is valid, otherwise `value.err` is valid.
+///
+/// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RawResult<T: Copy, E: Copy> {
+    pub is_ok: u8,
+    pub value: RawResultValue<T, E>,
+}
+
+/// The union of the success and error values of a [`RawResult`].
+///
+/// [`RawResult`]: struct.RawResult.html
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union RawResultValue<T: Copy, E: Copy> {
+    pub ok: T,
+    pub err: E,
+}
+
+impl<T: Copy, E: Copy> RawResult<T, E> {
+    pub const fn ok(value: T) -> Self {
+        Self {
+            is_ok: 1,
+            value: RawResultValue { ok: value },
+        }
+    }
+
+    pub const fn err(err: E) -> Self {
+        Self {
+            is_ok: 0,
+            value: RawResultValue { err },
+        }
+    }
+
+    /// Reconstructs the original `Result` from its raw representation.
+    ///
+    /// # Safety
+    ///
+    /// The union field indicated by `is_ok` must have been initialized.
+    pub unsafe fn into_result(self) -> Result<T, E> {
+        if self.is_ok != 0 {
+            Ok(self.value.ok)
+        } else {
+            Err(self.value.err)
+        }
+    }
+}
+
+unsafe impl<T: AbiPrimitive, E: AbiPrimitive> AbiPrimitive for RawResult<T, E> {}
+
+/// Raw representation of a callback passed in from C#, e.g. a `Box<dyn Fn(i32) -> bool>`.
+///
+/// The C# delegate is kept alive by a `GCHandle` while Rust holds the callback, and
+/// the pointer to the handle is passed as `context`. `invoke` is a pointer to a
+/// trampoline function generated for the signature of the callback, which takes
+/// `context`, a pointer to a flag that's set if the delegate threw an exception, and
+/// the raw arguments, and returns the raw result. `drop` frees the `GCHandle`, and is
+/// called once the callback is dropped.
+///
+/// `drop` is nullable so that a zeroed `RawCallback` is still valid, since raw values
+/// are zeroed when a call into Rust panics.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RawCallback {
+    pub context: *const c_void,
+    pub invoke: *const c_void,
+    pub drop: Option<unsafe extern "C" fn(*const c_void)>,
+}
+
+unsafe impl AbiPrimitive for RawCallback {}
+
+/// Owns a [`RawCallback`], releasing the C# delegate when dropped.
+///
+/// [`RawCallback`]: struct.RawCallback.html
+struct CallbackHandle(RawCallback);
+
+impl Drop for CallbackHandle {
+    fn drop(&mut self) {
+        if let Some(drop) = self.0.drop {
+            unsafe { drop(self.0.context) }
+        }
+    }
+}
+
+// SAFETY: The `GCHandle` for the delegate can be used and freed from any thread, and
+// the generated trampoline functions don't depend on the thread that they're called
+// from.
+unsafe impl Send for CallbackHandle {}
+unsafe impl Sync for CallbackHandle {}
+
+/// A type that has the same representation in Rust and C#, and so can be used
+/// directly in the signature of an `extern "C" fn` passed in from C#.
+///
+/// # Safety
+///
+/// The type must be its own `Abi` representation.
+pub unsafe trait FnPtrSafe: Abi {}
+
+macro_rules! fn_ptr_safe {
+    ( $( $ty:ty ),* ) => {
+        $(
+            unsafe impl FnPtrSafe for $ty {}
+        )*
+    };
+}
+
+fn_ptr_safe!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// A type that can be returned from an `extern "C" fn` passed in from C#.
+///
+/// This is any [`FnPtrSafe`] type, as well as `()`, which corresponds to a `void`
+/// return type in C#.
+///
+/// # Safety
+///
+/// The type must either be its own `Abi` representation or be `()`.
+///
+/// [`FnPtrSafe`]: trait.FnPtrSafe.html
+pub unsafe trait FnPtrReturn: Abi {}
+
+unsafe impl<T: FnPtrSafe> FnPtrReturn for T {}
+unsafe impl FnPtrReturn for () {}
+
+/// Calls a trampoline generated by the C# bindings and converts its raw result.
+///
+/// `invoke` calls the trampoline, passing it the flag that the trampoline sets if the
+/// C# code threw an exception. In that case the raw result isn't valid and can't be
+/// converted, so this panics with `message` instead. The exception is rethrown in C#
+/// once the panic reaches the FFI boundary.
+///
+/// # Safety
+///
+/// The raw result returned by `invoke` must be valid to convert into `R` if the
+/// trampoline didn't throw.
+pub unsafe fn call_trampoline<R: Abi>(message: &str, invoke: impl FnOnce(*mut u8) -> R::Abi) -> R {
+    let mut threw = 0;
+    let result = invoke(&mut threw);
+    if threw != 0 {
+        panic!("{}", message);
+    }
+
+    R::from_abi(result)
+}
+
+/// Generates the `Abi` implementations for callbacks with different numbers of
+/// arguments.
+///
+/// `Box<dyn Fn(A, B, ...) -> R>` (optionally with `Send` and `Sync` bounds) is
+/// passed as a [`RawCallback`]. Calling the boxed closure converts each argument to
+/// its raw representation and invokes the C# delegate through the trampoline, so the
+/// arguments and return type of the callback can be any type that implements `Abi`.
+/// If the delegate throws an exception, calling the closure panics.
+///
+/// `extern "C" fn(A, B, ...) -> R` is passed as a raw function pointer that Rust
+/// calls directly, so the arguments must implement [`FnPtrSafe`] and the return type
+/// must implement [`FnPtrReturn`]. Note that the C# bindings don't support function
+/// pointers, see `Repr::FnPtr`.
+///
+/// Callbacks can only be passed from C# to Rust, and `#[cs_bindgen]` rejects them
+/// anywhere they would be converted into their raw representation: in return types,
+/// nested within other types, and in the fields of types marshaled by value.
+///
+/// [`RawCallback`]: struct.RawCallback.html
+/// [`FnPtrSafe`]: trait.FnPtrSafe.html
+/// [`FnPtrReturn`]: trait.FnPtrReturn.html
+macro_rules! callback_abi {
+    ( $( $arg:ident: $ty:ident ),* ) => {
+        callback_abi!(@boxed [dyn Fn($( $ty ),*) -> R] $( $arg: $ty ),*);
+        callback_abi!(@boxed [dyn Fn($( $ty ),*) -> R + Send] $( $arg: $ty ),*);
+        callback_abi!(@boxed [dyn Fn($( $ty ),*) -> R + Send + Sync] $( $arg: $ty ),*);
+
+        impl<$( $ty: FnPtrSafe, )* R: FnPtrReturn> Abi for extern "C" fn($( $ty ),*) -> R {
+            type Abi = Self;
+
+            fn repr() -> Repr {
+                Repr::FnPtr {
+                    inputs: vec![$( $ty::repr() ),*],
+                    output: Box::new(R::repr()),
+                }
+            }
+
+            fn as_abi(&self) -> Self::Abi {
+                *self
+            }
+
+            fn into_abi(self) -> Self::Abi {
+                self
+            }
+
+            unsafe fn from_abi(abi: Self::Abi) -> Self {
+                abi
+            }
+        }
+
+        unsafe impl<$( $ty, )* R> AbiPrimitive for extern "C" fn($( $ty ),*) -> R {}
+    };
+
+    (@boxed [$( $dyn:tt )*] $( $arg:ident: $ty:ident ),*) => {
+        impl<$( $ty: Abi + 'static, )* R: Abi + 'static> Abi for Box<$( $dyn )*> {
+            type Abi = RawCallback;
+
+            fn repr() -> Repr {
+                Repr::Callback {
+                    inputs: vec![$( $ty::repr() ),*],
+                    output: Box::new(R::repr()),
+                }
+            }
+
+            fn as_abi(&self) -> Self::Abi {
+                unreachable!("Callbacks passed from Rust to C# should have already been rejected");
+            }
+
+            fn into_abi(self) -> Self::Abi {
+                unreachable!("Callbacks passed from Rust to C# should have already been rejected");
+            }
+
+            unsafe fn from_abi(abi: Self::Abi) -> Self {
+                let handle = CallbackHandle(abi);
+                Box::new(move |$( $arg: $ty ),*| {
+                    let invoke: unsafe extern "C" fn(*const c_void, *mut u8, $( $ty::Abi ),*) -> R::Abi =
+                        mem::transmute(handle.0.invoke);
+
+                    call_trampoline("C# callback threw an exception", |threw| {
+                        invoke(handle.0.context, threw, $( $arg.into_abi() ),*)
+                    })
+                })
+            }
+        }
+    };
+}
+
+callback_abi!();
+callback_abi!(a: A);
+callback_abi!(a: A, b: B);
+callback_abi!(a: A, b: B, c: C);
+callback_abi!(a: A, b: B, c: C, d: D);
+callback_abi!(a: A, b: B, c: C, d: D, e: E);
+callback_abi!(a: A, b: B, c: C, d: D, e: E, f: F);
+
+/// Raw representation of a C# object implementing an exported trait, e.g. a
+/// `Box<dyn AssetLoader>`.
+///
+/// As with [`RawCallback`], the C# object is kept alive by a `GCHandle` that's passed
+/// as `context` and freed by `drop`. `vtable` is the struct generated by
+/// `#[cs_bindgen]` for the trait, which contains a pointer to a trampoline function for
+/// each method of the trait. Each trampoline has the same signature as the trampoline
+/// for a [`RawCallback`], with the method's arguments following `context` and the
+/// exception flag. As with [`RawCallback`], `drop` is nullable so that a zeroed
+/// `RawTraitObject` is still valid.
+///
+/// [`RawCallback`]: struct.RawCallback.html
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RawTraitObject<V> {
+    pub context: *const c_void,
+    pub drop: Option<unsafe extern "C" fn(*const c_void)>,
+    pub vtable: V,
+}
+
+unsafe impl<V: AbiPrimitive> AbiPrimitive for RawTraitObject<V> {}
+
+/// Owns a [`RawTraitObject`], releasing the C# object when dropped.
+///
+/// The type that `#[cs_bindgen]` generates to implement an exported trait wraps a
+/// `TraitObject`, and calls into C# through the trampolines in its vtable.
+///
+/// [`RawTraitObject`]: struct.RawTraitObject.html
+pub struct TraitObject<V>(RawTraitObject<V>);
+
+impl<V> TraitObject<V> {
+    /// Takes ownership of a raw trait object passed in from C#.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been created by the generated C# bindings for the trait that
+    /// `V` is the vtable of, and must not be used to create another `TraitObject`.
+    pub unsafe fn from_raw(raw: RawTraitObject<V>) -> Self {
+        TraitObject(raw)
+    }
+
+    /// The `GCHandle` for the C# object, which is passed to each trampoline.
+    pub fn context(&self) -> *const c_void {
+        self.0.context
+    }
+
+    pub fn vtable(&self) -> &V {
+        &self.0.vtable
+    }
+}
+
+impl<V> Drop for TraitObject<V> {
+    fn drop(&mut self) {
+        if let Some(drop) = self.0.drop {
+            unsafe { drop(self.0.context) }
+        }
+    }
+}
+
+// SAFETY: See `CallbackHandle`. The vtable only contains pointers to the generated
+// trampolines, which can be called from any thread.
+unsafe impl<V> Send for TraitObject<V> {}
+unsafe impl<V> Sync for TraitObject<V> {}
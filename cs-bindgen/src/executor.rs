@@ -0,0 +1,186 @@
+//! A minimal executor for the futures returned by exported `async` functions.
+//!
+//! The binding function for an `async fn` doesn't wait for the returned future.
+//! Instead, the future is spawned onto the executor along with a [`RawCompletion`]
+//! passed in from C#, which is invoked with the raw output once the future
+//! completes. The generated C# wraps the completion in a `TaskCompletionSource`, and
+//! returns the corresponding `Task` from the wrapper function.
+//!
+//! All futures are run on a single worker thread, which is started the first time a
+//! future is spawned. A future is only polled again once it has been woken, so any
+//! future that uses its `Waker` correctly can be run on the executor. Futures that
+//! depend on a specific runtime (e.g. futures that need a Tokio reactor) must still
+//! be driven by that runtime, e.g. by spawning them onto the runtime and awaiting
+//! the result through a channel.
+//!
+//! [`RawCompletion`]: struct.RawCompletion.html
+
+use crate::{abi::Abi, panic::catch_panic};
+use std::{
+    ffi::c_void,
+    future::{self, Future},
+    mem,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+};
+
+/// Raw representation of the completion callback passed in from C# for a call to an
+/// exported `async fn`.
+///
+/// `context` is a `GCHandle` for the `TaskCompletionSource` of the call, and
+/// `complete` is a trampoline that completes it with the raw output of the future.
+/// `complete` is called exactly once, from the executor's worker thread, and frees
+/// the `GCHandle`.
+///
+/// If the future panics, `complete` is called with a zeroed output, and the panic is
+/// stored in the same way as for [`catch_panic`]. The C# trampoline checks for a
+/// panic before converting the output, and fails the `Task` if there was one.
+///
+/// [`catch_panic`]: ../panic/fn.catch_panic.html
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RawCompletion<T> {
+    pub context: *const c_void,
+    pub complete: unsafe extern "C" fn(*const c_void, T),
+}
+
+/// Owns a [`RawCompletion`] so that it can be sent to the worker thread.
+///
+/// [`RawCompletion`]: struct.RawCompletion.html
+struct Completion<T>(RawCompletion<T>);
+
+// SAFETY: The `GCHandle` for the `TaskCompletionSource` can be used and freed from any
+// thread, and the trampoline doesn't depend on the thread that it's called from.
+unsafe impl<T> Send for Completion<T> {}
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A spawned future, along with the state needed to reschedule it once woken.
+struct Task {
+    /// The future, or `None` once it has completed.
+    future: Mutex<Option<BoxFuture>>,
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        schedule(self);
+    }
+}
+
+/// The queue of tasks that are ready to be polled, or `None` if the worker thread
+/// hasn't been started yet.
+static QUEUE: Mutex<Option<Sender<Arc<Task>>>> = Mutex::new(None);
+
+/// Spawns `future` onto the executor.
+///
+/// The future is run to completion on the executor's worker thread. If polling the
+/// future panics, the future is dropped.
+pub fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    schedule(Arc::new(Task {
+        future: Mutex::new(Some(Box::pin(future))),
+    }));
+}
+
+/// Spawns the future returned by an exported `async fn`, invoking `completion` with
+/// the raw output of the future once it completes.
+///
+/// The future is polled and its output is converted within [`catch_panic`], so a
+/// panic completes the call rather than being lost on the worker thread.
+///
+/// # Safety
+///
+/// `completion` must have been created by the generated C# bindings for the output
+/// type of the future. The raw output type must also be valid when zeroed, as with
+/// [`catch_panic`].
+///
+/// [`catch_panic`]: ../panic/fn.catch_panic.html
+pub unsafe fn spawn_with_completion<F>(
+    future: F,
+    completion: RawCompletion<<F::Output as Abi>::Abi>,
+) where
+    F: Future + Send + 'static,
+    F::Output: Abi,
+{
+    let completion = Completion(completion);
+    let mut future = Some(Box::pin(future));
+    spawn(future::poll_fn(move |cx| {
+        // NOTE: The future is dropped before the call is completed, since the future
+        // may borrow a handle that C# only releases once the call completes. If polling
+        // panics, the future is still dropped within `catch_panic`, since dropping it
+        // may panic as well. Otherwise, the original panic is resumed so that it's the
+        // one reported.
+        let mut pending = false;
+        let output = catch_panic(|| {
+            let poll = panic::catch_unwind(AssertUnwindSafe(|| {
+                future.as_mut().unwrap().as_mut().poll(cx)
+            }));
+            match poll {
+                Ok(Poll::Ready(output)) => {
+                    future = None;
+                    output.into_abi()
+                }
+                Ok(Poll::Pending) => {
+                    pending = true;
+                    mem::zeroed()
+                }
+                Err(payload) => {
+                    future = None;
+                    panic::resume_unwind(payload)
+                }
+            }
+        });
+
+        if pending {
+            return Poll::Pending;
+        }
+
+        (completion.0.complete)(completion.0.context, output);
+        Poll::Ready(())
+    }));
+}
+
+/// Adds `task` to the queue of tasks to be polled, starting the worker thread if
+/// necessary.
+fn schedule(task: Arc<Task>) {
+    let mut queue = QUEUE.lock().unwrap();
+    let sender = queue.get_or_insert_with(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("cs-bindgen-executor".into())
+            .spawn(move || run(receiver))
+            .expect("Failed to start the executor thread");
+        sender
+    });
+
+    // NOTE: The worker thread never exits, so sending only fails if the thread itself
+    // panicked, in which case there's nothing left to run the task.
+    let _ = sender.send(task);
+}
+
+/// Polls tasks as they're scheduled. Runs on the worker thread.
+fn run(receiver: Receiver<Arc<Task>>) {
+    for task in receiver {
+        let waker = Waker::from(task.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        // NOTE: A task may be scheduled again while it's being polled, or after it has
+        // already completed, in which case the extra wakeups are ignored.
+        let mut future = task.future.lock().unwrap();
+        let completed = match future.as_mut() {
+            Some(inner) => panic::catch_unwind(AssertUnwindSafe(|| inner.as_mut().poll(&mut cx)))
+                .map(|poll| poll.is_ready())
+                .unwrap_or(true),
+            None => false,
+        };
+
+        if completed {
+            *future = None;
+        }
+    }
+}
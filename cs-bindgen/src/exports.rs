@@ -14,7 +14,10 @@
 //!
 //! [`export`]: ../macro.export.html
 
-use crate::abi::{self, Abi, RawSlice, RawString, RawVec};
+use crate::{
+    abi::{self, Abi, RawOption, RawSlice, RawString, RawVec},
+    panic::{self, RawPanic},
+};
 
 macro_rules! drop_vec {
     ( $( $prim:ty => [$drop_fn:ident, $convert_fn:ident], )* ) => {
@@ -48,6 +51,22 @@ drop_vec! {
 
     bool => [__cs_bindgen_drop_vec_bool, __cs_bindgen_convert_vec_bool],
     char => [__cs_bindgen_drop_vec_char, __cs_bindgen_convert_vec_char],
+
+    String => [__cs_bindgen_drop_vec_string, __cs_bindgen_convert_vec_string],
+}
+
+/// Borrows the string at `index` in a `Vec<String>`.
+///
+/// Strings can't be copied directly out of the vec, so the C# code uses this to
+/// convert each element when converting a `Vec<String>` to a `List<string>`. The
+/// string is still owned by the vec, so it's returned as a borrowed slice that the
+/// C# code copies without freeing.
+///
+/// # Safety
+///
+/// `raw` must point to a valid slice of strings, and `index` must be in bounds.
+pub unsafe fn __cs_bindgen_index_vec_string(raw: RawSlice<String>, index: usize) -> RawSlice<u8> {
+    raw.as_slice()[index].as_str().into()
 }
 
 /// Converts a C# string (i.e. a UTF-16 slice) into a Rust string.
@@ -56,3 +75,8 @@ pub unsafe fn __cs_bindgen_string_from_utf16(raw: RawSlice<u16>) -> RawString {
         .expect("Failed to convert C# string to Rust string")
         .into()
 }
+
+/// Retrieves the most recent panic caught at the FFI boundary on the current thread.
+pub fn __cs_bindgen_take_panic() -> RawOption<RawPanic> {
+    panic::take_raw_panic()
+}
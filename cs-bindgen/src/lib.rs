@@ -1,5 +1,8 @@
 pub mod abi;
+pub mod executor;
 pub mod exports;
+pub mod panic;
+pub mod thread_safety;
 
 // Re-export crates used in the generated code.
 pub use cs_bindgen_shared as shared;
@@ -22,7 +25,7 @@ macro_rules! export {
     (fn $name:ident($( $arg:ident : $type:ty ),*) -> $ret:ty) => {
         #[no_mangle]
         pub unsafe extern "C" fn $name($( $arg : $type),*) -> $ret {
-            $crate::exports::$name($( $arg ),*)
+            $crate::panic::catch_panic(move || $crate::exports::$name($( $arg ),*))
         }
     };
 
@@ -31,6 +34,7 @@ macro_rules! export {
     };
 
     () => {
+        $crate::export!(fn __cs_bindgen_take_panic() -> $crate::abi::RawOption<$crate::panic::RawPanic>);
         $crate::export!(fn __cs_bindgen_string_from_utf16(raw: $crate::abi::RawSlice<u16>) -> $crate::abi::RawString);
 
         $crate::export!(fn __cs_bindgen_drop_vec_u8(raw: $crate::abi::RawVec<u8>));
@@ -47,6 +51,7 @@ macro_rules! export {
         $crate::export!(fn __cs_bindgen_drop_vec_f64(raw: $crate::abi::RawVec<f64>));
         $crate::export!(fn __cs_bindgen_drop_vec_bool(raw: $crate::abi::RawVec<bool>));
         $crate::export!(fn __cs_bindgen_drop_vec_char(raw: $crate::abi::RawVec<char>));
+        $crate::export!(fn __cs_bindgen_drop_vec_string(raw: $crate::abi::RawVec<String>));
 
         $crate::export!(fn __cs_bindgen_convert_vec_u8(raw: $crate::abi::RawSlice<<u8 as $crate::abi::Abi>::Abi>) -> $crate::abi::RawVec<u8>);
         $crate::export!(fn __cs_bindgen_convert_vec_u16(raw: $crate::abi::RawSlice<<u16 as $crate::abi::Abi>::Abi>) -> $crate::abi::RawVec<u16>);
@@ -62,5 +67,8 @@ macro_rules! export {
         $crate::export!(fn __cs_bindgen_convert_vec_f64(raw: $crate::abi::RawSlice<<f64 as $crate::abi::Abi>::Abi>) -> $crate::abi::RawVec<f64>);
         $crate::export!(fn __cs_bindgen_convert_vec_bool(raw: $crate::abi::RawSlice<<bool as $crate::abi::Abi>::Abi>) -> $crate::abi::RawVec<bool>);
         $crate::export!(fn __cs_bindgen_convert_vec_char(raw: $crate::abi::RawSlice<<char as $crate::abi::Abi>::Abi>) -> $crate::abi::RawVec<char>);
+        $crate::export!(fn __cs_bindgen_convert_vec_string(raw: $crate::abi::RawSlice<<String as $crate::abi::Abi>::Abi>) -> $crate::abi::RawVec<String>);
+
+        $crate::export!(fn __cs_bindgen_index_vec_string(raw: $crate::abi::RawSlice<String>, index: usize) -> $crate::abi::RawSlice<u8>);
     };
 }
@@ -0,0 +1,128 @@
+//! Support for catching panics at the FFI boundary.
+//!
+//! Unwinding out of an `extern "C"` function is undefined behavior, so all of the
+//! generated binding functions run user code within [`catch_panic`]. If the user
+//! code panics, the panic message (and the location of the panic, if available) is
+//! stored in a thread-local slot and the binding function returns a zeroed value.
+//! The generated C# code then calls `__cs_bindgen_take_panic` after each call into
+//! Rust, and throws a `RustPanicException` if a panic occurred.
+//!
+//! [`catch_panic`]: fn.catch_panic.html
+
+use crate::abi::{RawOption, RawString};
+use std::{
+    any::Any,
+    cell::RefCell,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+thread_local! {
+    /// The location of the most recent panic on the current thread, as reported by the
+    /// panic hook.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+
+    /// The most recent panic caught by `catch_panic` that hasn't yet been retrieved by
+    /// `take_panic`.
+    static LAST_PANIC: RefCell<Option<Panic>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// A panic that was caught at the FFI boundary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panic {
+    /// The panic message, or a generic message if the panic payload wasn't a string.
+    pub message: String,
+
+    /// The source location of the panic, e.g. `src/lib.rs:12:5`.
+    ///
+    /// The location is captured by a panic hook, and so will be `None` if the user
+    /// has replaced the panic hook.
+    pub location: Option<String>,
+}
+
+/// Raw representation of a [`Panic`], compatible with FFI.
+///
+/// `location` is empty if the location of the panic is not known.
+///
+/// [`Panic`]: struct.Panic.html
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RawPanic {
+    pub message: RawString,
+    pub location: RawString,
+}
+
+/// Invokes `func`, catching any panic that occurs.
+///
+/// If `func` panics, the panic is stored so that it can later be retrieved with
+/// [`take_panic`] and a zeroed value is returned.
+///
+/// # Safety
+///
+/// `R` must be valid when zeroed. This is true for all of the raw ABI types used in
+/// the generated bindings, which are all primitives, raw pointers, nullable function
+/// pointers (i.e. `Option<extern "C" fn(..)>`), or `#[repr(C)]` structs composed of
+/// them. A plain function pointer isn't valid when zeroed, so it must never be part of
+/// `R`.
+///
+/// [`take_panic`]: fn.take_panic.html
+pub unsafe fn catch_panic<R>(func: impl FnOnce() -> R) -> R {
+    // Install a panic hook in order to capture the location of the panic, since that
+    // information isn't available from the panic payload. The previous hook is still
+    // invoked so that the default panic output isn't lost.
+    INSTALL_HOOK.call_once(|| {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(ToString::to_string);
+            PANIC_LOCATION.with(|cell| *cell.borrow_mut() = location);
+            prev_hook(info);
+        }));
+    });
+
+    // NOTE: It's safe to assert unwind safety here because the state of the closure's
+    // captures is never observed after a panic, they're all dropped during unwinding.
+    match panic::catch_unwind(AssertUnwindSafe(func)) {
+        Ok(result) => result,
+
+        Err(payload) => {
+            let panic = Panic {
+                message: panic_message(&*payload),
+                location: PANIC_LOCATION.with(|cell| cell.borrow_mut().take()),
+            };
+            LAST_PANIC.with(|cell| *cell.borrow_mut() = Some(panic));
+
+            mem::zeroed()
+        }
+    }
+}
+
+/// Retrieves the most recent panic caught on the current thread, if any.
+///
+/// Clears the stored panic, such that subsequent calls return `None` until another
+/// panic is caught.
+pub fn take_panic() -> Option<Panic> {
+    LAST_PANIC.with(|cell| cell.borrow_mut().take())
+}
+
+/// Converts the most recent panic into its raw representation, if any.
+pub fn take_raw_panic() -> RawOption<RawPanic> {
+    take_panic()
+        .map(|panic| RawPanic {
+            message: panic.message.into(),
+            location: panic.location.unwrap_or_default().into(),
+        })
+        .into()
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Rust code panicked with a non-string payload".into()
+    }
+}
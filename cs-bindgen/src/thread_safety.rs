@@ -0,0 +1,65 @@
+//! Support for detecting whether an exported type is `Send` and `Sync`.
+//!
+//! A proc macro only sees the tokens of the item it's applied to, so it can't tell
+//! whether a type implements an auto trait. Instead, the generated describe function
+//! uses [`Probe`] to check at compile time of the user's crate, relying on inherent
+//! methods taking precedence over trait methods:
+//!
+//! ```
+//! use cs_bindgen::thread_safety::{Fallback, Probe};
+//! use std::rc::Rc;
+//!
+//! assert!(Probe::<String>::new().is_send());
+//! assert!(!Probe::<Rc<String>>::new().is_send());
+//! ```
+//!
+//! The inherent `is_send` and `is_sync` methods only apply if `T` implements the
+//! corresponding trait. Otherwise, method resolution falls back to the methods of the
+//! [`Fallback`] trait, which return `false`. This only works when `T` is a concrete
+//! type, which is always the case for exported types.
+//!
+//! [`Probe`]: struct.Probe.html
+//! [`Fallback`]: trait.Fallback.html
+
+use std::marker::PhantomData;
+
+/// Checks whether `T` implements `Send` and `Sync`.
+///
+/// See [the module documentation](index.html) for more information.
+pub struct Probe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> Probe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Probe(PhantomData)
+    }
+}
+
+impl<T: ?Sized + Send> Probe<T> {
+    pub fn is_send(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ?Sized + Sync> Probe<T> {
+    pub fn is_sync(&self) -> bool {
+        true
+    }
+}
+
+/// The methods used by [`Probe`] when `T` doesn't implement `Send` or `Sync`.
+///
+/// This trait must be in scope when calling the methods on [`Probe`].
+///
+/// [`Probe`]: struct.Probe.html
+pub trait Fallback {
+    fn is_send(&self) -> bool {
+        false
+    }
+
+    fn is_sync(&self) -> bool {
+        false
+    }
+}
+
+impl<T: ?Sized> Fallback for Probe<T> {}
@@ -0,0 +1,180 @@
+//! Tests for verifying that `async` functions are recorded in the exported metadata,
+//! and that their futures are run on the executor and completed through the
+//! completion callback.
+
+mod common;
+
+use common::describe;
+use cs_bindgen::{
+    abi::Abi,
+    executor::{self, RawCompletion},
+    panic::{self, Panic},
+    prelude::*,
+};
+use pretty_assertions::assert_eq;
+use std::{
+    ffi::c_void,
+    future::Future,
+    pin::Pin,
+    sync::mpsc::{self, Sender},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A future that returns `Pending` once before completing, in order to verify that
+/// the executor polls futures again once they're woken.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// A future that panics when polled, and again when it's dropped.
+struct PanicTwice;
+
+impl Future for PanicTwice {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+        panic!("Poll panicked");
+    }
+}
+
+impl Drop for PanicTwice {
+    fn drop(&mut self) {
+        panic!("Drop panicked");
+    }
+}
+
+#[cs_bindgen]
+pub async fn double(value: u32) -> u32 {
+    YieldOnce(false).await;
+    value * 2
+}
+
+#[cs_bindgen]
+pub async fn fail(message: String) -> u32 {
+    YieldOnce(false).await;
+    panic!("{}", message);
+}
+
+#[cs_bindgen]
+pub struct Greeter {
+    greeting: String,
+}
+
+#[cs_bindgen]
+impl Greeter {
+    pub fn new(greeting: String) -> Self {
+        Self { greeting }
+    }
+
+    pub async fn greet(&self, name: String) -> String {
+        YieldOnce(false).await;
+        format!("{}, {}!", self.greeting, name)
+    }
+}
+
+#[test]
+fn async_metadata() {
+    let desc = describe(__cs_bindgen_describe__double);
+    assert!(desc.contains(r#""output":"U32""#));
+    assert!(desc.contains(r#""is_async":true"#));
+
+    let desc = describe(__cs_bindgen_describe__greet__Greeter);
+    assert!(desc.contains(r#""output":"String""#));
+    assert!(desc.contains(r#""is_async":true"#));
+
+    let desc = describe(__cs_bindgen_describe__new__Greeter);
+    assert!(desc.contains(r#""is_async":false"#));
+}
+
+/// The value sent by `complete` once a call completes.
+type Completed<T> = (T, Option<Panic>);
+
+// Stand in for the completion trampoline generated in C#, using a boxed `Sender` as
+// the context. The panic is taken on the executor thread, the same as in C#.
+unsafe extern "C" fn complete<T: Abi>(context: *const c_void, result: T::Abi) {
+    let sender = Box::from_raw(context as *mut Sender<Completed<Option<T>>>);
+    let panic = panic::take_panic();
+    let result = match panic {
+        Some(_) => None,
+        None => Some(T::from_abi(result)),
+    };
+    sender.send((result, panic)).unwrap();
+}
+
+fn channel_completion<T: Abi>() -> (RawCompletion<T::Abi>, mpsc::Receiver<Completed<Option<T>>>) {
+    let (sender, receiver) = mpsc::channel();
+    let completion = RawCompletion {
+        context: Box::into_raw(Box::new(sender)) as *const c_void,
+        complete: complete::<T>,
+    };
+    (completion, receiver)
+}
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[test]
+fn future_completes() {
+    let (completion, receiver) = channel_completion::<u32>();
+    unsafe { __cs_bindgen_generated__double(21, completion) };
+    assert_eq!((Some(42), None), receiver.recv_timeout(TIMEOUT).unwrap());
+}
+
+#[test]
+fn panic_completes_with_panic() {
+    let (completion, receiver) = channel_completion::<u32>();
+    unsafe { __cs_bindgen_generated__fail("Oh no".to_string().into_abi(), completion) };
+
+    let (result, panic) = receiver.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(None, result);
+    assert_eq!("Oh no", panic.expect("Panic wasn't reported").message);
+
+    // The executor keeps running other futures after a panic.
+    let (completion, receiver) = channel_completion::<u32>();
+    unsafe { __cs_bindgen_generated__double(2, completion) };
+    assert_eq!((Some(4), None), receiver.recv_timeout(TIMEOUT).unwrap());
+}
+
+#[test]
+fn panic_while_dropping_completes_with_panic() {
+    let (completion, receiver) = channel_completion::<u32>();
+    unsafe { executor::spawn_with_completion(PanicTwice, completion) };
+
+    // The future is dropped after polling it panicked, and the call is still completed
+    // even though dropping it panicked too.
+    let (result, panic) = receiver.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(None, result);
+    assert_eq!(
+        "Drop panicked",
+        panic.expect("Panic wasn't reported").message
+    );
+}
+
+#[test]
+fn async_method_borrows_handle() {
+    let greeter = Greeter::new("Hello".into()).into_abi();
+
+    let (completion, receiver) = channel_completion::<String>();
+    unsafe {
+        __cs_bindgen_generated__greet__Greeter(greeter, "world".to_string().into_abi(), completion)
+    };
+    assert_eq!(
+        (Some("Hello, world!".to_string()), None),
+        receiver.recv_timeout(TIMEOUT).unwrap()
+    );
+
+    // The handle is only dropped once the future has completed.
+    drop(unsafe { Greeter::from_abi(greeter) });
+}
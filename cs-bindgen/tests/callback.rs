@@ -0,0 +1,98 @@
+//! Tests for verifying that callbacks are accepted as arguments, are recorded in the
+//! exported metadata, and release the C# delegate once they're dropped.
+
+mod common;
+
+use common::describe;
+use cs_bindgen::{
+    abi::{Abi, RawCallback},
+    prelude::*,
+};
+use std::{
+    cell::Cell,
+    ffi::c_void,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+#[cs_bindgen]
+pub fn apply_twice(value: i32, callback: Box<dyn Fn(i32) -> i32>) -> i32 {
+    callback(callback(value))
+}
+
+#[cs_bindgen]
+pub fn count_to(count: u32, mut visit: impl FnMut(u32)) {
+    for value in 1..=count {
+        visit(value);
+    }
+}
+
+#[cs_bindgen]
+pub fn call_fn_ptr(value: f64, callback: extern "C" fn(f64) -> f64) -> f64 {
+    callback(value)
+}
+
+#[test]
+fn callback_metadata() {
+    let desc = describe(__cs_bindgen_describe__apply_twice);
+    assert!(desc.contains(r#"{"Callback":{"inputs":["I32"],"output":"I32"}}"#));
+
+    let desc = describe(__cs_bindgen_describe__count_to);
+    assert!(desc.contains(r#"{"Callback":{"inputs":["U32"],"output":"Unit"}}"#));
+
+    let desc = describe(__cs_bindgen_describe__call_fn_ptr);
+    assert!(desc.contains(r#"{"FnPtr":{"inputs":["F64"],"output":"F64"}}"#));
+
+    let visited = Cell::new(0);
+    count_to(3, |value| visited.set(visited.get() + value));
+    assert_eq!(visited.get(), 6);
+}
+
+/// The number of times `drop_context` has been called.
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+/// Stands in for the trampoline generated in C#, adding the value pointed to by the
+/// context to the argument.
+unsafe extern "C" fn add_context(context: *const c_void, _threw: *mut u8, value: i32) -> i32 {
+    *(context as *const i32) + value
+}
+
+unsafe extern "C" fn drop_context(_: *const c_void) {
+    DROPPED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn boxed_callback_from_raw() {
+    let offset = 5_i32;
+    let raw = RawCallback {
+        context: &offset as *const i32 as *const c_void,
+        invoke: add_context as *const c_void,
+        drop: Some(drop_context),
+    };
+
+    let callback = unsafe { <Box<dyn Fn(i32) -> i32>>::from_abi(raw) };
+    assert_eq!(apply_twice(1, callback), 11);
+
+    // The context is released when the callback is dropped.
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+}
+
+extern "C" fn halve(value: f64) -> f64 {
+    value / 2.0
+}
+
+#[test]
+fn fn_ptr_from_raw() {
+    let callback = unsafe { <extern "C" fn(f64) -> f64>::from_abi(halve) };
+    assert_eq!(call_fn_ptr(3.0, callback), 1.5);
+}
+
+#[test]
+fn zeroed_callback_is_valid() {
+    // Raw values must be valid when zeroed (see `catch_panic`), so dropping a zeroed
+    // callback must not call through a null `drop` pointer.
+    let raw: RawCallback = unsafe { std::mem::zeroed() };
+    assert!(raw.drop.is_none());
+
+    let callback = unsafe { <Box<dyn Fn(i32) -> i32>>::from_abi(raw) };
+    drop(callback);
+}
@@ -0,0 +1,47 @@
+use cs_bindgen::{abi::Abi, prelude::*};
+use pretty_assertions::assert_eq;
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructWithChar {
+    pub value: char,
+}
+
+#[cs_bindgen]
+pub fn to_uppercase(value: char) -> char {
+    value.to_ascii_uppercase()
+}
+
+#[test]
+fn char_round_trip() {
+    for &original in &['a', '\0', 'ß', '\u{FFFF}', '🦀', char::MAX] {
+        let result: char = unsafe { Abi::from_abi(original.into_abi()) };
+        assert_eq!(original, result);
+    }
+}
+
+#[test]
+fn char_vec_round_trip() {
+    let original = vec!['f', 'o', 'o', '🦀'];
+    let result: Vec<char> = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn struct_round_trip() {
+    let original = StructWithChar { value: '🔪' };
+    let result: StructWithChar = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+#[should_panic(expected = "Invalid char value received from C#: 0xd800")]
+fn surrogate_is_rejected() {
+    let _: char = unsafe { Abi::from_abi(0xD800) };
+}
+
+#[test]
+#[should_panic(expected = "Invalid char value received from C#: 0x110000")]
+fn out_of_range_is_rejected() {
+    let _: char = unsafe { Abi::from_abi(0x11_0000) };
+}
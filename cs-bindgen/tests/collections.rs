@@ -1,5 +1,10 @@
-use cs_bindgen::{abi::Abi, prelude::*};
+use cs_bindgen::{
+    abi::{Abi, RawSlice},
+    exports,
+    prelude::*,
+};
 use pretty_assertions::assert_eq;
+use std::collections::{BTreeMap, HashMap};
 
 #[cs_bindgen]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -10,11 +15,14 @@ pub struct StructWithArrays {
 
 #[cs_bindgen]
 impl StructWithArrays {
-    // TODO: Add a method that returns the vec field as a slice, once slices are supported.
     pub fn get_vec(&self) -> Vec<u32> {
         self.vec_field.clone()
     }
 
+    pub fn get_slice(&self) -> &[u32] {
+        &self.vec_field
+    }
+
     pub fn get_array(&self) -> [i32; 4] {
         self.array_field
     }
@@ -30,6 +38,12 @@ fn struct_round_trip() {
     assert_eq!(original, result);
 }
 
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix {
+    pub elements: [f32; 16],
+}
+
 #[test]
 fn int_vec_round_trip() {
     let original: Vec<u32> = vec![1, 2, 3, 4, 5, 6];
@@ -37,6 +51,30 @@ fn int_vec_round_trip() {
     assert_eq!(original, result);
 }
 
+#[test]
+fn slice_round_trip() {
+    let original: Vec<u32> = vec![1, 2, 3, 4, 5, 6];
+    let result: &[u32] = unsafe { Abi::from_abi(original.as_slice().into_abi()) };
+    assert_eq!(original.as_slice(), result);
+
+    // The elements are borrowed rather than copied.
+    assert_eq!(original.as_ptr(), result.as_ptr());
+}
+
+#[test]
+fn struct_slice_round_trip() {
+    let original = vec![
+        Matrix {
+            elements: [1.0; 16],
+        },
+        Matrix {
+            elements: [2.0; 16],
+        },
+    ];
+    let result: &[Matrix] = unsafe { Abi::from_abi(original.as_slice().into_abi()) };
+    assert_eq!(original.as_slice(), result);
+}
+
 #[test]
 fn str_vec_round_trip() {
     let original: Vec<&'static str> = vec!["foo", "bar", "baz"];
@@ -51,6 +89,21 @@ fn string_vec_round_trip() {
     assert_eq!(original, result);
 }
 
+// The elements of a `Vec<String>` are borrowed by the C# code while it converts the
+// list, so they're indexed as slices into the strings still owned by the vec.
+#[test]
+fn index_string_vec_borrows_element() {
+    let strings: Vec<String> = vec!["foo".into(), "bar".into()];
+    let raw = RawSlice {
+        ptr: strings.as_ptr(),
+        len: strings.len(),
+    };
+
+    let element = unsafe { exports::__cs_bindgen_index_vec_string(raw, 1) };
+    assert_eq!(strings[1].as_ptr(), element.ptr);
+    assert_eq!("bar", unsafe { element.as_str_unchecked() });
+}
+
 #[test]
 fn int_array_round_trip() {
     let original: [u32; 4] = [1, 2, 3, 4];
@@ -58,6 +111,37 @@ fn int_array_round_trip() {
     assert_eq!(original, result);
 }
 
+#[test]
+fn large_array_round_trip() {
+    let mut original = [0_u16; 100];
+    for (index, element) in original.iter_mut().enumerate() {
+        *element = index as u16;
+    }
+
+    let result: [u16; 100] = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(&original[..], &result[..]);
+}
+
+#[test]
+fn empty_array_round_trip() {
+    let original: [String; 0] = [];
+    let result: [String; 0] = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn matrix_round_trip() {
+    let mut original = Matrix {
+        elements: [0.0; 16],
+    };
+    for index in 0..4 {
+        original.elements[index * 5] = 1.0;
+    }
+
+    let result: Matrix = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
+
 #[test]
 fn str_array_round_trip() {
     let original: [&'static str; 3] = ["foo", "bar", "baz"];
@@ -71,3 +155,55 @@ fn string_array_round_trip() {
     let result: [String; 3] = unsafe { Abi::from_abi(original.clone().into_abi()) };
     assert_eq!(original, result);
 }
+
+#[cs_bindgen]
+pub fn invert_map(map: HashMap<String, u32>) -> BTreeMap<u32, String> {
+    map.into_iter().map(|(key, value)| (value, key)).collect()
+}
+
+#[test]
+fn hash_map_round_trip() {
+    let mut original = HashMap::new();
+    original.insert(String::from("foo"), 1_u32);
+    original.insert(String::from("bar"), 2);
+    original.insert(String::from("baz"), 3);
+
+    let result: HashMap<String, u32> = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn btree_map_round_trip() {
+    let mut original = BTreeMap::new();
+    original.insert(1_i32, String::from("foo"));
+    original.insert(2, String::from("bar"));
+
+    let result: BTreeMap<i32, String> = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn empty_map_round_trip() {
+    let original: HashMap<u8, bool> = HashMap::new();
+    let result: HashMap<u8, bool> = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn mut_slice_writes_through() {
+    let mut original = vec![1, 2, 3];
+    let raw = original.as_mut_slice().into_abi();
+    let result: &mut [i32] = unsafe { Abi::from_abi(raw) };
+    result[1] = 20;
+    assert_eq!(vec![1, 20, 3], original);
+}
+
+#[test]
+fn empty_slice_from_null() {
+    let raw = RawSlice::<i32> {
+        ptr: std::ptr::null(),
+        len: 0,
+    };
+    let result: &[i32] = unsafe { Abi::from_abi(raw) };
+    assert!(result.is_empty());
+}
@@ -0,0 +1,10 @@
+//! Helpers shared by the integration tests.
+
+use cs_bindgen::abi::RawString;
+
+/// Calls a generated describe function and returns the JSON description of the
+/// exported item.
+pub fn describe(describe_fn: unsafe extern "C" fn() -> Box<RawString>) -> String {
+    let raw = unsafe { describe_fn() };
+    String::from_utf8(unsafe { raw.into_vec() }).unwrap()
+}
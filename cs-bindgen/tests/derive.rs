@@ -49,9 +49,20 @@ impl StructWithMethods {
         Self { foo }
     }
 
+    #[cs_bindgen(getter)]
     pub fn foo(&self) -> String {
         self.foo.clone()
     }
+
+    #[cs_bindgen(getter)]
+    pub fn foo_len(&self) -> u32 {
+        self.foo.len() as u32
+    }
+
+    #[cs_bindgen(setter)]
+    pub fn set_foo(&mut self, value: String) {
+        self.foo = value;
+    }
 }
 
 #[cs_bindgen]
@@ -66,7 +77,72 @@ impl AnotherStructWithMethods {
         Self { foo }
     }
 
+    #[cs_bindgen(constructor)]
+    pub fn from_len(len: u32) -> Self {
+        Self {
+            foo: "a".repeat(len as usize),
+        }
+    }
+
+    // A constructor may name the self type with a path instead of `Self`.
+    #[cs_bindgen(constructor)]
+    pub fn repeated(text: String, count: u32) -> crate::AnotherStructWithMethods {
+        Self {
+            foo: text.repeat(count as usize),
+        }
+    }
+
+    #[cs_bindgen(factory)]
+    pub fn empty() -> Self {
+        Self { foo: String::new() }
+    }
+
+    pub fn same_foo(&self, other: &Self) -> Option<Self> {
+        if self.foo == other.foo {
+            Some(self.clone())
+        } else {
+            None
+        }
+    }
+
     pub fn foo(&self) -> String {
         self.foo.clone()
     }
 }
+
+#[cs_bindgen]
+impl CopyTupleStruct {
+    pub fn first(&self) -> u32 {
+        self.0
+    }
+
+    pub fn set_first(&mut self, value: u32) {
+        self.0 = value;
+    }
+
+    pub fn into_flag(self) -> bool {
+        self.2
+    }
+}
+
+#[cs_bindgen]
+impl CLikeEnumCopy {
+    pub fn is_foo(&self) -> bool {
+        matches!(self, CLikeEnumCopy::Foo)
+    }
+
+    pub fn reset(&mut self) {
+        *self = CLikeEnumCopy::Foo;
+    }
+}
+
+#[cs_bindgen]
+impl DataEnum {
+    pub fn bar(value: u32) -> DataEnum {
+        DataEnum::Bar(value)
+    }
+
+    pub fn clear(&mut self) {
+        *self = DataEnum::Foo;
+    }
+}
@@ -0,0 +1,120 @@
+//! Tests for verifying that doc comments are recorded in the exported metadata.
+
+mod common;
+
+use common::describe;
+use cs_bindgen::prelude::*;
+
+/// A point in 2D space.
+///
+/// Points are copied when passed to C#.
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    /// The horizontal position.
+    pub x: i32,
+    pub y: i32,
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub enum Shape {
+    /// A single point.
+    Dot,
+
+    Line {
+        /// Where the line starts.
+        start: Point,
+        end: Point,
+    },
+}
+
+#[cs_bindgen]
+pub struct Counter {
+    count: u32,
+}
+
+#[cs_bindgen]
+impl Counter {
+    /// Increments the counter.
+    ///
+    /// # Returns
+    ///
+    /// The new value of the counter.
+    pub fn increment(
+        &mut self,
+        /// The amount to add.
+        amount: u32,
+    ) -> u32 {
+        self.count += amount;
+        self.count
+    }
+}
+
+/// Adds two numbers.
+///
+/// # Returns
+///
+/// The sum of `left` and `right`.
+///
+/// # Panics
+///
+/// Panics if the sum overflows.
+#[cs_bindgen]
+pub fn add(
+    /// The first number.
+    left: i32,
+    right: i32,
+) -> i32 {
+    left + right
+}
+
+#[cs_bindgen]
+#[doc(hidden)]
+pub fn undocumented() {}
+
+#[test]
+fn fn_docs() {
+    let desc = describe(__cs_bindgen_describe__add);
+    assert!(
+        desc.contains(r#""docs":"Adds two numbers.\n\n# Panics\n\nPanics if the sum overflows.""#)
+    );
+    assert!(desc.contains(r#""return_docs":"The sum of `left` and `right`.""#));
+    assert!(desc.contains(r#""docs":"The first number.""#));
+    assert!(desc.contains(r#""docs":null"#));
+
+    assert_eq!(add(1, 2), 3);
+}
+
+#[test]
+fn method_docs() {
+    let desc = describe(__cs_bindgen_describe__increment__Counter);
+    assert!(desc.contains(r#""docs":"Increments the counter.""#));
+    assert!(desc.contains(r#""return_docs":"The new value of the counter.""#));
+    assert!(desc.contains(r#""docs":"The amount to add.""#));
+
+    let mut counter = Counter { count: 0 };
+    assert_eq!(counter.increment(2), 2);
+}
+
+#[test]
+fn type_docs() {
+    let desc = describe(__cs_bindgen_describe__Point);
+    assert!(
+        desc.contains(r#""docs":"A point in 2D space.\n\nPoints are copied when passed to C#.""#)
+    );
+    assert!(desc.contains(r#""field_docs":["The horizontal position.",null]"#));
+
+    let desc = describe(__cs_bindgen_describe__Shape);
+    assert!(desc.contains(r#""docs":null"#));
+    assert!(desc.contains(
+        r#""variant_docs":[{"docs":"A single point.","field_docs":[]},{"docs":null,"field_docs":["Where the line starts.",null]}]"#
+    ));
+}
+
+#[test]
+fn hidden_docs_are_ignored() {
+    let desc = describe(__cs_bindgen_describe__undocumented);
+    assert!(desc.contains(r#""docs":null"#));
+    assert!(desc.contains(r#""return_docs":null"#));
+}
@@ -0,0 +1,187 @@
+//! Tests for verifying that functions returning iterators are recorded in the exported
+//! metadata, and that the returned iterators can be advanced and dropped through the
+//! generated bindings.
+
+mod common;
+
+use common::describe;
+use cs_bindgen::{abi::Abi, panic, prelude::*};
+use pretty_assertions::assert_eq;
+use std::{
+    mem::MaybeUninit,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cs_bindgen]
+pub fn count_to(max: u32) -> impl Iterator<Item = u32> {
+    1..=max
+}
+
+#[cs_bindgen]
+pub fn fail_after(count: u32) -> impl Iterator<Item = u32> {
+    (0..).inspect(move |&index| {
+        if index == count {
+            panic!("Failed after {}", count);
+        }
+    })
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// An iterator that records when it's dropped.
+pub struct Tracked(Range<u32>);
+
+impl Iterator for Tracked {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.0.next()
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cs_bindgen(iterator)]
+pub fn tracked(max: u32) -> Tracked {
+    Tracked(0..max)
+}
+
+#[cs_bindgen]
+pub struct Words {
+    words: Vec<String>,
+}
+
+#[cs_bindgen]
+impl Words {
+    pub fn new(text: String) -> Self {
+        Self {
+            words: text.split_whitespace().map(Into::into).collect(),
+        }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = String> {
+        self.words.clone().into_iter()
+    }
+
+    #[cs_bindgen(iterator)]
+    pub fn lengths(&self) -> std::vec::IntoIter<u32> {
+        self.words
+            .iter()
+            .map(|word| word.len() as u32)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+// Stand in for `MoveNext` in the generated C# enumerator, advancing the iterator until
+// it's exhausted.
+unsafe fn collect<H: Copy, T: Abi>(
+    handle: H,
+    next: unsafe extern "C" fn(H, *mut T::Abi) -> u8,
+) -> Vec<T> {
+    let mut items = Vec::new();
+    let mut item = MaybeUninit::uninit();
+    while next(handle, item.as_mut_ptr()) != 0 {
+        items.push(T::from_abi(item.as_ptr().read()));
+    }
+    items
+}
+
+#[test]
+fn iterator_metadata() {
+    let desc = describe(__cs_bindgen_describe__count_to);
+    assert!(desc.contains(r#""Iterator""#));
+    assert!(desc.contains(r#""item":"U32""#));
+
+    let desc = describe(__cs_bindgen_describe__words__Words);
+    assert!(desc.contains(r#""Iterator""#));
+    assert!(desc.contains(r#""item":"String""#));
+}
+
+#[test]
+fn iterate_to_end() {
+    unsafe {
+        let iter = __cs_bindgen_generated__count_to(3);
+        let items = collect::<_, u32>(
+            iter,
+            __cs_bindgen_next____cs_bindgen_generated__count_to__Iter,
+        );
+        assert_eq!(vec![1, 2, 3], items);
+
+        // Advancing an exhausted iterator keeps returning `false`.
+        let mut item = 0;
+        assert_eq!(
+            0,
+            __cs_bindgen_next____cs_bindgen_generated__count_to__Iter(iter, &mut item)
+        );
+
+        __cs_bindgen_drop____cs_bindgen_generated__count_to__Iter(iter);
+    }
+    assert_eq!(None, panic::take_panic());
+}
+
+#[test]
+fn iterate_method_results() {
+    let words = Words::new("a quick fox".into()).into_abi();
+    unsafe {
+        let iter = __cs_bindgen_generated__words__Words(words);
+        let items = collect::<_, String>(
+            iter,
+            __cs_bindgen_next____cs_bindgen_generated__words__Words__Iter,
+        );
+        assert_eq!(vec!["a", "quick", "fox"], items);
+        __cs_bindgen_drop____cs_bindgen_generated__words__Words__Iter(iter);
+
+        let iter = __cs_bindgen_generated__lengths__Words(words);
+
+        // The iterator owns its data, so it outlives the handle it was returned from.
+        drop(Words::from_abi(words));
+
+        let items = collect::<_, u32>(
+            iter,
+            __cs_bindgen_next____cs_bindgen_generated__lengths__Words__Iter,
+        );
+        assert_eq!(vec![1, 5, 3], items);
+        __cs_bindgen_drop____cs_bindgen_generated__lengths__Words__Iter(iter);
+    }
+}
+
+#[test]
+fn drop_before_exhausted() {
+    unsafe {
+        let iter = __cs_bindgen_generated__tracked(10);
+
+        let mut item = 0;
+        assert_eq!(
+            1,
+            __cs_bindgen_next____cs_bindgen_generated__tracked__Iter(iter, &mut item)
+        );
+        assert_eq!(0, item);
+        assert_eq!(0, DROPPED.load(Ordering::SeqCst));
+
+        __cs_bindgen_drop____cs_bindgen_generated__tracked__Iter(iter);
+    }
+    assert_eq!(1, DROPPED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn panic_in_next() {
+    unsafe {
+        let iter = __cs_bindgen_generated__fail_after(2);
+        let items = collect::<_, u32>(
+            iter,
+            __cs_bindgen_next____cs_bindgen_generated__fail_after__Iter,
+        );
+        assert_eq!(vec![0, 1], items);
+
+        let panic = panic::take_panic().expect("Panic wasn't stored");
+        assert_eq!("Failed after 2", panic.message);
+
+        __cs_bindgen_drop____cs_bindgen_generated__fail_after__Iter(iter);
+    }
+}
@@ -9,12 +9,16 @@ use cs_bindgen::{abi::*, shared::*};
 // * The binding function, which is exported as `extern "C"` and handles the
 //   boilerplate work of converting two and from ABI-compatible types.
 // * The describe function, which
+//
+// Both functions are exported with a symbol name that includes the module path, so
+// that functions with the same name in different modules don't conflict.
 
 pub fn example_fn(first: u32, second: String) -> String {
     format!("first: {}, second: {}", first, second)
 }
 
-#[no_mangle]
+#[export_name = concat!("__cs_bindgen_generated__", module_path!(), "::", "example_fn")]
+#[allow(bad_style)]
 pub unsafe extern "C" fn __cs_bindgen_generated__example_fn(
     first: <u32 as Abi>::Abi,
     second: <String as Abi>::Abi,
@@ -24,16 +28,28 @@ pub unsafe extern "C" fn __cs_bindgen_generated__example_fn(
     example_fn(first, second).into_abi()
 }
 
-#[no_mangle]
+#[export_name = concat!("__cs_bindgen_describe__", module_path!(), "::", "example_fn")]
+#[allow(bad_style)]
 pub unsafe extern "C" fn __cs_bindgen_describe__example_fn() -> Box<RawVec<u8>> {
     let export = Func {
         name: "example_fn".into(),
-        binding: "__cs_bindgen_generated__example_fn".into(),
+        binding: concat!(
+            "__cs_bindgen_generated__",
+            module_path!(),
+            "::",
+            "example_fn"
+        )
+        .into(),
         inputs: vec![
             FnArg::new("first", u32::repr()),
             FnArg::new("second", String::repr()),
         ],
         output: Some(String::repr()),
+        try_pattern: false,
+        is_async: false,
+        rename: None,
+        docs: None,
+        return_docs: None,
     };
 
     Box::new(serialize_export(export).into())
@@ -0,0 +1,147 @@
+//! Tests for verifying that exported items with the same name in different modules
+//! don't conflict with each other.
+//!
+//! The symbols for all exported functions must be unique, so the generated symbol
+//! names include the module path of the exported item. If the symbols aren't mangled
+//! correctly then this test will fail to build.
+
+mod common;
+
+use common::describe;
+
+mod first {
+    use cs_bindgen::prelude::*;
+
+    #[cs_bindgen]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    #[cs_bindgen]
+    pub struct Counter {
+        count: u32,
+    }
+
+    #[cs_bindgen]
+    impl Counter {
+        pub fn increment(&mut self) -> u32 {
+            self.count += 1;
+            self.count
+        }
+    }
+
+    #[cs_bindgen]
+    pub fn make_point() -> Point {
+        Point { x: 1, y: 2 }
+    }
+}
+
+mod second {
+    use cs_bindgen::prelude::*;
+
+    #[cs_bindgen]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Point {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    #[cs_bindgen]
+    pub struct Counter {
+        count: u64,
+    }
+
+    #[cs_bindgen]
+    impl Counter {
+        pub fn increment(&mut self) -> u64 {
+            self.count += 2;
+            self.count
+        }
+    }
+
+    #[cs_bindgen]
+    pub fn make_point() -> Point {
+        Point { x: 1.0, y: 2.0 }
+    }
+}
+
+mod traits {
+    use cs_bindgen::prelude::*;
+
+    pub mod audio {
+        use cs_bindgen::prelude::*;
+
+        #[cs_bindgen]
+        pub trait Source {
+            fn level(&self) -> u32;
+        }
+    }
+
+    pub mod video {
+        use cs_bindgen::prelude::*;
+
+        #[cs_bindgen]
+        pub trait Source {
+            fn level(&self) -> u32;
+        }
+    }
+
+    #[cs_bindgen]
+    pub struct Player;
+
+    #[cs_bindgen]
+    impl audio::Source for Player {
+        fn level(&self) -> u32 {
+            1
+        }
+    }
+
+    #[cs_bindgen]
+    impl video::Source for Player {
+        fn level(&self) -> u32 {
+            2
+        }
+    }
+}
+
+#[test]
+fn binding_names_include_module_path() {
+    let first = describe(first::__cs_bindgen_describe__make_point);
+    let second = describe(second::__cs_bindgen_describe__make_point);
+
+    assert!(first.contains(r#""__cs_bindgen_generated__name_collision::first::make_point""#));
+    assert!(second.contains(r#""__cs_bindgen_generated__name_collision::second::make_point""#));
+}
+
+#[test]
+fn method_binding_names_include_module_path() {
+    let first = describe(first::__cs_bindgen_describe__increment__Counter);
+    let second = describe(second::__cs_bindgen_describe__increment__Counter);
+
+    assert!(
+        first.contains(r#""__cs_bindgen_generated__name_collision::first::increment__Counter""#)
+    );
+    assert!(
+        second.contains(r#""__cs_bindgen_generated__name_collision::second::increment__Counter""#)
+    );
+}
+
+#[test]
+fn named_type_fn_names_include_module_path() {
+    let first = describe(first::__cs_bindgen_describe__Point);
+    let second = describe(second::__cs_bindgen_describe__Point);
+
+    assert!(first.contains(r#""__cs_bindgen_generated_index_name_collision::first::Point""#));
+    assert!(second.contains(r#""__cs_bindgen_generated_index_name_collision::second::Point""#));
+}
+
+#[test]
+fn trait_impl_binding_names_include_trait_path() {
+    let audio = describe(traits::__cs_bindgen_describe__level__Player__audio__Source);
+    let video = describe(traits::__cs_bindgen_describe__level__Player__video__Source);
+
+    assert!(audio.contains(r#""module":"name_collision::traits::audio""#));
+    assert!(video.contains(r#""module":"name_collision::traits::video""#));
+}
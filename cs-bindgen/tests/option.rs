@@ -0,0 +1,45 @@
+use cs_bindgen::{abi::Abi, prelude::*};
+use pretty_assertions::assert_eq;
+
+#[cs_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructWithOptions {
+    pub int_field: Option<u32>,
+    pub string_field: Option<String>,
+}
+
+#[cs_bindgen]
+pub fn maybe_string(value: Option<String>) -> Option<String> {
+    value
+}
+
+#[test]
+fn int_some_round_trip() {
+    let original = Some(123_u32);
+    let result: Option<u32> = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn int_none_round_trip() {
+    let original: Option<u32> = None;
+    let result: Option<u32> = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn string_round_trip() {
+    let original = Some(String::from("Cool string"));
+    let result: Option<String> = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn struct_round_trip() {
+    let original = StructWithOptions {
+        int_field: Some(7),
+        string_field: None,
+    };
+    let result: StructWithOptions = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
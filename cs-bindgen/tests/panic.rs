@@ -0,0 +1,30 @@
+use cs_bindgen::panic::{self, Panic};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn no_panic() {
+    let result = unsafe { panic::catch_panic(|| 7_i32) };
+    assert_eq!(7, result);
+    assert_eq!(None, panic::take_panic());
+}
+
+#[test]
+fn panic_is_caught() {
+    let result = unsafe { panic::catch_panic(|| -> i32 { panic!("Oh no: {}", 12) }) };
+    assert_eq!(0, result);
+
+    let Panic { message, location } = panic::take_panic().expect("Panic wasn't stored");
+    assert_eq!("Oh no: 12", message);
+    assert!(location.unwrap().starts_with(file!()));
+
+    // The panic is cleared once it has been taken.
+    assert_eq!(None, panic::take_panic());
+}
+
+#[test]
+fn non_string_payload() {
+    unsafe { panic::catch_panic(|| -> i32 { std::panic::panic_any(5_u32) }) };
+
+    let panic = panic::take_panic().expect("Panic wasn't stored");
+    assert_eq!("Rust code panicked with a non-string payload", panic.message);
+}
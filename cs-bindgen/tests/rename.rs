@@ -0,0 +1,112 @@
+//! Tests for verifying that `#[cs_bindgen(rename = "...")]` attributes are accepted on
+//! all supported items and are recorded in the exported metadata.
+
+mod common;
+
+use common::describe;
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen(rename = "Vector")]
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    #[cs_bindgen(rename = "Horizontal")]
+    pub x: i32,
+    pub y: i32,
+}
+
+#[cs_bindgen(rename = "Tuple")]
+#[derive(Debug, Clone, Copy)]
+pub struct TupleStruct(#[cs_bindgen(rename = "First")] u32, u8);
+
+#[cs_bindgen(rename = "Direction")]
+#[derive(Debug, Clone, Copy)]
+pub enum CLikeEnum {
+    #[cs_bindgen(rename = "Up")]
+    North,
+    South,
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub enum DataEnum {
+    #[cs_bindgen(rename = "Single")]
+    One(#[cs_bindgen(rename = "Value")] u32),
+    Named {
+        #[cs_bindgen(rename = "Left")]
+        first: u32,
+        second: u8,
+    },
+}
+
+#[cs_bindgen(rename = "Accumulator")]
+pub struct Counter {
+    count: u32,
+}
+
+#[cs_bindgen]
+impl Counter {
+    #[cs_bindgen(rename = "Bump")]
+    pub fn increment(&mut self, #[cs_bindgen(rename = "step")] amount: u32) -> u32 {
+        self.count += amount;
+        self.count
+    }
+
+    #[cs_bindgen(getter, rename = "Total")]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+#[cs_bindgen(rename = "AddNumbers")]
+pub fn add(#[cs_bindgen(rename = "lhs")] left: i32, right: i32) -> i32 {
+    left + right
+}
+
+#[test]
+fn renamed_fn() {
+    let desc = describe(__cs_bindgen_describe__add);
+    assert!(desc.contains(r#""rename":"AddNumbers""#));
+    assert!(desc.contains(r#""name":"left","#));
+    assert!(desc.contains(r#""rename":"lhs""#));
+    assert!(desc.contains(r#""rename":null"#));
+
+    assert_eq!(add(1, 2), 3);
+}
+
+#[test]
+fn renamed_method() {
+    let desc = describe(__cs_bindgen_describe__increment__Counter);
+    assert!(desc.contains(r#""rename":"Bump""#));
+    assert!(desc.contains(r#""rename":"step""#));
+
+    let desc = describe(__cs_bindgen_describe__count__Counter);
+    assert!(desc.contains(r#""rename":"Total""#));
+
+    let mut counter = Counter { count: 0 };
+    assert_eq!(counter.increment(2), 2);
+    assert_eq!(counter.count(), 2);
+}
+
+#[test]
+fn renamed_struct_fields() {
+    let desc = describe(__cs_bindgen_describe__Point);
+    assert!(desc.contains(r#""rename":"Vector""#));
+    assert!(desc.contains(r#""field_renames":["Horizontal",null]"#));
+
+    let desc = describe(__cs_bindgen_describe__TupleStruct);
+    assert!(desc.contains(r#""rename":"Tuple""#));
+    assert!(desc.contains(r#""field_renames":["First",null]"#));
+}
+
+#[test]
+fn renamed_variants() {
+    let desc = describe(__cs_bindgen_describe__CLikeEnum);
+    assert!(desc.contains(r#""rename":"Direction""#));
+    assert!(desc.contains(
+        r#""variant_renames":[{"rename":"Up","field_renames":[]},{"rename":null,"field_renames":[]}]"#
+    ));
+
+    let desc = describe(__cs_bindgen_describe__DataEnum);
+    assert!(desc.contains(r#"{"rename":"Single","field_renames":["Value"]}"#));
+    assert!(desc.contains(r#"{"rename":null,"field_renames":["Left",null]}"#));
+}
@@ -0,0 +1,60 @@
+use cs_bindgen::{abi::Abi, prelude::*};
+use pretty_assertions::assert_eq;
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    NotFound,
+    PermissionDenied,
+}
+
+#[cs_bindgen]
+pub fn checked_div(a: i32, b: i32) -> Result<i32, String> {
+    a.checked_div(b).ok_or_else(|| String::from("Division by zero"))
+}
+
+#[cs_bindgen]
+pub fn try_open(path: String) -> Result<(), ErrorKind> {
+    if path.is_empty() {
+        Err(ErrorKind::NotFound)
+    } else {
+        Ok(())
+    }
+}
+
+#[cs_bindgen(try_pattern)]
+pub fn try_checked_div(a: i32, b: i32) -> Result<i32, String> {
+    checked_div(a, b)
+}
+
+#[cs_bindgen]
+pub struct Parser;
+
+#[cs_bindgen]
+impl Parser {
+    #[cs_bindgen(try_pattern)]
+    pub fn parse(&self, value: String) -> Result<u32, String> {
+        value.parse().map_err(|_| value)
+    }
+}
+
+#[test]
+fn ok_round_trip() {
+    let original: Result<u32, String> = Ok(123);
+    let result: Result<u32, String> = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn err_round_trip() {
+    let original: Result<u32, String> = Err(String::from("Something went wrong"));
+    let result: Result<u32, String> = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn unit_round_trip() {
+    let original: Result<(), ErrorKind> = Err(ErrorKind::PermissionDenied);
+    let result: Result<(), ErrorKind> = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
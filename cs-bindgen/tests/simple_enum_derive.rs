@@ -11,7 +11,10 @@
 //! with each variant, i.e. pass the result of `into_abi` back through
 //! `from_abi` and then verify that the result matches the original.
 
-use cs_bindgen::{abi::Abi, prelude::*};
+use cs_bindgen::{
+    abi::{Abi, AbiReceiver},
+    prelude::*,
+};
 use strum::{EnumIter, IntoEnumIterator};
 
 #[test]
@@ -77,3 +80,20 @@ fn simple_enum_explicit_first_discriminant() {
         assert_eq!(variant, result);
     }
 }
+
+#[test]
+fn simple_enum_receiver_write_back() {
+    #[cs_bindgen]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Toggle {
+        Off,
+        On,
+    }
+
+    let mut abi = Toggle::Off.into_abi();
+    unsafe {
+        Toggle::with_mut(&mut abi, |toggle| *toggle = Toggle::On);
+        assert_eq!(Toggle::On, Toggle::from_abi(abi));
+        assert!(Toggle::with_ref(abi, |toggle| *toggle == Toggle::On));
+    }
+}
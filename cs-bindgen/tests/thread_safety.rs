@@ -0,0 +1,64 @@
+//! Tests for verifying that whether an exported type is `Send` and `Sync` is recorded
+//! in the exported metadata.
+
+mod common;
+
+use common::describe;
+use cs_bindgen::prelude::*;
+use std::{cell::Cell, rc::Rc};
+
+// The fields are only here to determine whether the types are `Send` and `Sync`.
+#[cs_bindgen]
+#[allow(dead_code)]
+pub struct Shared {
+    value: u32,
+}
+
+#[cs_bindgen]
+#[allow(dead_code)]
+pub struct Unsynced {
+    value: Cell<u32>,
+}
+
+#[cs_bindgen]
+pub struct Local {
+    value: Rc<u32>,
+}
+
+#[cs_bindgen]
+impl Local {
+    pub fn value(&self) -> u32 {
+        *self.value
+    }
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[test]
+fn send_and_sync() {
+    let desc = describe(__cs_bindgen_describe__Shared);
+    assert!(desc.contains(r#""is_send":true,"is_sync":true"#));
+
+    let desc = describe(__cs_bindgen_describe__Point);
+    assert!(desc.contains(r#""is_send":true,"is_sync":true"#));
+}
+
+#[test]
+fn send_but_not_sync() {
+    let desc = describe(__cs_bindgen_describe__Unsynced);
+    assert!(desc.contains(r#""is_send":true,"is_sync":false"#));
+}
+
+#[test]
+fn neither_send_nor_sync() {
+    let desc = describe(__cs_bindgen_describe__Local);
+    assert!(desc.contains(r#""is_send":false,"is_sync":false"#));
+
+    let local = Local { value: Rc::new(7) };
+    assert_eq!(local.value(), 7);
+}
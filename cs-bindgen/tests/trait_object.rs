@@ -0,0 +1,167 @@
+//! Tests for verifying that traits can be exported, are recorded in the exported
+//! metadata, and that trait objects passed in from C# call through their vtable.
+//! Also verifies that trait impls for exported types record the implemented trait.
+
+mod common;
+
+use common::describe;
+use cs_bindgen::{
+    abi::{Abi, RawString, RawTraitObject},
+    prelude::*,
+};
+use std::{
+    cell::Cell,
+    ffi::c_void,
+    panic,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// Keeps a running total.
+#[cs_bindgen(rename = "ITally")]
+pub trait Accumulator {
+    /// Adds `value` to the total.
+    ///
+    /// # Returns
+    ///
+    /// The new total.
+    fn add(&self, value: u32) -> u32;
+
+    #[cs_bindgen(rename = "Clear")]
+    fn reset(&mut self, #[cs_bindgen(rename = "reason")] _: String);
+}
+
+#[cs_bindgen]
+pub fn add_all(accumulator: Box<dyn Accumulator>, values: Vec<u32>) -> u32 {
+    values
+        .into_iter()
+        .fold(0, |_, value| accumulator.add(value))
+}
+
+#[cs_bindgen]
+pub fn reset(mut accumulator: Box<dyn Accumulator + Send + Sync>) {
+    accumulator.reset("done".into());
+}
+
+/// Keeps a running total in a handle owned by C#.
+#[cs_bindgen]
+#[derive(Default)]
+pub struct Total {
+    total: Cell<u32>,
+}
+
+#[cs_bindgen]
+impl Total {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[cs_bindgen]
+impl Accumulator for Total {
+    fn add(&self, value: u32) -> u32 {
+        self.total.set(self.total.get() + value);
+        self.total.get()
+    }
+
+    fn reset(&mut self, _: String) {
+        self.total.set(0);
+    }
+}
+
+#[test]
+fn trait_metadata() {
+    let desc = describe(__cs_bindgen_describe__Accumulator);
+    assert!(desc.contains(r#""rename":"ITally""#));
+    assert!(desc.contains(r#""docs":"Keeps a running total.""#));
+    assert!(desc.contains(r#"{"name":"add","inputs":[{"name":"value","repr":"U32""#));
+    assert!(desc.contains(r#""output":"U32""#));
+    assert!(desc.contains(r#""return_docs":"The new total.""#));
+    assert!(desc.contains(r#""name":"reset""#));
+    assert!(desc.contains(r#""rename":"Clear""#));
+    assert!(desc.contains(r#"{"name":"__arg0","repr":"String","rename":"reason""#));
+
+    let desc = describe(__cs_bindgen_describe__add_all);
+    assert!(desc.contains(r#"{"TraitObject":{"name":"Accumulator""#));
+
+    let desc = describe(__cs_bindgen_describe__reset);
+    assert!(desc.contains(r#"{"TraitObject":{"name":"Accumulator""#));
+}
+
+#[test]
+fn trait_impl_metadata() {
+    let desc = describe(__cs_bindgen_describe__add__Total__Accumulator);
+    assert!(desc.contains(r#""name":"add""#));
+    assert!(desc.contains(r#""self_type":{"name":"Total""#));
+    assert!(desc.contains(r#""trait_name":{"name":"Accumulator""#));
+
+    let desc = describe(__cs_bindgen_describe__reset__Total__Accumulator);
+    assert!(desc.contains(r#""trait_name":{"name":"Accumulator""#));
+
+    // Inherent methods don't record a trait.
+    let desc = describe(__cs_bindgen_describe__new__Total);
+    assert!(desc.contains(r#""trait_name":null"#));
+}
+
+/// The number of times `drop_context` has been called.
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+// Stand in for the trampolines generated in C#, using a `Cell` as the context.
+
+unsafe extern "C" fn add(context: *const c_void, _threw: *mut u8, value: u32) -> u32 {
+    let total = &*(context as *const Cell<u32>);
+    total.set(total.get() + value);
+    total.get()
+}
+
+unsafe extern "C" fn reset_total(context: *const c_void, _threw: *mut u8, reason: RawString) -> u8 {
+    assert_eq!(String::from_abi(reason), "done");
+    (*(context as *const Cell<u32>)).set(0);
+    0
+}
+
+unsafe extern "C" fn add_throws(_: *const c_void, threw: *mut u8, _: u32) -> u32 {
+    *threw = 1;
+    0
+}
+
+unsafe extern "C" fn drop_context(_: *const c_void) {
+    DROPPED.fetch_add(1, Ordering::SeqCst);
+}
+
+fn raw_accumulator(
+    total: &Cell<u32>,
+    add: unsafe extern "C" fn(*const c_void, *mut u8, u32) -> u32,
+) -> RawTraitObject<__cs_bindgen_generated__Accumulator__VTable> {
+    RawTraitObject {
+        context: total as *const Cell<u32> as *const c_void,
+        drop: Some(drop_context),
+        vtable: __cs_bindgen_generated__Accumulator__VTable {
+            add: add as *const c_void,
+            reset: reset_total as *const c_void,
+        },
+    }
+}
+
+#[test]
+fn trait_object_from_raw() {
+    let total = Cell::new(0);
+
+    let accumulator = unsafe { <Box<dyn Accumulator>>::from_abi(raw_accumulator(&total, add)) };
+    assert_eq!(add_all(accumulator, vec![1, 2, 3]), 6);
+    assert_eq!(total.get(), 6);
+
+    let accumulator =
+        unsafe { <Box<dyn Accumulator + Send + Sync>>::from_abi(raw_accumulator(&total, add)) };
+    reset(accumulator);
+    assert_eq!(total.get(), 0);
+
+    // An exception thrown by the C# object is reported as a panic.
+    let accumulator =
+        unsafe { <Box<dyn Accumulator>>::from_abi(raw_accumulator(&total, add_throws)) };
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| accumulator.add(1)));
+    assert!(result.is_err());
+
+    // The context is released when each trait object is dropped.
+    drop(accumulator);
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 3);
+}
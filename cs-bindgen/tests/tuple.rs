@@ -0,0 +1,54 @@
+use cs_bindgen::{abi::Abi, prelude::*};
+use pretty_assertions::assert_eq;
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StructWithTuple {
+    pub position: (f32, f32),
+    pub flags: (bool, u8),
+}
+
+#[cs_bindgen]
+pub fn swap(pair: (i32, String)) -> (String, i32) {
+    (pair.1, pair.0)
+}
+
+#[test]
+fn primitive_round_trip() {
+    let original = (1_i32, 2.5_f32, true);
+    let result: (i32, f32, bool) = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn single_element_round_trip() {
+    let original = (7_u64,);
+    let result: (u64,) = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn string_round_trip() {
+    let original = (String::from("Cool string"), 12_u8);
+    let result: (String, u8) = unsafe { Abi::from_abi(original.clone().into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn max_length_round_trip() {
+    let original = (
+        1_u8, 2_u16, 3_u32, 4_u64, 5_i8, 6_i16, 7_i32, 8_i64, 9.0_f32, 10.0_f64, true, 12_usize,
+    );
+    let result = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
+
+#[test]
+fn struct_round_trip() {
+    let original = StructWithTuple {
+        position: (1.0, -2.0),
+        flags: (true, 3),
+    };
+    let result: StructWithTuple = unsafe { Abi::from_abi(original.into_abi()) };
+    assert_eq!(original, result);
+}
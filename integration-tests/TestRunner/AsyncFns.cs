@@ -0,0 +1,111 @@
+using System;
+using System.Collections.Generic;
+using System.Threading.Tasks;
+using Bindings;
+using Bindings.AsyncFn;
+using Xunit;
+
+namespace TestRunner
+{
+    public class AsyncFns
+    {
+        [Fact]
+        public async Task ReturnsValue()
+        {
+            Assert.Equal(3, await IntegrationTests.AddAsync(1, 2));
+        }
+
+        [Fact]
+        public async Task ReturnsUnit()
+        {
+            await IntegrationTests.Wait(10);
+        }
+
+        [Fact]
+        public async Task ResultOk()
+        {
+            Assert.Equal(new List<byte>() { 97, 98, 99 }, await IntegrationTests.Fetch("abc"));
+        }
+
+        [Fact]
+        public async Task ResultErrThrows()
+        {
+            var exception = await Assert.ThrowsAsync<RustException>(() => IntegrationTests.Fetch(""));
+            Assert.Equal("URL must not be empty", exception.Message);
+        }
+
+        [Fact]
+        public async Task PanicThrows()
+        {
+            var exception = await Assert.ThrowsAsync<RustPanicException>(() => IntegrationTests.PanicAsync("Async panic"));
+            Assert.Equal("Async panic", exception.Message);
+        }
+
+        [Fact]
+        public async Task ConcurrentCalls()
+        {
+            var tasks = new List<Task<int>>();
+            for (var i = 0; i < 10; i++)
+            {
+                tasks.Add(IntegrationTests.AddAsync(i, i));
+            }
+
+            var results = await Task.WhenAll(tasks);
+            for (var i = 0; i < 10; i++)
+            {
+                Assert.Equal(i * 2, results[i]);
+            }
+        }
+
+        [Fact]
+        public async Task AsyncMethods()
+        {
+            using (var connection = new Connection("a"))
+            {
+                Assert.Equal("a <- hi", await connection.Send("hi"));
+
+                connection.Reconnect("b");
+                Assert.Equal("b", connection.Address());
+            }
+        }
+
+        [Fact]
+        public async Task CannotMutateWhileAsyncMethodRuns()
+        {
+            using (var connection = new Connection("a"))
+            {
+                var ping = connection.Ping(1000);
+                Assert.Throws<InvalidOperationException>(() => connection.Reconnect("b"));
+                Assert.Throws<InvalidOperationException>(() => connection.Close());
+                Assert.Equal("a", await ping);
+
+                // The value can be mutated again once the async method has completed.
+                connection.Reconnect("b");
+                Assert.Equal("b", connection.Address());
+            }
+        }
+
+        [Fact]
+        public async Task AsyncMethodTakesSelf()
+        {
+            var connection = new Connection("a");
+            Assert.Equal("closed a", await connection.Close());
+        }
+
+        [Fact]
+        public async Task AsyncStaticMethod()
+        {
+            using (var connection = await Connection.Connect("c"))
+            {
+                Assert.Equal("c", connection.Address());
+            }
+        }
+
+        [Fact]
+        public async Task AsyncStaticMethodOnValueType()
+        {
+            Latency latency = await Latency.Measure(5);
+            Assert.Equal(5ul, latency.Millis);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+using System;
+using System.Collections.Generic;
+using Bindings;
+using Bindings.Callback;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Callbacks
+    {
+        [Fact]
+        public void BoxedCallback()
+        {
+            Assert.Equal(12, IntegrationTests.ApplyTwice(3, value => value * 2));
+        }
+
+        [Fact]
+        public void ImplFnCallback()
+        {
+            var greetings = IntegrationTests.GreetAll(
+                new List<string>() { "Ferris", "Clippy" },
+                name => "Hello, " + name + "!");
+            Assert.Equal(new List<string>() { "Hello, Ferris!", "Hello, Clippy!" }, greetings);
+        }
+
+        [Fact]
+        public void ActionCallback()
+        {
+            var visited = new List<uint>();
+            IntegrationTests.CountTo(3, visited.Add);
+            Assert.Equal(new List<uint>() { 1, 2, 3 }, visited);
+        }
+
+        [Fact]
+        public void CallbackExceptionIsRethrown()
+        {
+            Assert.Throws<InvalidOperationException>(
+                () => IntegrationTests.ApplyTwice(1, value => throw new InvalidOperationException()));
+
+            // Later calls aren't affected by the exception.
+            Assert.Equal(4, IntegrationTests.ApplyTwice(1, value => value * 2));
+        }
+
+        [Fact]
+        public void StoredCallbackStaysAlive()
+        {
+            var received = new List<uint>();
+            using (var source = new EventSource())
+            {
+                source.Subscribe(value => received.Add(value));
+
+                // The delegate is only referenced by Rust, so it has to survive a collection.
+                GC.Collect();
+                GC.WaitForPendingFinalizers();
+
+                source.Emit(7);
+                source.Emit(8);
+                Assert.Equal(new List<uint>() { 7, 8 }, received);
+
+                source.Clear();
+                source.Emit(9);
+                Assert.Equal(new List<uint>() { 7, 8 }, received);
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+using System;
+using System.Collections.Generic;
+using Bindings;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Chars
+    {
+        [Fact]
+        public void AsciiChar()
+        {
+            Assert.Equal(new RustChar('a'), IntegrationTests.RoundTripChar('a'));
+        }
+
+        [Fact]
+        public void NonBmpChar()
+        {
+            var crab = RustChar.FromString("🦀", 0);
+            Assert.Equal(0x1F980u, crab.Value);
+            Assert.Equal(crab, IntegrationTests.RoundTripChar(crab));
+            Assert.Equal("🦀", IntegrationTests.CharToString(crab));
+            Assert.Equal("🦀", crab.ToString());
+        }
+
+        [Fact]
+        public void RoundTripManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                NonBmpChar();
+            }
+        }
+
+        [Fact]
+        public void InvalidCharThrows()
+        {
+            Assert.Throws<ArgumentOutOfRangeException>(() => new RustChar(0xD800));
+            Assert.Throws<ArgumentOutOfRangeException>(() => new RustChar(0x110000));
+            Assert.Throws<ArgumentOutOfRangeException>(() => new RustChar('\uDC00'));
+        }
+
+        [Fact]
+        public void StringToChars()
+        {
+            var expected = new List<RustChar>()
+            {
+                'f',
+                'o',
+                RustChar.FromString("🔪", 0),
+            };
+            Assert.Equal(expected, IntegrationTests.StringToChars("fo🔪"));
+        }
+
+        [Fact]
+        public void RoundTripCharList()
+        {
+            var expected = new List<RustChar>() { 'a', 'ß', RustChar.FromString("🦀", 0) };
+            Assert.Equal(expected, IntegrationTests.RoundTripCharVec(expected));
+        }
+
+        [Fact]
+        public void OptionalChar()
+        {
+            Assert.Equal(new RustChar('x'), IntegrationTests.RoundTripOptionalChar('x'));
+            Assert.Null(IntegrationTests.RoundTripOptionalChar(null));
+        }
+    }
+}
@@ -1,4 +1,9 @@
+using System;
 using System.Collections.Generic;
+using Bindings;
+using Bindings.Collections;
+using Bindings.DataEnum;
+using Bindings.SimpleEnum;
 using Xunit;
 
 namespace TestRunner
@@ -99,5 +104,198 @@ namespace TestRunner
                 ReturnDataEnumList();
             }
         }
+
+        [Fact]
+        public void RoundTripStringList()
+        {
+            var expected = new List<string>() { "foo", "bar", "baz" };
+            var actual = IntegrationTests.RoundTripStringVec(expected);
+            Assert.Equal(expected, actual);
+        }
+
+        [Fact]
+        public void ReturnHashMap()
+        {
+            var expected = new Dictionary<string, int>()
+            {
+                { "foo", 1 },
+                { "bar", 2 },
+                { "baz", 3 },
+            };
+            Assert.Equal(expected, IntegrationTests.ReturnHashMap());
+        }
+
+        [Fact]
+        public void RoundTripHashMap()
+        {
+            var expected = new Dictionary<string, int>()
+            {
+                { "Cool string", 12 },
+                { "Even cooler string", -7 },
+            };
+            Assert.Equal(expected, IntegrationTests.RoundTripHashMap(expected));
+        }
+
+        [Fact]
+        public void RoundTripHashMapManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                RoundTripHashMap();
+            }
+        }
+
+        [Fact]
+        public void RoundTripBTreeMap()
+        {
+            var expected = new Dictionary<uint, string>()
+            {
+                { 1, "foo" },
+                { 22, "bar" },
+            };
+            Assert.Equal(expected, IntegrationTests.RoundTripBtreeMap(expected));
+        }
+
+        [Fact]
+        public void RoundTripEmptyMap()
+        {
+            var expected = new Dictionary<uint, string>();
+            Assert.Equal(expected, IntegrationTests.RoundTripBtreeMap(expected));
+        }
+
+        [Fact]
+        public void RoundTripNamedMap()
+        {
+            var expected = new Dictionary<SimpleCEnum, CopyStruct>()
+            {
+                { SimpleCEnum.Foo, new CopyStruct(1) },
+                { SimpleCEnum.Baz, new CopyStruct(3) },
+            };
+            Assert.Equal(expected, IntegrationTests.RoundTripNamedMap(expected));
+        }
+
+        [Fact]
+        public void ReturnMatrix()
+        {
+            var expected = new float[]
+            {
+                1, 0, 0, 0,
+                0, 1, 0, 0,
+                0, 0, 1, 0,
+                0, 0, 0, 1,
+            };
+            Assert.Equal(expected, IntegrationTests.IdentityMatrix());
+        }
+
+        [Fact]
+        public void ReturnMatrixManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                ReturnMatrix();
+            }
+        }
+
+        [Fact]
+        public void LargeArrayArg()
+        {
+            var values = new int[64];
+            for (var index = 0; index < values.Length; index += 1)
+            {
+                values[index] = index;
+            }
+
+            Assert.Equal(2016, IntegrationTests.SumArray(values));
+        }
+
+        [Fact]
+        public void WrongArrayLengthThrows()
+        {
+            Assert.Throws<ArgumentException>(() => IntegrationTests.SumArray(new int[3]));
+        }
+
+        [Fact]
+        public void RoundTripStringArray()
+        {
+            var expected = new string[] { "foo", "bar", "baz" };
+            Assert.Equal(expected, IntegrationTests.RoundTripStringArray(expected));
+        }
+
+        [Fact]
+        public void RoundTripStructArray()
+        {
+            var expected = new CopyStruct[] { new CopyStruct(1), new CopyStruct(2) };
+            Assert.Equal(expected, IntegrationTests.RoundTripStructArray(expected));
+        }
+
+        [Fact]
+        public void StructWithArrayFields()
+        {
+            var original = new Transform(IntegrationTests.IdentityMatrix(), new bool[] { true, false });
+            var result = IntegrationTests.RoundTripTransform(original);
+            Assert.Equal(original.Matrix, result.Matrix);
+            Assert.Equal(original.Enabled, result.Enabled);
+        }
+
+        [Fact]
+        public void SumSlice()
+        {
+            int[] values = new int[] { 1, 2, 3, 4, 5 };
+            Assert.Equal(15, IntegrationTests.SumSlice(values));
+            Assert.Equal(9, IntegrationTests.SumSlice(new ReadOnlySpan<int>(values, 1, 3)));
+            Assert.Equal(0, IntegrationTests.SumSlice(ReadOnlySpan<int>.Empty));
+        }
+
+        [Fact]
+        public void ScaleSamplesInPlace()
+        {
+            float[] samples = new float[] { 1.0f, -0.5f, 0.25f, 2.0f };
+            IntegrationTests.ScaleSamples(samples, 2.0f);
+            Assert.Equal(new float[] { 2.0f, -1.0f, 0.5f, 4.0f }, samples);
+
+            // Only the elements covered by the span are modified.
+            IntegrationTests.ScaleSamples(new Span<float>(samples, 2, 2), 0.5f);
+            Assert.Equal(new float[] { 2.0f, -1.0f, 0.25f, 2.0f }, samples);
+        }
+
+        [Fact]
+        public void SumSliceFromList()
+        {
+            Assert.Equal(15, IntegrationTests.SumSlice(new List<int>() { 1, 2, 3, 4, 5 }));
+            Assert.Equal(0, IntegrationTests.SumSlice(new List<int>()));
+        }
+
+        [Fact]
+        public void SumVec()
+        {
+            // Lists of up to 32 elements are copied onto the stack, and longer lists are
+            // copied into a pooled array.
+            var small = new List<long>() { 1, 2, 3 };
+            Assert.Equal(6, IntegrationTests.SumVec(small));
+
+            var large = new List<long>();
+            for (long value = 1; value <= 100; value += 1)
+            {
+                large.Add(value);
+            }
+            Assert.Equal(5050, IntegrationTests.SumVec(large));
+        }
+
+        [Fact]
+        public void ScaleSamplesInList()
+        {
+            List<float> samples = new List<float>() { 1.0f, -0.5f, 0.25f, 2.0f };
+            IntegrationTests.ScaleSamples(samples, 2.0f);
+            Assert.Equal(new List<float>() { 2.0f, -1.0f, 0.5f, 4.0f }, samples);
+        }
+
+        [Fact]
+        public void CopyBytesIntoBuffer()
+        {
+            byte[] source = new byte[] { 1, 2, 3 };
+            Span<byte> dest = stackalloc byte[5];
+            Assert.Equal((UIntPtr)3, IntegrationTests.CopyBytes(source, dest));
+            Assert.Equal(new byte[] { 1, 2, 3, 0, 0 }, dest.ToArray());
+        }
     }
 }
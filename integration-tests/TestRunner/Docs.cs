@@ -0,0 +1,41 @@
+using Bindings;
+using Bindings.Docs;
+using Xunit;
+
+namespace TestRunner
+{
+    // The doc comments in the generated bindings aren't visible at runtime, so these
+    // tests only verify that documented items still work as expected.
+    public class Docs
+    {
+        [Fact]
+        public void DocumentedFunctions()
+        {
+            var reading = new Reading(35, 1);
+            Assert.Equal(95, reading.Fahrenheit());
+            Assert.Equal(Level.Alert, IntegrationTests.Classify(reading, 30));
+            Assert.Equal(Level.Normal, IntegrationTests.Classify(reading, 40));
+        }
+
+        [Fact]
+        public void DocumentedEnumVariants()
+        {
+            var reading = new Reading(35, 1);
+            Assert.IsType<Check.Ok>(IntegrationTests.CheckRange(reading, 30, 40));
+
+            var check = Assert.IsType<Check.OutOfRange>(IntegrationTests.CheckRange(reading, 10, 20));
+            Assert.Equal(15, check.Amount);
+        }
+
+        [Fact]
+        public void DocumentedMethods()
+        {
+            using (var log = new Log())
+            {
+                log.Record(new Reading(20, 1));
+                log.Record(new Reading(21, 2));
+                Assert.Equal(2u, log.Count);
+            }
+        }
+    }
+}
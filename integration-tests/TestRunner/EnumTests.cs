@@ -1,5 +1,8 @@
 using System;
 using System.Linq;
+using Bindings;
+using Bindings.DataEnum;
+using Bindings.SimpleEnum;
 using Xunit;
 
 namespace TestRunner
@@ -59,5 +62,29 @@ namespace TestRunner
                 Assert.Equal(orig.Value, result.Value);
             }
         }
+
+        [Fact]
+        public void SimpleEnumMethods()
+        {
+            var value = SimpleCEnumExtensions.First();
+            Assert.Equal(SimpleCEnum.Foo, value);
+            Assert.Equal(0u, value.Index());
+
+            value.Cycle();
+            Assert.Equal(SimpleCEnum.Bar, value);
+            Assert.Equal(1u, value.Index());
+        }
+
+        [Fact]
+        public void DataEnumMethods()
+        {
+            IDataEnum value = DataEnum.FromName("Randal");
+            Assert.Equal("Bar(Randal)", value.Describe());
+            Assert.Equal("Foo", new DataEnum.Foo().Describe());
+
+            DataEnum.Rename(ref value, "Dante");
+            Assert.Equal("Dante", ((DataEnum.Bar)value).Element0);
+            Assert.Equal("Bar(Dante)", DataEnum.Describe(value));
+        }
     }
 }
@@ -0,0 +1,57 @@
+using System;
+using System.Runtime.CompilerServices;
+using Bindings;
+using Bindings.Finalizer;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Finalizers
+    {
+        [Fact]
+        public void DisposeDropsValue()
+        {
+            var before = IntegrationTests.LiveTracked();
+            var tracked = new Tracked(1);
+            Assert.Equal(before + 1, IntegrationTests.LiveTracked());
+
+            tracked.Dispose();
+            Assert.Equal(before, IntegrationTests.LiveTracked());
+            Assert.Throws<ObjectDisposedException>(() => tracked.Id());
+        }
+
+        [Fact]
+        public void FinalizerDropsValue()
+        {
+            var before = IntegrationTests.LiveTracked();
+            CreateUndisposed(10);
+
+            GC.Collect();
+            GC.WaitForPendingFinalizers();
+
+            Assert.Equal(before, IntegrationTests.LiveTracked());
+        }
+
+        [Fact]
+        public void HandleArgumentKeptAlive()
+        {
+            using (var first = new Tracked(7))
+            using (var second = new Tracked(7))
+            {
+                Assert.True(first.SameId(second));
+                Assert.Equal(7u, second.Id());
+            }
+        }
+
+        // Creates objects in a separate method so that they're unreachable once it
+        // returns, even in debug builds.
+        [MethodImpl(MethodImplOptions.NoInlining)]
+        private static void CreateUndisposed(uint count)
+        {
+            for (uint id = 0; id < count; id++)
+            {
+                Assert.Equal(id, new Tracked(id).Id());
+            }
+        }
+    }
+}
@@ -1,3 +1,7 @@
+using System;
+using System.Collections.Generic;
+using Bindings;
+using Bindings.Method;
 using Xunit;
 
 namespace TestRunner
@@ -105,6 +109,30 @@ namespace TestRunner
             Assert.Equal(7, PersonInfo.StaticFunction());
         }
 
+        [Fact]
+        public void MultipleConstructors()
+        {
+            using (PersonInfo info = new PersonInfo("David", 12, 42u, "Elm Street"))
+            using (Address address = info.Address())
+            {
+                Assert.Equal("David", info.Name());
+                Assert.Equal(42u, address.StreetNumber());
+                Assert.Equal("Elm Street", address.StreetName());
+            }
+        }
+
+        [Fact]
+        public void FactoryMethod()
+        {
+            using (PersonInfo baby = PersonInfo.Newborn("Dante"))
+            using (PersonInfo info = new PersonInfo("David", 12))
+            {
+                Assert.Equal(0, baby.Age());
+                Assert.True(info.IsOlderThan(baby));
+                Assert.False(baby.IsOlderThan(info));
+            }
+        }
+
         [Fact]
         public void PersonAddress()
         {
@@ -133,5 +161,120 @@ namespace TestRunner
                 }
             }
         }
+
+        [Fact]
+        public void BorrowedAddress()
+        {
+            using (PersonInfo info = new PersonInfo("David", 12))
+            {
+                // Disposing the borrowed address must not free the person's address.
+                using (Address address = info.AddressRef())
+                {
+                    Assert.Equal(123u, address.StreetNumber());
+                    Assert.Equal("Cool Kids Lane", address.StreetName());
+                }
+
+                using (Address address = info.AddressRef())
+                {
+                    Assert.Equal("Cool Kids Lane", address.StreetName());
+                }
+            }
+        }
+
+        [Fact]
+        public void BorrowedAddressAfterOwnerDisposed()
+        {
+            PersonInfo info = new PersonInfo("David", 12);
+            Address address = info.AddressRef();
+            info.Dispose();
+
+            Assert.Throws<ObjectDisposedException>(() => address.StreetName());
+        }
+
+        [Fact]
+        public void BorrowedSlices()
+        {
+            using (Inventory inventory = new Inventory())
+            {
+                Assert.Equal(new List<int>() { 1, 2, 3, 5, 8 }, inventory.Counts());
+                Assert.Equal(new List<bool>() { true, false, true }, inventory.Flags());
+                Assert.Equal(new List<string>() { "apple", "banana", "🍒" }, inventory.Labels());
+                Assert.Equal("apple", inventory.FirstLabel());
+                Assert.Empty(inventory.Empty());
+            }
+        }
+
+        [Fact]
+        public void ConsumingMethod()
+        {
+            using (PersonInfo info = new PersonInfo("David", 12))
+            {
+                Assert.Equal("David", info.IntoName());
+                Assert.Throws<ObjectDisposedException>(() => info.Age());
+                Assert.Throws<ObjectDisposedException>(() => info.IntoName());
+            }
+        }
+
+        [Fact]
+        public void ConsumingMethodWithInvalidArgument()
+        {
+            using (PersonInfo info = new PersonInfo("David", 12))
+            {
+                Address address = info.Address();
+                address.Dispose();
+                Assert.Throws<ObjectDisposedException>(() => info.MoveTo(address));
+
+                // The receiver is only moved into Rust once the arguments have been
+                // converted, so it's still usable.
+                Assert.Equal(12, info.Age());
+            }
+        }
+
+        [Fact]
+        public void ConsumingFunction()
+        {
+            using (PersonInfo info = new PersonInfo("David", 12))
+            {
+                Assert.Equal(12, IntegrationTests.ConsumePerson(info));
+                Assert.Throws<ObjectDisposedException>(() => info.Name());
+                Assert.Throws<ObjectDisposedException>(() => IntegrationTests.ConsumePerson(info));
+            }
+        }
+
+        [Fact]
+        public void UseAfterDispose()
+        {
+            PersonInfo info = new PersonInfo("David", 12);
+            info.Dispose();
+            Assert.Throws<ObjectDisposedException>(() => info.Age());
+
+            // Disposing more than once is allowed.
+            info.Dispose();
+        }
+
+        [Fact]
+        public void CannotMoveBorrowedHandle()
+        {
+            using (PersonInfo info = new PersonInfo("David", 12))
+            {
+                Address address = info.AddressRef();
+                Assert.Throws<InvalidOperationException>(() => IntegrationTests.ConsumeAddress(address));
+                Assert.Equal("Cool Kids Lane", address.StreetName());
+            }
+        }
+
+        [Fact]
+        public void MutableBorrowIsExclusive()
+        {
+            using (PersonInfo info = new PersonInfo("David", 12))
+            using (PersonInfo other = new PersonInfo("Sarah", 30))
+            {
+                Assert.Throws<InvalidOperationException>(() => info.CopyAgeFrom(info));
+                Assert.Equal(12, info.Age());
+
+                info.CopyAgeFrom(other);
+                Assert.Equal(30, info.Age());
+            }
+        }
     }
 }
@@ -0,0 +1,94 @@
+using System;
+using System.Collections.Generic;
+using System.Linq;
+using Bindings;
+using Bindings.Iterator;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Iterators
+    {
+        [Fact]
+        public void Foreach()
+        {
+            var values = new List<int>();
+            foreach (var value in IntegrationTests.Range(1, 4))
+            {
+                values.Add(value);
+            }
+
+            Assert.Equal(new List<int>() { 1, 2, 3 }, values);
+        }
+
+        [Fact]
+        public void Empty()
+        {
+            Assert.Empty(IntegrationTests.Range(4, 4));
+        }
+
+        [Fact]
+        public void ConcreteIteratorType()
+        {
+            Assert.Equal(
+                new List<string>() { "the", "quick", "fox" },
+                IntegrationTests.SplitWords("the  quick fox").ToList());
+        }
+
+        [Fact]
+        public void InfiniteIterator()
+        {
+            Assert.Equal(
+                new List<ulong>() { 1, 2, 3, 4, 5 },
+                IntegrationTests.Naturals().Take(5).ToList());
+        }
+
+        [Fact]
+        public void EnumerateTwiceThrows()
+        {
+            var values = IntegrationTests.Range(0, 3);
+            Assert.Equal(3, values.Count());
+            Assert.Throws<InvalidOperationException>(() => values.Count());
+        }
+
+        [Fact]
+        public void DisposeWithoutEnumerating()
+        {
+            using (var enumerator = IntegrationTests.Naturals().GetEnumerator())
+            {
+            }
+        }
+
+        [Fact]
+        public void PanicThrows()
+        {
+            var values = new List<uint>();
+            var exception = Assert.Throws<RustPanicException>(() =>
+            {
+                foreach (var value in IntegrationTests.PanicAfter(2, "Iterator panic"))
+                {
+                    values.Add(value);
+                }
+            });
+
+            Assert.Equal("Iterator panic", exception.Message);
+            Assert.Equal(new List<uint>() { 0, 1 }, values);
+        }
+
+        [Fact]
+        public void MethodsReturningIterators()
+        {
+            using (var catalog = new Catalog())
+            {
+                catalog.Add("pear", 2);
+                catalog.Add("apple", 3);
+                catalog.Add("pear", 1);
+
+                Assert.Equal(new List<string>() { "apple", "pear" }, catalog.Names().ToList());
+                Assert.Equal(
+                    new List<(string, uint)>() { ("apple", 3), ("pear", 3) },
+                    catalog.Entries().ToList());
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+using System.Collections.Generic;
+using Bindings;
+using Xunit;
+using First = Bindings.NameCollision.First;
+using Second = Bindings.NameCollision.Second;
+
+namespace TestRunner
+{
+    public class NameCollisions
+    {
+        [Fact]
+        public void SameNamedValueTypes()
+        {
+            var first = new First.Point(1, 2);
+            var second = new Second.Point(1.5f, 2.5f, 3.5f);
+
+            Assert.Equal("first (1, 2)", IntegrationTests.DescribePoint(first));
+            Assert.Equal("second (1.5, 2.5, 3.5)", IntegrationTests.DescribePoint(second));
+        }
+
+        [Fact]
+        public void SameNamedHandleTypes()
+        {
+            using (var first = new First.Counter())
+            using (var second = new Second.Counter())
+            {
+                Assert.Equal(1u, first.Increment());
+                Assert.Equal(2u, second.Increment());
+                Assert.Equal(2u, first.Increment());
+                Assert.Equal(4u, second.Increment());
+            }
+        }
+
+        [Fact]
+        public void SameNamedTypesInLists()
+        {
+            var first = IntegrationTests.ReversePoints(new List<First.Point>
+            {
+                new First.Point(1, 2),
+                new First.Point(3, 4),
+            });
+            Assert.Equal(new List<First.Point> { new First.Point(3, 4), new First.Point(1, 2) }, first);
+
+            var second = IntegrationTests.ReversePoints(new List<Second.Point>
+            {
+                new Second.Point(1, 2, 3),
+                new Second.Point(4, 5, 6),
+            });
+            Assert.Equal(new List<Second.Point> { new Second.Point(4, 5, 6), new Second.Point(1, 2, 3) }, second);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+using System.Collections.Generic;
+using Bindings;
+using Bindings.Collections;
+using Bindings.DataEnum;
+using Bindings.Option;
+using Bindings.SimpleEnum;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Options
+    {
+        [Fact]
+        public void OptionIntSome()
+        {
+            Assert.Equal(123, IntegrationTests.RoundTripOptionI32(123));
+        }
+
+        [Fact]
+        public void OptionIntNone()
+        {
+            Assert.Null(IntegrationTests.RoundTripOptionI32(null));
+        }
+
+        [Fact]
+        public void OptionBool()
+        {
+            Assert.Equal(false, IntegrationTests.RoundTripOptionBool(false));
+            Assert.Null(IntegrationTests.RoundTripOptionBool(null));
+        }
+
+        [Fact]
+        public void OptionString()
+        {
+            Assert.Equal("Cool string", IntegrationTests.RoundTripOptionString("Cool string"));
+            Assert.Null(IntegrationTests.RoundTripOptionString(null));
+        }
+
+        [Fact]
+        public void OptionStringManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                OptionString();
+            }
+        }
+
+        [Fact]
+        public void OptionStruct()
+        {
+            var original = new CopyStruct(33);
+            Assert.Equal(original, IntegrationTests.RoundTripOptionCopyStruct(original));
+            Assert.Null(IntegrationTests.RoundTripOptionCopyStruct(null));
+        }
+
+        [Fact]
+        public void OptionSimpleEnum()
+        {
+            Assert.Equal(SimpleCEnum.Bar, IntegrationTests.RoundTripOptionSimpleEnum(SimpleCEnum.Bar));
+            Assert.Null(IntegrationTests.RoundTripOptionSimpleEnum(null));
+        }
+
+        [Fact]
+        public void OptionDataEnum()
+        {
+            IDataEnum original = new DataEnum.Bar("Cool string");
+            Assert.Equal(original, IntegrationTests.RoundTripOptionDataEnum(original));
+            Assert.Null(IntegrationTests.RoundTripOptionDataEnum(null));
+        }
+
+        [Fact]
+        public void OptionList()
+        {
+            var original = new List<int>() { 1, 2, 3, 4 };
+            Assert.Equal(original, IntegrationTests.RoundTripOptionVec(original));
+            Assert.Null(IntegrationTests.RoundTripOptionVec(null));
+        }
+
+        [Fact]
+        public void StructWithOptionalFields()
+        {
+            var value = new StructWithOptionalFields(12, null);
+            Assert.Equal(12u, value.Count());
+            Assert.Null(value.Name());
+
+            value = new StructWithOptionalFields(null, "Cool name");
+            Assert.Null(value.Count());
+            Assert.Equal("Cool name", value.Name());
+        }
+    }
+}
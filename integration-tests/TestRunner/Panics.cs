@@ -0,0 +1,57 @@
+using Bindings;
+using Bindings.Panic;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Panics
+    {
+        [Fact]
+        public void NoPanic()
+        {
+            Assert.Equal(5, IntegrationTests.PanicIf(5));
+        }
+
+        [Fact]
+        public void PanicThrows()
+        {
+            var exception = Assert.Throws<RustPanicException>(() => IntegrationTests.PanicIf(-3));
+            Assert.Equal("Value was negative: -3", exception.Message);
+            Assert.Contains("panic.rs", exception.Location);
+        }
+
+        [Fact]
+        public void CallAfterPanic()
+        {
+            Assert.Throws<RustPanicException>(() => IntegrationTests.PanicIf(-1));
+            Assert.Equal(10, IntegrationTests.PanicIf(10));
+        }
+
+        [Fact]
+        public void PanicWithString()
+        {
+            var exception = Assert.Throws<RustPanicException>(() => IntegrationTests.PanicWithString("Cool panic"));
+            Assert.Equal("Cool panic", exception.Message);
+        }
+
+        [Fact]
+        public void PanicManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                PanicThrows();
+            }
+        }
+
+        [Fact]
+        public void MethodPanic()
+        {
+            var value = new PanicOnDrop("Method");
+            var exception = Assert.Throws<RustPanicException>(() => value.PanicNow());
+            Assert.Equal("Method", exception.Message);
+
+            exception = Assert.Throws<RustPanicException>(() => value.Dispose());
+            Assert.Equal("Dropped: Method", exception.Message);
+        }
+    }
+}
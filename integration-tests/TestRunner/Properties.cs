@@ -0,0 +1,55 @@
+using Bindings;
+using Bindings.Property;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Properties
+    {
+        [Fact]
+        public void GetAndSet()
+        {
+            using (var thermostat = new Thermostat("Hallway"))
+            {
+                Assert.Equal("Hallway", thermostat.Label);
+
+                thermostat.Target = 10;
+                thermostat.Label = "Kitchen 🍳";
+                Assert.Equal(10, thermostat.Target);
+                Assert.Equal("Kitchen 🍳", thermostat.Label);
+            }
+        }
+
+        [Fact]
+        public void ReadOnlyProperty()
+        {
+            using (var thermostat = new Thermostat("Hallway"))
+            {
+                thermostat.Target = 25;
+                Assert.True(thermostat.IsHeating);
+
+                thermostat.Target = 5;
+                Assert.False(thermostat.IsHeating);
+            }
+        }
+
+        [Fact]
+        public void StaticProperty()
+        {
+            var original = Thermostat.DefaultTarget;
+            try
+            {
+                Thermostat.DefaultTarget = 18;
+                using (var thermostat = new Thermostat("Bedroom"))
+                {
+                    Assert.Equal(18, Thermostat.DefaultTarget);
+                    Assert.Equal(18, thermostat.Target);
+                }
+            }
+            finally
+            {
+                Thermostat.DefaultTarget = original;
+            }
+        }
+    }
+}
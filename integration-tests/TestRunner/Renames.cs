@@ -0,0 +1,45 @@
+using Bindings;
+using Bindings.Rename;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Renames
+    {
+        [Fact]
+        public void RenamedStructFields()
+        {
+            var point = new Vector2(1, 2);
+            Assert.Equal(1, point.Horizontal);
+            Assert.Equal(2, point.Vertical);
+
+            var moved = IntegrationTests.ApplyMovement(point, new Move.Step(3));
+            Assert.Equal(new Vector2(1, 5), moved);
+        }
+
+        [Fact]
+        public void RenamedEnumVariants()
+        {
+            Assert.Equal(Heading.Down, IntegrationTests.Flip(heading: Heading.Up));
+            Assert.Equal(Heading.Up, IntegrationTests.Flip(heading: Heading.Down));
+
+            var turn = new Move.Turn(180);
+            Assert.Equal(180, turn.Degrees);
+
+            var turned = IntegrationTests.ApplyMovement(new Vector2(1, 0), turn);
+            Assert.Equal(-1, turned.Horizontal, 3);
+            Assert.Equal(0, turned.Vertical, 3);
+        }
+
+        [Fact]
+        public void RenamedMethods()
+        {
+            using (var tally = new Tally())
+            {
+                Assert.Equal(2u, tally.Bump(step: 2));
+                Assert.Equal(5u, tally.Bump(step: 3));
+                Assert.Equal(5u, tally.Total);
+            }
+        }
+    }
+}
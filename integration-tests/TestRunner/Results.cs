@@ -0,0 +1,130 @@
+using System.Collections.Generic;
+using Bindings;
+using Bindings.DataEnum;
+using Bindings.Result;
+using Bindings.SimpleEnum;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Results
+    {
+        [Fact]
+        public void StringErrorOk()
+        {
+            Assert.Equal(123, IntegrationTests.ParseInt("123"));
+        }
+
+        [Fact]
+        public void StringErrorThrows()
+        {
+            var exception = Assert.Throws<RustException>(() => IntegrationTests.ParseInt("abc"));
+            Assert.Equal("Invalid integer: \"abc\"", exception.Message);
+        }
+
+        [Fact]
+        public void StringErrorManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                StringErrorThrows();
+            }
+        }
+
+        [Fact]
+        public void SimpleEnumError()
+        {
+            Assert.Equal(7, IntegrationTests.FailWithSimpleEnum(false));
+
+            var exception = Assert.Throws<SimpleCEnumException>(() => IntegrationTests.FailWithSimpleEnum(true));
+            Assert.Equal(SimpleCEnum.Baz, exception.Error);
+        }
+
+        [Fact]
+        public void DataEnumError()
+        {
+            Assert.Equal("Success", IntegrationTests.FailWithDataEnum(false));
+
+            var exception = Assert.Throws<DataEnumException>(() => IntegrationTests.FailWithDataEnum(true));
+            Assert.Equal(new DataEnum.Bar("Cool error"), exception.Error);
+        }
+
+        [Fact]
+        public void StructErrorVoidReturn()
+        {
+            IntegrationTests.FailWithStruct(0);
+
+            var exception = Assert.Throws<ErrorCodeException>(() => IntegrationTests.FailWithStruct(12));
+            Assert.Equal(12, exception.Error.Code);
+        }
+
+        [Fact]
+        public void OtherError()
+        {
+            var values = new List<int>() { 1, 2, 3 };
+            Assert.Equal(2, IntegrationTests.CheckedIndex(values, 1));
+
+            var exception = Assert.Throws<RustException<uint>>(() => IntegrationTests.CheckedIndex(values, 5));
+            Assert.Equal(3u, exception.Error);
+            Assert.Equal("3", exception.Message);
+        }
+
+        [Fact]
+        public void MethodError()
+        {
+            using (var validator = new Validator(10))
+            {
+                Assert.Equal(5, validator.Validate(5));
+
+                var exception = Assert.Throws<RustException>(() => validator.Validate(11));
+                Assert.Equal("11 is larger than 10", exception.Message);
+            }
+        }
+
+        [Fact]
+        public void TryPatternSuccess()
+        {
+            Assert.True(IntegrationTests.TryParseFloat("1.5", out var value, out var error));
+            Assert.Equal(1.5f, value);
+            Assert.Null(error);
+        }
+
+        [Fact]
+        public void TryPatternFailure()
+        {
+            Assert.False(IntegrationTests.TryParseFloat("abc", out var value, out var error));
+            Assert.Equal(0f, value);
+            Assert.Equal("Invalid float: \"abc\"", error);
+        }
+
+        [Fact]
+        public void TryPatternManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                TryPatternFailure();
+            }
+        }
+
+        [Fact]
+        public void TryPatternUnit()
+        {
+            Assert.True(IntegrationTests.TryCheckFlag(false, out var error));
+            Assert.False(IntegrationTests.TryCheckFlag(true, out error));
+            Assert.Equal(SimpleCEnum.Bar, error);
+        }
+
+        [Fact]
+        public void TryPatternMethod()
+        {
+            using (var validator = new Validator(10))
+            {
+                Assert.True(validator.TryCheckedValidate(5, out var value, out var error));
+                Assert.Equal(5, value);
+
+                Assert.False(validator.TryCheckedValidate(11, out value, out error));
+                Assert.Equal("11 is larger than 10", error);
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+using Bindings;
+using Bindings.Structs;
 using Xunit;
 
 namespace TestRunner
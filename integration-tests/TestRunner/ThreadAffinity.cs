@@ -0,0 +1,81 @@
+using System;
+using System.Threading;
+using Bindings.ThreadAffinity;
+using Xunit;
+
+namespace TestRunner
+{
+    public class ThreadAffinity
+    {
+        [Fact]
+        public void SameThreadUse()
+        {
+            using (var counter = new LocalCounter(1))
+            {
+                Assert.Equal(2u, counter.Increment());
+                Assert.Equal(3u, counter.Increment());
+            }
+        }
+
+        [Fact]
+        public void CrossThreadUseThrows()
+        {
+            using (var counter = new LocalCounter(1))
+            {
+                Assert.IsType<InvalidOperationException>(OnOtherThread(() => counter.Increment()));
+                Assert.IsType<InvalidOperationException>(OnOtherThread(() => counter.Dispose()));
+
+                // The counter is still usable from the thread that created it.
+                Assert.Equal(2u, counter.Increment());
+            }
+        }
+
+        [Fact]
+        public void SendTypeUsableFromAnyThread()
+        {
+            using (var counter = new SharedCounter(5))
+            {
+                uint count = 0;
+                Assert.Null(OnOtherThread(() => count = counter.Count()));
+                Assert.Equal(5u, count);
+            }
+        }
+
+        [Fact]
+        public void UnsyncedCallsAreSerialized()
+        {
+            using (var counter = new UnsyncedCounter(0))
+            {
+                var threads = new Thread[4];
+                for (var index = 0; index < threads.Length; index += 1)
+                {
+                    threads[index] = new Thread(() =>
+                    {
+                        for (var count = 0; count < 1000; count += 1)
+                        {
+                            counter.Increment();
+                        }
+                    });
+                    threads[index].Start();
+                }
+
+                foreach (var thread in threads)
+                {
+                    thread.Join();
+                }
+
+                Assert.Equal(4000u, counter.Count());
+            }
+        }
+
+        // Runs `action` on a new thread, returning the exception it threw, if any.
+        private static Exception OnOtherThread(Action action)
+        {
+            Exception error = null;
+            var thread = new Thread(() => error = Record.Exception(action));
+            thread.Start();
+            thread.Join();
+            return error;
+        }
+    }
+}
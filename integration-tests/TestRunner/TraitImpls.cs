@@ -0,0 +1,44 @@
+using System;
+using System.Collections.Generic;
+using System.Linq;
+using Bindings.TraitImpl;
+using Xunit;
+
+namespace TestRunner
+{
+    public class TraitImpls
+    {
+        [Fact]
+        public void CallThroughInterface()
+        {
+            using (var circle = new Circle(1.0))
+            using (var rectangle = new Rectangle(2.0, 3.0))
+            {
+                var shapes = new List<IShape>() { circle, rectangle };
+                Assert.Equal(new List<string>() { "circle", "rectangle" }, shapes.Select(shape => shape.Describe()).ToList());
+                Assert.Equal(Math.PI + 6.0, shapes.Sum(shape => shape.Area()), 6);
+            }
+        }
+
+        [Fact]
+        public void MutableReceiver()
+        {
+            using (var circle = new Circle(1.0))
+            {
+                IShape shape = circle;
+                shape.Scale(2.0);
+                Assert.Equal(2.0, circle.Radius());
+            }
+        }
+
+        [Fact]
+        public void InherentMethodWithSameName()
+        {
+            using (var rectangle = new Rectangle(2.0, 3.0))
+            {
+                Assert.Equal("2x3", rectangle.Name());
+                Assert.Equal("rectangle", rectangle.Describe());
+            }
+        }
+    }
+}
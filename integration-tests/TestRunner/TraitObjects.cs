@@ -0,0 +1,94 @@
+using System;
+using System.Collections.Generic;
+using Bindings;
+using Bindings.TraitObject;
+using Xunit;
+
+namespace TestRunner
+{
+    public class TraitObjects
+    {
+        private class InMemoryLoader : IAssetLoader
+        {
+            public readonly List<string> Loaded = new List<string>();
+
+            public List<byte> Load(string path)
+            {
+                Loaded.Add(path);
+                return new List<byte>(new byte[path.Length]);
+            }
+
+            public string Name()
+            {
+                return "memory";
+            }
+        }
+
+        private class ThrowingLoader : IAssetLoader
+        {
+            public List<byte> Load(string path)
+            {
+                throw new InvalidOperationException();
+            }
+
+            public string Name()
+            {
+                return "throwing";
+            }
+        }
+
+        private class CollectingVisitor : IVisitor
+        {
+            public readonly List<int> Visited = new List<int>();
+
+            public void Visit(int value)
+            {
+                Visited.Add(value);
+            }
+        }
+
+        [Fact]
+        public void CallMethods()
+        {
+            var loader = new InMemoryLoader();
+            Assert.Equal(7u, IntegrationTests.TotalSize(loader, new List<string>() { "a.png", "bc" }));
+            Assert.Equal(new List<string>() { "a.png", "bc" }, loader.Loaded);
+        }
+
+        [Fact]
+        public void MutableReceiver()
+        {
+            var visitor = new CollectingVisitor();
+            IntegrationTests.VisitRange(visitor, 2, 5);
+            Assert.Equal(new List<int>() { 2, 3, 4 }, visitor.Visited);
+        }
+
+        [Fact]
+        public void ExceptionIsRethrown()
+        {
+            Assert.Throws<InvalidOperationException>(
+                () => IntegrationTests.TotalSize(new ThrowingLoader(), new List<string>() { "a" }));
+
+            // Later calls aren't affected by the exception.
+            Assert.Equal(1u, IntegrationTests.TotalSize(new InMemoryLoader(), new List<string>() { "a" }));
+        }
+
+        [Fact]
+        public void StoredObjectStaysAlive()
+        {
+            var loader = new InMemoryLoader();
+            using (var cache = new AssetCache(loader))
+            {
+                loader = null;
+
+                // The loader is only referenced by Rust, so it has to survive a collection.
+                GC.Collect();
+                GC.WaitForPendingFinalizers();
+
+                Assert.Equal(3, cache.Get("abc").Count);
+                Assert.Equal(3, cache.Get("abc").Count);
+                Assert.Equal("memory", cache.LoaderName());
+            }
+        }
+    }
+}
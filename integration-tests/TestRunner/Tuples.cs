@@ -0,0 +1,74 @@
+using System;
+using Bindings;
+using Bindings.Collections;
+using Bindings.SimpleEnum;
+using Bindings.Tuple;
+using Xunit;
+
+namespace TestRunner
+{
+    public class Tuples
+    {
+        [Fact]
+        public void SwapPair()
+        {
+            Assert.Equal(("Cool string", 7), IntegrationTests.SwapPair((7, "Cool string")));
+        }
+
+        [Fact]
+        public void SwapPairManyTimes()
+        {
+            for (var count = 0; count < 100_000; count += 1)
+            {
+                SwapPair();
+            }
+        }
+
+        [Fact]
+        public void SumTriple()
+        {
+            Assert.Equal(6, IntegrationTests.SumTriple((1, 2, 3)));
+        }
+
+        [Fact]
+        public void SingleElement()
+        {
+            var original = new ValueTuple<ulong>(123);
+            Assert.Equal(original, IntegrationTests.RoundTripSingle(original));
+        }
+
+        [Fact]
+        public void ExportedTypes()
+        {
+            var original = (new CopyStruct(33), SimpleCEnum.Baz, true);
+            Assert.Equal(original, IntegrationTests.RoundTripExportedTypes(original));
+        }
+
+        [Fact]
+        public void OptionalPair()
+        {
+            Assert.Equal((1, 2.5f), IntegrationTests.RoundTripOptionalPair((1, 2.5f)));
+            Assert.Null(IntegrationTests.RoundTripOptionalPair(null));
+        }
+
+        [Fact]
+        public void TwelveElements()
+        {
+            var result = IntegrationTests.CountToTwelve();
+            Assert.Equal(1, result.Item1);
+            Assert.Equal(8, result.Item8);
+            Assert.Equal(10.0, result.Item10);
+            Assert.True(result.Item11);
+            Assert.Equal("12", result.Item12);
+        }
+
+        [Fact]
+        public void StructWithTupleField()
+        {
+            var original = new StructWithTupleField((1.5f, -2f), 7);
+            var result = IntegrationTests.RoundTripStructWithTupleField(original);
+            Assert.Equal(original, result);
+            Assert.Equal((1.5f, -2f), result.Position);
+        }
+    }
+}
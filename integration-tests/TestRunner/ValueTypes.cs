@@ -1,3 +1,5 @@
+using Bindings;
+using Bindings.CopyTypes;
 using Xunit;
 
 namespace TestRunner
@@ -16,5 +18,21 @@ namespace TestRunner
             Assert.Equal(tile.Suit, result.Suit);
             Assert.Equal(tile.Value, result.Value);
         }
+
+        [Fact]
+        public void StructMethods()
+        {
+            var tile = SimpleTile.Man(9);
+            Assert.Equal(Suit.Man, tile.Suit);
+            Assert.True(tile.IsTerminal());
+
+            tile.SetValue(5);
+            Assert.Equal(5, tile.Value);
+            Assert.False(tile.IsTerminal());
+
+            var next = tile.Next();
+            Assert.Equal(6, next.Value);
+            Assert.Equal(5, tile.Value);
+        }
     }
 }
@@ -36,6 +36,8 @@ fn main() {
         .arg(wasm_module_path)
         .arg("-o")
         .arg(&bindings_path)
+        .arg("--namespace=Bindings")
+        .arg("--module-namespaces")
         .spawn()
         .expect("Failed to spawn cs-bindgen process");
 
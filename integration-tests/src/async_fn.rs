@@ -0,0 +1,140 @@
+//! Tests verifying that `async` functions and methods are exposed as functions
+//! returning a `Task`.
+
+use cs_bindgen::prelude::*;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::mpsc,
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+/// A future that completes on a background thread after `duration` has elapsed.
+struct Delay {
+    receiver: Option<mpsc::Receiver<()>>,
+    duration: Duration,
+}
+
+fn delay(millis: u64) -> Delay {
+    Delay {
+        receiver: None,
+        duration: Duration::from_millis(millis),
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match &self.receiver {
+            Some(receiver) => match receiver.try_recv() {
+                Err(mpsc::TryRecvError::Empty) => Poll::Pending,
+                _ => Poll::Ready(()),
+            },
+
+            None => {
+                let (sender, receiver) = mpsc::channel();
+                let waker = cx.waker().clone();
+                let duration = self.duration;
+                thread::spawn(move || {
+                    thread::sleep(duration);
+                    let _ = sender.send(());
+                    waker.wake();
+                });
+
+                self.receiver = Some(receiver);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cs_bindgen]
+pub async fn add_async(a: i32, b: i32) -> i32 {
+    delay(10).await;
+    a + b
+}
+
+#[cs_bindgen]
+pub async fn wait(millis: u64) {
+    delay(millis).await;
+}
+
+/// Fetches the contents of `url`.
+///
+/// # Returns
+///
+/// The bytes of the response.
+#[cs_bindgen]
+pub async fn fetch(url: String) -> Result<Vec<u8>, String> {
+    delay(10).await;
+    if url.is_empty() {
+        return Err("URL must not be empty".into());
+    }
+
+    Ok(url.into_bytes())
+}
+
+#[cs_bindgen]
+pub async fn panic_async(message: String) -> String {
+    delay(10).await;
+    panic!("{}", message);
+}
+
+#[cs_bindgen]
+pub struct Connection {
+    address: String,
+}
+
+#[cs_bindgen]
+impl Connection {
+    pub fn new(address: String) -> Self {
+        Self { address }
+    }
+
+    pub async fn send(&self, message: String) -> String {
+        delay(10).await;
+        format!("{} <- {}", self.address, message)
+    }
+
+    /// Waits for `millis` milliseconds before returning the address, which keeps the
+    /// connection borrowed for at least that long.
+    pub async fn ping(&self, millis: u64) -> String {
+        delay(millis).await;
+        self.address.clone()
+    }
+
+    pub fn reconnect(&mut self, address: String) {
+        self.address = address;
+    }
+
+    pub fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    pub async fn close(self) -> String {
+        delay(10).await;
+        format!("closed {}", self.address)
+    }
+
+    pub async fn connect(address: String) -> Connection {
+        delay(10).await;
+        Connection { address }
+    }
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Latency {
+    pub millis: u64,
+}
+
+#[cs_bindgen]
+impl Latency {
+    pub async fn measure(millis: u64) -> Latency {
+        delay(millis).await;
+        Latency { millis }
+    }
+}
@@ -0,0 +1,49 @@
+//! Tests verifying that C# delegates can be passed to Rust as callbacks.
+
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen]
+pub fn apply_twice(value: i32, callback: Box<dyn Fn(i32) -> i32>) -> i32 {
+    callback(callback(value))
+}
+
+#[cs_bindgen]
+pub fn greet_all(names: Vec<String>, greet: impl Fn(String) -> String) -> Vec<String> {
+    names.into_iter().map(greet).collect()
+}
+
+#[cs_bindgen]
+pub fn count_to(count: u32, mut visit: impl FnMut(u32)) {
+    for value in 1..=count {
+        visit(value);
+    }
+}
+
+/// Stores callbacks so that they can be invoked after the call that registered them
+/// has returned.
+#[cs_bindgen]
+#[derive(Default)]
+pub struct EventSource {
+    listeners: Vec<Box<dyn Fn(u32) + Send + Sync>>,
+}
+
+#[cs_bindgen]
+impl EventSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&mut self, listener: Box<dyn Fn(u32) + Send + Sync>) {
+        self.listeners.push(listener);
+    }
+
+    pub fn emit(&self, value: u32) {
+        for listener in &self.listeners {
+            listener(value);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.listeners.clear();
+    }
+}
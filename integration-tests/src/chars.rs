@@ -0,0 +1,29 @@
+//! Tests verifying that `char` is exposed to C# as `RustChar`, and can represent
+//! characters outside of the Basic Multilingual Plane.
+
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen]
+pub fn round_trip_char(value: char) -> char {
+    value
+}
+
+#[cs_bindgen]
+pub fn char_to_string(value: char) -> String {
+    value.to_string()
+}
+
+#[cs_bindgen]
+pub fn string_to_chars(value: String) -> Vec<char> {
+    value.chars().collect()
+}
+
+#[cs_bindgen]
+pub fn round_trip_char_vec(value: Vec<char>) -> Vec<char> {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_optional_char(value: Option<char>) -> Option<char> {
+    value
+}
@@ -2,6 +2,7 @@
 
 use crate::{data_enum::DataEnum, simple_enum::SimpleCEnum};
 use cs_bindgen::prelude::*;
+use std::collections::{BTreeMap, HashMap};
 
 #[cs_bindgen]
 pub fn return_vec_i8() -> Vec<i8> {
@@ -58,6 +59,11 @@ pub fn return_vec_bool() -> Vec<bool> {
     vec![true, false, true, true]
 }
 
+#[cs_bindgen]
+pub fn round_trip_string_vec(val: Vec<String>) -> Vec<String> {
+    val
+}
+
 #[cs_bindgen]
 #[derive(Debug, Clone, Copy)]
 pub struct CopyStruct {
@@ -84,3 +90,89 @@ pub fn round_trip_data_enum_vec(val: Vec<DataEnum>) -> Vec<DataEnum> {
 pub enum ValueTypeWithCollection {
     Foo { values: Vec<u32> },
 }
+
+#[cs_bindgen]
+pub fn round_trip_hash_map(val: HashMap<String, i32>) -> HashMap<String, i32> {
+    val
+}
+
+#[cs_bindgen]
+pub fn round_trip_btree_map(val: BTreeMap<u32, String>) -> BTreeMap<u32, String> {
+    val
+}
+
+#[cs_bindgen]
+pub fn return_hash_map() -> HashMap<String, i32> {
+    let mut map = HashMap::new();
+    map.insert("foo".into(), 1);
+    map.insert("bar".into(), 2);
+    map.insert("baz".into(), 3);
+    map
+}
+
+#[cs_bindgen]
+pub fn round_trip_named_map(
+    val: HashMap<SimpleCEnum, CopyStruct>,
+) -> HashMap<SimpleCEnum, CopyStruct> {
+    val
+}
+
+#[cs_bindgen]
+pub fn identity_matrix() -> [f32; 16] {
+    let mut matrix = [0.0; 16];
+    for index in 0..4 {
+        matrix[index * 5] = 1.0;
+    }
+    matrix
+}
+
+#[cs_bindgen]
+pub fn sum_array(values: [i32; 64]) -> i32 {
+    values.iter().sum()
+}
+
+#[cs_bindgen]
+pub fn round_trip_string_array(values: [String; 3]) -> [String; 3] {
+    values
+}
+
+#[cs_bindgen]
+pub fn round_trip_struct_array(values: [CopyStruct; 2]) -> [CopyStruct; 2] {
+    values
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub matrix: [f32; 16],
+    pub enabled: [bool; 2],
+}
+
+#[cs_bindgen]
+pub fn round_trip_transform(value: Transform) -> Transform {
+    value
+}
+
+#[cs_bindgen]
+pub fn sum_slice(values: &[i32]) -> i32 {
+    values.iter().sum()
+}
+
+#[cs_bindgen]
+pub fn sum_vec(values: Vec<i64>) -> i64 {
+    values.iter().sum()
+}
+
+#[cs_bindgen]
+pub fn scale_samples(samples: &mut [f32], gain: f32) {
+    for sample in samples {
+        *sample *= gain;
+    }
+}
+
+#[cs_bindgen]
+pub fn copy_bytes(source: &[u8], dest: &mut [u8]) -> usize {
+    let len = source.len().min(dest.len());
+    dest[..len].copy_from_slice(&source[..len]);
+    len
+}
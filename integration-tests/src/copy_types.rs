@@ -21,3 +21,28 @@ pub enum Suit {
 pub fn roundtrip_simple_tile(tile: SimpleTile) -> SimpleTile {
     tile
 }
+
+#[cs_bindgen]
+impl SimpleTile {
+    pub fn man(value: u8) -> Self {
+        Self {
+            suit: Suit::Man,
+            value,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.value == 1 || self.value == 9
+    }
+
+    pub fn set_value(&mut self, value: u8) {
+        self.value = value;
+    }
+
+    pub fn next(self) -> Self {
+        Self {
+            value: self.value % 9 + 1,
+            ..self
+        }
+    }
+}
@@ -11,6 +11,30 @@ pub enum DataEnum {
     NestedStruct(InnerStruct),
 }
 
+#[cs_bindgen]
+impl DataEnum {
+    pub fn from_name(name: String) -> DataEnum {
+        DataEnum::Bar(name)
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            DataEnum::Foo => "Foo".into(),
+            DataEnum::Bar(value) => format!("Bar({})", value),
+            DataEnum::Baz { name, value } => format!("Baz({}, {})", name, value),
+            DataEnum::Coolness(_) => "Coolness".into(),
+            DataEnum::NestedStruct(inner) => format!("NestedStruct({})", inner.value),
+        }
+    }
+
+    pub fn rename(&mut self, new_name: String) {
+        match self {
+            DataEnum::Bar(name) | DataEnum::Baz { name, .. } => *name = new_name,
+            _ => {}
+        }
+    }
+}
+
 #[cs_bindgen]
 #[derive(Debug, Clone)]
 pub enum InnerEnum {
@@ -0,0 +1,131 @@
+//! Tests verifying that doc comments are carried over into the generated bindings.
+//!
+//! The doc comments themselves aren't visible at runtime, so the C# tests only verify
+//! that the generated bindings still compile and behave correctly when items are
+//! documented.
+
+use cs_bindgen::prelude::*;
+
+/// A temperature reading from a sensor.
+///
+/// Readings are stored in degrees Celsius. Use `fahrenheit` to convert a reading for
+/// display in the US.
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Reading {
+    /// The temperature in degrees Celsius.
+    pub celsius: f32,
+
+    /// The ID of the sensor that took the reading.
+    pub sensor: u32,
+}
+
+#[cs_bindgen]
+impl Reading {
+    /// Converts the reading to degrees Fahrenheit.
+    ///
+    /// # Returns
+    ///
+    /// The temperature in degrees Fahrenheit.
+    pub fn fahrenheit(&self) -> f32 {
+        self.celsius * 9.0 / 5.0 + 32.0
+    }
+}
+
+/// How a reading compares to the threshold for an alert.
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// The reading is below the threshold.
+    Normal,
+
+    /// The reading is above the threshold, so an alert should be raised.
+    ///
+    /// Note that `*/` and `<tags>` in doc comments are escaped.
+    Alert,
+}
+
+/// The result of checking a reading.
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub enum Check {
+    /// The reading is within the expected range.
+    Ok,
+
+    /// The reading is out of range by `amount` degrees.
+    OutOfRange {
+        /// The number of degrees outside of the expected range.
+        amount: f32,
+    },
+}
+
+/// A collection of readings, which can be summarized as a "report".
+#[cs_bindgen]
+#[derive(Default)]
+pub struct Log {
+    readings: Vec<Reading>,
+}
+
+#[cs_bindgen]
+impl Log {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self {
+            readings: Vec::new(),
+        }
+    }
+
+    /// Adds a reading to the log.
+    pub fn record(
+        &mut self,
+        /// The reading to add.
+        reading: Reading,
+    ) {
+        self.readings.push(reading);
+    }
+
+    /// The number of readings in the log.
+    #[cs_bindgen(getter)]
+    pub fn count(&self) -> u32 {
+        self.readings.len() as u32
+    }
+}
+
+/// Determines the alert level for a reading.
+///
+/// ```text
+/// let level = classify(reading, 30.0);
+/// ```
+///
+/// # Returns
+///
+/// `Level::Alert` if the reading is above `threshold`, otherwise `Level::Normal`.
+#[cs_bindgen]
+pub fn classify(
+    /// The reading to classify.
+    reading: Reading,
+    /// The highest temperature that doesn't raise an alert, in degrees Celsius.
+    threshold: f32,
+) -> Level {
+    if reading.celsius > threshold {
+        Level::Alert
+    } else {
+        Level::Normal
+    }
+}
+
+/// Checks that a reading is within `min` and `max`, inclusive.
+#[cs_bindgen]
+pub fn check_range(reading: Reading, min: f32, max: f32) -> Check {
+    if reading.celsius < min {
+        Check::OutOfRange {
+            amount: min - reading.celsius,
+        }
+    } else if reading.celsius > max {
+        Check::OutOfRange {
+            amount: reading.celsius - max,
+        }
+    } else {
+        Check::Ok
+    }
+}
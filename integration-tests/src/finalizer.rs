@@ -0,0 +1,40 @@
+//! Tests verifying that handle types are dropped when the C# object is garbage
+//! collected without being disposed.
+
+use cs_bindgen::prelude::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The number of `Tracked` values that haven't been dropped yet.
+static LIVE: AtomicU32 = AtomicU32::new(0);
+
+#[cs_bindgen]
+pub struct Tracked {
+    id: u32,
+}
+
+#[cs_bindgen]
+impl Tracked {
+    pub fn new(id: u32) -> Self {
+        LIVE.fetch_add(1, Ordering::SeqCst);
+        Self { id }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn same_id(&self, other: &Tracked) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        LIVE.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cs_bindgen]
+pub fn live_tracked() -> u32 {
+    LIVE.load(Ordering::SeqCst)
+}
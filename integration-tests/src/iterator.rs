@@ -0,0 +1,61 @@
+//! Tests verifying that functions returning iterators are exposed as functions
+//! returning an `IEnumerable`.
+
+use cs_bindgen::prelude::*;
+use std::collections::BTreeMap;
+
+#[cs_bindgen]
+pub fn range(start: i32, end: i32) -> impl Iterator<Item = i32> {
+    start..end
+}
+
+#[cs_bindgen]
+pub fn naturals() -> impl Iterator<Item = u64> {
+    1..
+}
+
+#[cs_bindgen(iterator)]
+pub fn split_words(text: String) -> std::vec::IntoIter<String> {
+    text.split_whitespace()
+        .map(Into::into)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+#[cs_bindgen]
+pub fn panic_after(count: u32, message: String) -> impl Iterator<Item = u32> {
+    (0..).inspect(move |&index| {
+        if index == count {
+            panic!("{}", message);
+        }
+    })
+}
+
+#[cs_bindgen]
+#[derive(Default)]
+pub struct Catalog {
+    items: BTreeMap<String, u32>,
+}
+
+#[cs_bindgen]
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, name: String, count: u32) {
+        *self.items.entry(name).or_default() += count;
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = String> {
+        self.items.keys().cloned().collect::<Vec<_>>().into_iter()
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = (String, u32)> {
+        self.items
+            .iter()
+            .map(|(name, count)| (name.clone(), *count))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
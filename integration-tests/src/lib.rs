@@ -1,11 +1,26 @@
+pub mod async_fn;
+pub mod callback;
+pub mod chars;
 pub mod collections;
 pub mod copy_types;
 pub mod data_enum;
+pub mod docs;
+pub mod finalizer;
 pub mod function;
+pub mod iterator;
 pub mod method;
 pub mod name_collision;
+pub mod option;
+pub mod panic;
+pub mod property;
+pub mod rename;
+pub mod result;
 pub mod simple_enum;
 pub mod structs;
+pub mod thread_affinity;
+pub mod trait_impl;
+pub mod trait_object;
+pub mod tuple;
 
 // Re-export core cs_bindgen functionality. Required in order for the generated Wasm module.
 cs_bindgen::export!();
@@ -12,8 +12,7 @@ pub struct PersonInfo {
 // getters, setters, and methods that operate on the internal state of the object.
 #[cs_bindgen]
 impl PersonInfo {
-    // TODO: Change the return type back to `Self` once that's supported.
-    pub fn new(name: String, age: i32) -> PersonInfo {
+    pub fn new(name: String, age: i32) -> Self {
         Self {
             name,
             age,
@@ -24,9 +23,28 @@ impl PersonInfo {
         }
     }
 
-    // TODO: Change this to return `&str` once that's supported.
-    pub fn name(&self) -> String {
-        self.name.clone()
+    // A second constructor, which needs to be marked explicitly since the default is
+    // based on the return type alone.
+    #[cs_bindgen(constructor)]
+    pub fn with_address(name: String, age: i32, street_number: u32, street: String) -> Self {
+        Self {
+            name,
+            age,
+            address: Address {
+                street_number,
+                street,
+            },
+        }
+    }
+
+    // Exported as a static `PersonInfo.Newborn()` method rather than a constructor.
+    #[cs_bindgen(factory)]
+    pub fn newborn(name: String) -> Self {
+        Self::new(name, 0)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     pub fn age(&self) -> i32 {
@@ -45,10 +63,30 @@ impl PersonInfo {
         self.address.clone()
     }
 
+    pub fn address_ref(&self) -> &Address {
+        &self.address
+    }
+
     pub fn is_minor(&self) -> bool {
         self.age < 21
     }
 
+    pub fn is_older_than(&self, other: &Self) -> bool {
+        self.age > other.age
+    }
+
+    pub fn copy_age_from(&mut self, other: &Self) {
+        self.age = other.age;
+    }
+
+    pub fn into_name(self) -> String {
+        self.name
+    }
+
+    pub fn move_to(self, address: Address) -> PersonInfo {
+        PersonInfo { address, ..self }
+    }
+
     #[allow(bad_style)]
     pub fn arg_name_test(
         simple: bool,
@@ -61,6 +99,12 @@ impl PersonInfo {
     }
 }
 
+// Takes ownership of the person, after which the C# object can no longer be used.
+#[cs_bindgen]
+pub fn consume_person(person: PersonInfo) -> i32 {
+    person.age
+}
+
 #[cs_bindgen]
 #[derive(Debug, Clone)]
 pub struct Address {
@@ -74,8 +118,53 @@ impl Address {
         self.street_number
     }
 
-    // TODO: Change this to return `&str` once that's supported.
-    pub fn street_name(&self) -> String {
-        self.street.clone()
+    pub fn street_name(&self) -> &str {
+        &self.street
     }
 }
+
+// Methods returning slices borrowed from `self`. The elements are copied into a
+// list on the C# side without cloning the data in Rust.
+#[cs_bindgen]
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    counts: Vec<i32>,
+    flags: Vec<bool>,
+    labels: Vec<String>,
+}
+
+#[cs_bindgen]
+impl Inventory {
+    pub fn sample() -> Self {
+        Self {
+            counts: vec![1, 2, 3, 5, 8],
+            flags: vec![true, false, true],
+            labels: vec!["apple".into(), "banana".into(), "🍒".into()],
+        }
+    }
+
+    pub fn counts(&self) -> &[i32] {
+        &self.counts
+    }
+
+    pub fn flags(&self) -> &[bool] {
+        &self.flags
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn first_label(&self) -> &str {
+        &self.labels[0]
+    }
+
+    pub fn empty(&self) -> &[i32] {
+        &self.counts[..0]
+    }
+}
+
+#[cs_bindgen]
+pub fn consume_address(address: Address) -> u32 {
+    address.street_number
+}
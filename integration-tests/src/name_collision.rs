@@ -13,3 +13,88 @@ pub struct Test {
 pub enum TestEnum {
     Test(Test),
 }
+
+/// Types and functions with the same names as the ones in [`second`], in order to test
+/// that exports with the same name in different modules don't conflict.
+///
+/// [`second`]: ../second/index.html
+pub mod first {
+    use cs_bindgen::prelude::*;
+
+    #[cs_bindgen]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    #[cs_bindgen]
+    #[derive(Debug, Clone, Default)]
+    pub struct Counter {
+        count: u32,
+    }
+
+    #[cs_bindgen]
+    impl Counter {
+        pub fn new() -> Self {
+            Default::default()
+        }
+
+        pub fn increment(&mut self) -> u32 {
+            self.count += 1;
+            self.count
+        }
+    }
+
+    #[cs_bindgen]
+    pub fn describe_point(point: Point) -> String {
+        format!("first ({}, {})", point.x, point.y)
+    }
+
+    #[cs_bindgen]
+    pub fn reverse_points(mut points: Vec<Point>) -> Vec<Point> {
+        points.reverse();
+        points
+    }
+}
+
+pub mod second {
+    use cs_bindgen::prelude::*;
+
+    #[cs_bindgen]
+    #[derive(Debug, Clone, Copy)]
+    pub struct Point {
+        pub x: f32,
+        pub y: f32,
+        pub z: f32,
+    }
+
+    #[cs_bindgen]
+    #[derive(Debug, Clone, Default)]
+    pub struct Counter {
+        count: u32,
+    }
+
+    #[cs_bindgen]
+    impl Counter {
+        pub fn new() -> Self {
+            Default::default()
+        }
+
+        pub fn increment(&mut self) -> u32 {
+            self.count += 2;
+            self.count
+        }
+    }
+
+    #[cs_bindgen]
+    pub fn describe_point(point: Point) -> String {
+        format!("second ({}, {}, {})", point.x, point.y, point.z)
+    }
+
+    #[cs_bindgen]
+    pub fn reverse_points(mut points: Vec<Point>) -> Vec<Point> {
+        points.reverse();
+        points
+    }
+}
@@ -0,0 +1,63 @@
+//! Tests verifying that optional values are exposed to C# as nullable types.
+
+use crate::{collections::CopyStruct, data_enum::DataEnum, simple_enum::SimpleCEnum};
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen]
+pub fn round_trip_option_i32(value: Option<i32>) -> Option<i32> {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_option_bool(value: Option<bool>) -> Option<bool> {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_option_string(value: Option<String>) -> Option<String> {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_option_copy_struct(value: Option<CopyStruct>) -> Option<CopyStruct> {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_option_simple_enum(value: Option<SimpleCEnum>) -> Option<SimpleCEnum> {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_option_data_enum(value: Option<DataEnum>) -> Option<DataEnum> {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_option_vec(value: Option<Vec<i32>>) -> Option<Vec<i32>> {
+    value
+}
+
+// NOTE: This isn't `Copy`, so it's exported as a handle. The constructor and
+// accessors expose the optional fields in C#.
+#[cs_bindgen]
+#[derive(Debug, Clone)]
+pub struct StructWithOptionalFields {
+    pub count: Option<u32>,
+    pub name: Option<String>,
+}
+
+#[cs_bindgen]
+impl StructWithOptionalFields {
+    pub fn new(count: Option<u32>, name: Option<String>) -> StructWithOptionalFields {
+        Self { count, name }
+    }
+
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+}
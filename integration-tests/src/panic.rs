@@ -0,0 +1,43 @@
+//! Tests verifying that Rust panics are caught at the FFI boundary and rethrown as
+//! exceptions in C#.
+
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen]
+pub fn panic_if(value: i32) -> i32 {
+    if value < 0 {
+        panic!("Value was negative: {}", value);
+    }
+
+    value
+}
+
+#[cs_bindgen]
+pub fn panic_with_string(message: String) -> String {
+    panic!("{}", message);
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone)]
+pub struct PanicOnDrop {
+    message: String,
+}
+
+#[cs_bindgen]
+impl PanicOnDrop {
+    pub fn new(message: String) -> PanicOnDrop {
+        PanicOnDrop { message }
+    }
+
+    pub fn panic_now(&self) {
+        panic!("{}", self.message);
+    }
+}
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            panic!("Dropped: {}", self.message);
+        }
+    }
+}
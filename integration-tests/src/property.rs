@@ -0,0 +1,64 @@
+//! Tests verifying that getter and setter methods are exposed to C# as properties.
+
+use cs_bindgen::prelude::*;
+use std::sync::atomic::{AtomicI32, Ordering};
+
+static DEFAULT_TARGET: AtomicI32 = AtomicI32::new(20);
+
+#[cs_bindgen]
+#[derive(Debug, Clone)]
+pub struct Thermostat {
+    target: i32,
+    current: i32,
+    label: String,
+}
+
+#[cs_bindgen]
+impl Thermostat {
+    pub fn with_label(label: String) -> Self {
+        Self {
+            target: DEFAULT_TARGET.load(Ordering::SeqCst),
+            current: 15,
+            label,
+        }
+    }
+
+    #[cs_bindgen(getter)]
+    pub fn target(&self) -> i32 {
+        self.target
+    }
+
+    #[cs_bindgen(setter)]
+    pub fn set_target(&mut self, target: i32) {
+        self.target = target;
+    }
+
+    // The getter returns a borrowed string while the setter takes an owned one, both
+    // of which are exposed as a `string` property.
+    #[cs_bindgen(getter)]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    #[cs_bindgen(setter)]
+    pub fn set_label(&mut self, label: String) {
+        self.label = label;
+    }
+
+    // A read-only property.
+    #[cs_bindgen(getter)]
+    pub fn is_heating(&self) -> bool {
+        self.current < self.target
+    }
+
+    // A static property.
+    #[cs_bindgen(getter)]
+    pub fn get_default_target() -> i32 {
+        DEFAULT_TARGET.load(Ordering::SeqCst)
+    }
+
+    #[cs_bindgen(setter)]
+    pub fn set_default_target(target: i32) {
+        DEFAULT_TARGET.store(target, Ordering::SeqCst);
+    }
+}
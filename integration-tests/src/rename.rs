@@ -0,0 +1,85 @@
+//! Tests verifying that `#[cs_bindgen(rename = "...")]` overrides the names used in the
+//! generated bindings.
+
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen(rename = "Vector2")]
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    #[cs_bindgen(rename = "Horizontal")]
+    pub x: f32,
+
+    #[cs_bindgen(rename = "Vertical")]
+    pub y: f32,
+}
+
+#[cs_bindgen(rename = "Heading")]
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    #[cs_bindgen(rename = "Up")]
+    North,
+
+    #[cs_bindgen(rename = "Down")]
+    South,
+}
+
+#[cs_bindgen(rename = "Move")]
+#[derive(Debug, Clone, Copy)]
+pub enum Movement {
+    #[cs_bindgen(rename = "Step")]
+    Walk(#[cs_bindgen(rename = "Distance")] f32),
+
+    Turn {
+        #[cs_bindgen(rename = "Degrees")]
+        angle: f32,
+    },
+}
+
+#[cs_bindgen(rename = "Tally")]
+#[derive(Default)]
+pub struct Counter {
+    count: u32,
+}
+
+#[cs_bindgen]
+impl Counter {
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    #[cs_bindgen(rename = "Bump")]
+    pub fn increment(&mut self, #[cs_bindgen(rename = "step")] amount: u32) -> u32 {
+        self.count += amount;
+        self.count
+    }
+
+    #[cs_bindgen(getter, rename = "Total")]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+#[cs_bindgen(rename = "Flip")]
+pub fn flip_direction(#[cs_bindgen(rename = "heading")] direction: Direction) -> Direction {
+    match direction {
+        Direction::North => Direction::South,
+        Direction::South => Direction::North,
+    }
+}
+
+#[cs_bindgen]
+pub fn apply_movement(point: Point, movement: Movement) -> Point {
+    match movement {
+        Movement::Walk(distance) => Point {
+            x: point.x,
+            y: point.y + distance,
+        },
+        Movement::Turn { angle } => {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            Point {
+                x: point.x * cos - point.y * sin,
+                y: point.x * sin + point.y * cos,
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Tests verifying that functions returning `Result` throw an exception in C# when
+//! they return an error.
+
+use crate::{data_enum::DataEnum, simple_enum::SimpleCEnum};
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen]
+pub fn parse_int(value: String) -> Result<i32, String> {
+    value.parse().map_err(|_| format!("Invalid integer: {:?}", value))
+}
+
+#[cs_bindgen(try_pattern)]
+pub fn parse_float(value: String) -> Result<f32, String> {
+    value.parse().map_err(|_| format!("Invalid float: {:?}", value))
+}
+
+#[cs_bindgen(try_pattern)]
+pub fn check_flag(fail: bool) -> Result<(), SimpleCEnum> {
+    if fail {
+        Err(SimpleCEnum::Bar)
+    } else {
+        Ok(())
+    }
+}
+
+#[cs_bindgen]
+pub fn fail_with_simple_enum(fail: bool) -> Result<i32, SimpleCEnum> {
+    if fail {
+        Err(SimpleCEnum::Baz)
+    } else {
+        Ok(7)
+    }
+}
+
+#[cs_bindgen]
+pub fn fail_with_data_enum(fail: bool) -> Result<String, DataEnum> {
+    if fail {
+        Err(DataEnum::Bar("Cool error".into()))
+    } else {
+        Ok("Success".into())
+    }
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorCode {
+    pub code: i32,
+}
+
+#[cs_bindgen]
+pub fn fail_with_struct(code: i32) -> Result<(), ErrorCode> {
+    if code != 0 {
+        Err(ErrorCode { code })
+    } else {
+        Ok(())
+    }
+}
+
+// Error types that aren't exported or strings are thrown as a `RustException<T>`.
+#[cs_bindgen]
+pub fn checked_index(values: Vec<i32>, index: u32) -> Result<i32, u32> {
+    values.get(index as usize).copied().ok_or(values.len() as u32)
+}
+
+#[cs_bindgen]
+pub struct Validator {
+    max: i32,
+}
+
+#[cs_bindgen]
+impl Validator {
+    pub fn new(max: i32) -> Validator {
+        Validator { max }
+    }
+
+    pub fn validate(&self, value: i32) -> Result<i32, String> {
+        if value <= self.max {
+            Ok(value)
+        } else {
+            Err(format!("{} is larger than {}", value, self.max))
+        }
+    }
+
+    #[cs_bindgen(try_pattern)]
+    pub fn checked_validate(&self, value: i32) -> Result<i32, String> {
+        self.validate(value)
+    }
+}
@@ -29,3 +29,22 @@ pub fn roundtrip_simple_enum_with_discriminants(
 ) -> EnumWithDiscriminants {
     val
 }
+
+#[cs_bindgen]
+impl SimpleCEnum {
+    pub fn first() -> SimpleCEnum {
+        SimpleCEnum::Foo
+    }
+
+    pub fn index(&self) -> u32 {
+        *self as u32
+    }
+
+    pub fn cycle(&mut self) {
+        *self = match self {
+            SimpleCEnum::Foo => SimpleCEnum::Bar,
+            SimpleCEnum::Bar => SimpleCEnum::Baz,
+            SimpleCEnum::Baz => SimpleCEnum::Foo,
+        };
+    }
+}
@@ -0,0 +1,70 @@
+//! Tests verifying that handle types that aren't `Send` can only be used from the
+//! thread that created them, and that calls on types that aren't `Sync` are serialized.
+
+use cs_bindgen::prelude::*;
+use std::{cell::Cell, rc::Rc, thread};
+
+/// A counter that shares its count through an `Rc`, and so isn't `Send`.
+#[cs_bindgen]
+pub struct LocalCounter {
+    count: Rc<Cell<u32>>,
+}
+
+#[cs_bindgen]
+impl LocalCounter {
+    pub fn new(start: u32) -> Self {
+        Self {
+            count: Rc::new(Cell::new(start)),
+        }
+    }
+
+    pub fn increment(&self) -> u32 {
+        self.count.set(self.count.get() + 1);
+        self.count.get()
+    }
+}
+
+/// A counter that stores its count in a `Cell`, and so is `Send` but not `Sync`.
+#[cs_bindgen]
+pub struct UnsyncedCounter {
+    count: Cell<u32>,
+}
+
+#[cs_bindgen]
+impl UnsyncedCounter {
+    pub fn new(start: u32) -> Self {
+        Self {
+            count: Cell::new(start),
+        }
+    }
+
+    /// Increments the count, yielding between the read and the write so that
+    /// concurrent calls would lose increments if they weren't serialized.
+    pub fn increment(&self) -> u32 {
+        let count = self.count.get();
+        thread::yield_now();
+        self.count.set(count + 1);
+        count + 1
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count.get()
+    }
+}
+
+/// A counter that is both `Send` and `Sync`.
+#[cs_bindgen]
+pub struct SharedCounter {
+    count: u32,
+}
+
+#[cs_bindgen]
+impl SharedCounter {
+    pub fn new(start: u32) -> Self {
+        Self { count: start }
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
@@ -0,0 +1,82 @@
+//! Tests verifying that handle types implementing an exported trait implement the
+//! C# interface generated for the trait.
+
+use cs_bindgen::prelude::*;
+
+/// A shape with a known area.
+#[cs_bindgen]
+pub trait Shape {
+    /// Calculates the area of the shape.
+    fn area(&self) -> f64;
+
+    #[cs_bindgen(rename = "Describe")]
+    fn name(&self) -> String;
+
+    fn scale(&mut self, factor: f64);
+}
+
+#[cs_bindgen]
+pub struct Circle {
+    radius: f64,
+}
+
+#[cs_bindgen]
+impl Circle {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+#[cs_bindgen]
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn name(&self) -> String {
+        "circle".into()
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.radius *= factor;
+    }
+}
+
+#[cs_bindgen]
+pub struct Rectangle {
+    width: f64,
+    height: f64,
+}
+
+#[cs_bindgen]
+impl Rectangle {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+
+    /// Inherent method with the same name as a trait method, which must not be
+    /// confused with the trait's `name`.
+    pub fn name(&self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+}
+
+#[cs_bindgen]
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    fn name(&self) -> String {
+        "rectangle".into()
+    }
+
+    fn scale(&mut self, factor: f64) {
+        self.width *= factor;
+        self.height *= factor;
+    }
+}
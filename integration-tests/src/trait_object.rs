@@ -0,0 +1,65 @@
+//! Tests verifying that C# objects can be passed to Rust as implementations of an
+//! exported trait.
+
+use cs_bindgen::prelude::*;
+use std::collections::HashMap;
+
+/// Loads the contents of assets.
+#[cs_bindgen]
+pub trait AssetLoader {
+    /// Loads the contents of the asset at `path`.
+    fn load(&self, path: &str) -> Vec<u8>;
+
+    fn name(&self) -> String;
+}
+
+#[cs_bindgen]
+pub fn total_size(loader: Box<dyn AssetLoader>, paths: Vec<String>) -> u32 {
+    paths
+        .iter()
+        .map(|path| loader.load(path).len() as u32)
+        .sum()
+}
+
+/// Caches the assets loaded by a loader implemented in C#.
+#[cs_bindgen]
+pub struct AssetCache {
+    loader: Box<dyn AssetLoader + Send + Sync>,
+    assets: HashMap<String, Vec<u8>>,
+}
+
+#[cs_bindgen]
+impl AssetCache {
+    pub fn new(loader: Box<dyn AssetLoader + Send + Sync>) -> Self {
+        Self {
+            loader,
+            assets: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, path: String) -> Vec<u8> {
+        if let Some(asset) = self.assets.get(&path) {
+            return asset.clone();
+        }
+
+        let asset = self.loader.load(&path);
+        self.assets.insert(path, asset.clone());
+        asset
+    }
+
+    pub fn loader_name(&self) -> String {
+        self.loader.name()
+    }
+}
+
+#[cs_bindgen]
+pub trait Visitor {
+    fn visit(&mut self, value: i32);
+}
+
+#[cs_bindgen]
+pub fn visit_range(mut visitor: Box<dyn Visitor>, start: i32, end: i32) {
+    for value in start..end {
+        visitor.visit(value);
+    }
+}
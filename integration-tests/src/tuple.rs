@@ -0,0 +1,49 @@
+//! Tests verifying that tuples are exposed to C# as `ValueTuple`.
+
+use crate::{collections::CopyStruct, simple_enum::SimpleCEnum};
+use cs_bindgen::prelude::*;
+
+#[cs_bindgen]
+pub fn swap_pair(pair: (i32, String)) -> (String, i32) {
+    (pair.1, pair.0)
+}
+
+#[cs_bindgen]
+pub fn sum_triple(triple: (i32, i32, i32)) -> i32 {
+    triple.0 + triple.1 + triple.2
+}
+
+#[cs_bindgen]
+pub fn round_trip_single(value: (u64,)) -> (u64,) {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_exported_types(
+    value: (CopyStruct, SimpleCEnum, bool),
+) -> (CopyStruct, SimpleCEnum, bool) {
+    value
+}
+
+#[cs_bindgen]
+pub fn round_trip_optional_pair(value: Option<(i32, f32)>) -> Option<(i32, f32)> {
+    value
+}
+
+#[cs_bindgen]
+#[allow(clippy::type_complexity)]
+pub fn count_to_twelve() -> (u8, u16, u32, u64, i8, i16, i32, i64, f32, f64, bool, String) {
+    (1, 2, 3, 4, 5, 6, 7, 8, 9.0, 10.0, true, "12".into())
+}
+
+#[cs_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct StructWithTupleField {
+    pub position: (f32, f32),
+    pub id: u32,
+}
+
+#[cs_bindgen]
+pub fn round_trip_struct_with_tuple_field(value: StructWithTupleField) -> StructWithTupleField {
+    value
+}